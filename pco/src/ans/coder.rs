@@ -0,0 +1,117 @@
+use std::fmt::Debug;
+
+#[cfg(feature = "compress")]
+use crate::ans::Encoder;
+use crate::ans::{AnsState, Decoder, Spec, Symbol};
+use crate::bit_reader::BitReader;
+use crate::constants::{Bitlen, Weight, ANS_INTERLEAVING, FULL_BATCH_N};
+use crate::errors::PcoResult;
+
+// These traits are the boundary between the bin machinery and the entropy
+// coder used for bin symbols. The latent compressors and decompressors are
+// generic over them so that alternative coders can be experimented with
+// without touching bins; ANS is the default and the only coder the file
+// format supports.
+
+/// Pairs an encoder and decoder that agree on how bin symbols are coded.
+pub trait EntropyCoder: Clone + Debug {
+  #[cfg(feature = "compress")]
+  type Encoder: EntropyEncoder;
+  type Decoder: EntropyDecoder;
+}
+
+#[cfg(feature = "compress")]
+pub trait EntropyEncoder: Clone + Debug + Sized {
+  /// Builds the encoder from the quantized weight of each symbol, which must
+  /// sum to `1 << size_log`.
+  fn from_weights(size_log: Bitlen, weights: Vec<Weight>) -> PcoResult<Self>;
+
+  /// The state every interleaved stream starts from when encoding (in
+  /// reverse).
+  fn default_state(&self) -> AnsState;
+
+  /// Returns the new state, and how many of the lowest bits of the existing
+  /// state must be written.
+  fn encode(&self, state: AnsState, symbol: Symbol) -> (AnsState, Bitlen);
+}
+
+pub trait EntropyDecoder: Clone + Debug + Sized {
+  /// Whatever the decoder needs to resume decoding a page, initially read
+  /// from page metadata.
+  type State: Clone + Debug;
+
+  /// Builds the decoder from the same weights given to the encoder.
+  fn from_weights(size_log: Bitlen, weights: Vec<Weight>) -> PcoResult<Self>;
+
+  /// Decodes the next `batch_n` symbols, at most a full batch, calling
+  /// `on_symbol(i, symbol)` for each in order, and advances the reader and
+  /// state past them.
+  ///
+  /// Taking a callback instead of filling a buffer lets the caller fuse its
+  /// per-symbol work into the decoding loop, which is the hot path of
+  /// decompression.
+  /// Every decoded symbol must be less than the count of weights, even for
+  /// corrupt data, since callers use them as unchecked bin indices.
+  ///
+  /// # Safety
+  /// The reader must have enough padding to read a full batch.
+  unsafe fn decode_batch<F: FnMut(usize, Symbol)>(
+    &self,
+    reader: &mut BitReader,
+    state: &mut Self::State,
+    batch_n: usize,
+    on_symbol: F,
+  );
+}
+
+/// Tabled asymmetric numeral systems, the default entropy coder.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Ans;
+
+impl EntropyCoder for Ans {
+  #[cfg(feature = "compress")]
+  type Encoder = Encoder;
+  type Decoder = Decoder;
+}
+
+#[cfg(feature = "compress")]
+impl EntropyEncoder for Encoder {
+  fn from_weights(size_log: Bitlen, weights: Vec<Weight>) -> PcoResult<Self> {
+    let spec = Spec::from_weights(size_log, weights)?;
+    Ok(Self::new(&spec))
+  }
+
+  #[inline]
+  fn default_state(&self) -> AnsState {
+    self.default_state()
+  }
+
+  #[inline]
+  fn encode(&self, state: AnsState, symbol: Symbol) -> (AnsState, Bitlen) {
+    self.encode(state, symbol)
+  }
+}
+
+impl EntropyDecoder for Decoder {
+  type State = [AnsState; ANS_INTERLEAVING];
+
+  fn from_weights(size_log: Bitlen, weights: Vec<Weight>) -> PcoResult<Self> {
+    let spec = Spec::from_weights(size_log, weights)?;
+    Ok(Self::new(&spec))
+  }
+
+  #[inline(always)]
+  unsafe fn decode_batch<F: FnMut(usize, Symbol)>(
+    &self,
+    reader: &mut BitReader,
+    state: &mut Self::State,
+    batch_n: usize,
+    on_symbol: F,
+  ) {
+    if batch_n == FULL_BATCH_N {
+      self.decode_full_batch(reader, state, on_symbol)
+    } else {
+      self.decode_partial_batch(reader, state, batch_n, on_symbol)
+    }
+  }
+}
@@ -1,6 +1,8 @@
 use crate::ans::spec::Spec;
 use crate::ans::{AnsState, Symbol};
-use crate::constants::Bitlen;
+use crate::bit_reader;
+use crate::bit_reader::BitReader;
+use crate::constants::{Bitlen, ANS_INTERLEAVING, FULL_BATCH_N};
 
 #[derive(Clone, Debug)]
 #[repr(align(16))]
@@ -16,6 +18,17 @@ pub struct Decoder {
 }
 
 impl Decoder {
+  // Decoding indexes into the nodes without bounds checks, so it's important
+  // that every state index stays in [0, table_size), even for corrupt data:
+  // * Initial state indices are read with exactly size_log bits.
+  // * Each next_state_base below is x_s * 2^bits_to_read for some x_s, and the
+  //   loop stops once it reaches table_size, so it's less than 2 * table_size.
+  //   Since table_size is a power of 2 and a multiple of 2^bits_to_read,
+  //   adding any bits_to_read-bit value still gives less than
+  //   2 * table_size, i.e. an index less than table_size.
+  // * Spec validates that the weights sum to table_size, so there is exactly
+  //   one node per state.
+  // * Each node's symbol is the index of a weight, so it's a valid bin index.
   pub fn new(spec: &Spec) -> Self {
     let table_size = spec.table_size();
     let mut nodes = Vec::with_capacity(table_size);
@@ -38,4 +51,124 @@ impl Decoder {
 
     Self { nodes }
   }
+
+  // This implementation handles only a full batch, but is faster.
+  // It's always inlined so that on_symbol gets fused into the loop and the
+  // ANS states stay in registers.
+  #[inline(always)]
+  pub(crate) unsafe fn decode_full_batch<F: FnMut(usize, Symbol)>(
+    &self,
+    reader: &mut BitReader,
+    state_idxs: &mut [AnsState; ANS_INTERLEAVING],
+    mut on_symbol: F,
+  ) {
+    // At each iteration, this loads a single u64 and has all ANS decoders
+    // read a single symbol from it.
+    // Therefore it requires that ANS_INTERLEAVING * MAX_BITS_PER_ANS <= 57.
+    // Additionally, we're unpacking all ANS states using the fact that
+    // ANS_INTERLEAVING == 4.
+    let src = reader.src;
+    let mut stale_byte_idx = reader.stale_byte_idx;
+    let mut bits_past_byte = reader.bits_past_byte;
+    let [mut state_idx_0, mut state_idx_1, mut state_idx_2, mut state_idx_3] = *state_idxs;
+    let nodes = self.nodes.as_slice();
+    for base_i in (0..FULL_BATCH_N).step_by(ANS_INTERLEAVING) {
+      stale_byte_idx += bits_past_byte as usize / 8;
+      bits_past_byte %= 8;
+      let packed = bit_reader::u64_at(src, stale_byte_idx);
+      // I hate that I have to do this with a macro, but it gives a serious
+      // performance gain. If I use a [AnsState; 4] for the state_idxs instead
+      // of separate identifiers, it tries to repeatedly load and write to
+      // the array instead of keeping the states in registers.
+      macro_rules! handle_single_symbol {
+        ($j: expr, $state_idx: ident) => {
+          // in range for any input; see Decoder::new
+          debug_assert!(($state_idx as usize) < nodes.len());
+          let node = unsafe { nodes.get_unchecked($state_idx as usize) };
+          let ans_val = (packed >> bits_past_byte) as AnsState & ((1 << node.bits_to_read) - 1);
+          on_symbol(base_i + $j, node.symbol);
+          bits_past_byte += node.bits_to_read;
+          $state_idx = node.next_state_idx_base + ans_val;
+        };
+      }
+      handle_single_symbol!(0, state_idx_0);
+      handle_single_symbol!(1, state_idx_1);
+      handle_single_symbol!(2, state_idx_2);
+      handle_single_symbol!(3, state_idx_3);
+    }
+
+    reader.stale_byte_idx = stale_byte_idx;
+    reader.bits_past_byte = bits_past_byte;
+    *state_idxs = [state_idx_0, state_idx_1, state_idx_2, state_idx_3];
+  }
+
+  // This implementation handles arbitrary batch size and looks simpler, but is
+  // slower, so we only use it at the end of the page.
+  #[inline(always)]
+  pub(crate) unsafe fn decode_partial_batch<F: FnMut(usize, Symbol)>(
+    &self,
+    reader: &mut BitReader,
+    state_idxs: &mut [AnsState; ANS_INTERLEAVING],
+    batch_n: usize,
+    mut on_symbol: F,
+  ) {
+    let src = reader.src;
+    let mut stale_byte_idx = reader.stale_byte_idx;
+    let mut bits_past_byte = reader.bits_past_byte;
+    for i in 0..batch_n {
+      let j = i % ANS_INTERLEAVING;
+      stale_byte_idx += bits_past_byte as usize / 8;
+      bits_past_byte %= 8;
+      let packed = bit_reader::u64_at(src, stale_byte_idx);
+      // in range for any input; see Decoder::new
+      debug_assert!((state_idxs[j] as usize) < self.nodes.len());
+      let node = unsafe { self.nodes.get_unchecked(state_idxs[j] as usize) };
+      let ans_val = (packed >> bits_past_byte) as AnsState & ((1 << node.bits_to_read) - 1);
+      on_symbol(i, node.symbol);
+      bits_past_byte += node.bits_to_read;
+      state_idxs[j] = node.next_state_idx_base + ans_val;
+    }
+
+    reader.stale_byte_idx = stale_byte_idx;
+    reader.bits_past_byte = bits_past_byte;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::constants::Weight;
+  use crate::errors::PcoResult;
+
+  #[test]
+  fn test_next_states_in_range() -> PcoResult<()> {
+    let weight_sets: Vec<Vec<Weight>> = vec![
+      vec![1],
+      vec![1, 1],
+      vec![3, 1],
+      vec![1, 1, 1, 5],
+      vec![1; 64],
+      vec![1000, 1, 1, 22],
+      (1..=31).chain([528]).collect(),
+    ];
+    for weights in weight_sets {
+      let size_log = weights.iter().sum::<Weight>().ilog2();
+      let decoder = Decoder::new(&Spec::from_weights(
+        size_log,
+        weights.clone(),
+      )?);
+      let table_size = decoder.nodes.len() as AnsState;
+      assert_eq!(table_size, 1 << size_log);
+      for node in &decoder.nodes {
+        let max_next_state_idx = node.next_state_idx_base + ((1 << node.bits_to_read) - 1);
+        assert!(
+          max_next_state_idx < table_size,
+          "weights={:?} node={:?}",
+          weights,
+          node
+        );
+      }
+    }
+    Ok(())
+  }
 }
@@ -1,9 +1,16 @@
+#[cfg(feature = "compress")]
+pub use coder::EntropyEncoder;
+pub use coder::{Ans, EntropyCoder, EntropyDecoder};
 pub use decoding::Decoder;
+#[cfg(feature = "compress")]
 pub use encoding::quantize_weights;
+#[cfg(feature = "compress")]
 pub use encoding::Encoder;
 pub use spec::Spec;
 
+mod coder;
 mod decoding;
+#[cfg(feature = "compress")]
 mod encoding;
 mod spec;
 
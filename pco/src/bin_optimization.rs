@@ -152,11 +152,69 @@ fn choose_optimized_partitioning<L: Latent>(
   rewind_best_partitioning(&best_js, bins.len())
 }
 
+// Splits groups of bins until the partitioning has at least min_bins groups
+// (or can't be split further), each time choosing the split that increases
+// estimated cost the least.
+fn split_to_min_bins<L: Latent>(
+  mut partitioning: Partitioning,
+  bins: &[HistogramBin<L>],
+  ans_size_log: Bitlen,
+  min_bins: usize,
+) -> Partitioning {
+  let target_n_bins = min_bins.min(bins.len());
+  if partitioning.len() >= target_n_bins {
+    return partitioning;
+  }
+
+  let mut c_counts = Vec::with_capacity(bins.len() + 1);
+  let mut c = 0;
+  c_counts.push(0);
+  for bin in bins {
+    c += bin.count as Weight;
+    c_counts.push(c);
+  }
+  let total_count_log2 = (c as f32).log2();
+  let bin_meta_cost = Bin::<L>::exact_bit_size(ans_size_log) as f32;
+  let group_cost = |j: usize, i: usize| {
+    bin_cost(
+      bin_meta_cost,
+      bins[j].lower,
+      bins[i].upper,
+      c_counts[i + 1] - c_counts[j],
+      total_count_log2,
+    )
+  };
+
+  while partitioning.len() < target_n_bins {
+    let mut best_split = None;
+    let mut best_cost_delta = f32::MAX;
+    for (group_idx, &(j, i)) in partitioning.iter().enumerate() {
+      let unsplit_cost = group_cost(j, i);
+      for k in j..i {
+        let cost_delta = group_cost(j, k) + group_cost(k + 1, i) - unsplit_cost;
+        if cost_delta < best_cost_delta {
+          best_cost_delta = cost_delta;
+          best_split = Some((group_idx, k));
+        }
+      }
+    }
+
+    let (group_idx, k) = best_split.unwrap();
+    let (j, i) = partitioning[group_idx];
+    partitioning[group_idx] = (j, k);
+    partitioning.insert(group_idx + 1, (k + 1, i));
+  }
+
+  partitioning
+}
+
 pub fn optimize_bins<L: Latent>(
   bins: &[HistogramBin<L>],
   ans_size_log: Bitlen,
+  min_bins: usize,
 ) -> Vec<BinCompressionInfo<L>> {
   let partitioning = choose_optimized_partitioning(bins, ans_size_log);
+  let partitioning = split_to_min_bins(partitioning, bins, ans_size_log, min_bins);
   let mut res = Vec::with_capacity(partitioning.len());
   for (symbol, &(j, i)) in partitioning.iter().enumerate() {
     let count: usize = bins.iter().take(i + 1).skip(j).map(|bin| bin.count).sum();
@@ -195,7 +253,7 @@ mod tests {
       make_bin(100, 65, 74), // same density as next bin (but different from previous ones)
       make_bin(50, 75, 79),
     ];
-    let optimized = optimize_bins(&infos, 10);
+    let optimized = optimize_bins(&infos, 10, 0);
     assert_eq!(
       optimized,
       vec![
@@ -230,7 +288,7 @@ mod tests {
     // bits), but it's disadvantageous to combine them because the 2nd bin has
     // so much higher density
     let infos = vec![make_bin(1000, 0, 150), make_bin(1000, 200, 200)];
-    let optimized = optimize_bins(&infos, 10);
+    let optimized = optimize_bins(&infos, 10, 0);
     assert_eq!(
       optimized,
       vec![
@@ -251,4 +309,40 @@ mod tests {
       ]
     )
   }
+
+  #[test]
+  fn test_bin_optimization_min_bins() {
+    // these would all be combined into a single bin without a floor
+    let infos = vec![
+      make_bin(100, 0, 15),
+      make_bin(100, 16, 31),
+      make_bin(100, 32, 47),
+      make_bin(100, 48, 63),
+    ];
+    assert_eq!(optimize_bins(&infos, 10, 0).len(), 1);
+
+    let optimized = optimize_bins(&infos, 10, 2);
+    assert_eq!(
+      optimized,
+      vec![
+        BinCompressionInfo {
+          weight: 200,
+          lower: 0,
+          upper: 31,
+          offset_bits: 5,
+          symbol: 0,
+        },
+        BinCompressionInfo {
+          weight: 200,
+          lower: 32,
+          upper: 63,
+          offset_bits: 5,
+          symbol: 1,
+        },
+      ]
+    );
+
+    // the floor can't exceed the number of unoptimized bins
+    assert_eq!(optimize_bins(&infos, 10, 7).len(), 4);
+  }
 }
@@ -203,6 +203,7 @@ pub struct BitReaderBuilder<R: BetterBufRead> {
   reached_eof: bool,
   bytes_into_eof_buffer: usize,
   bits_past_byte: Bitlen,
+  n_bytes_consumed: usize,
 }
 
 impl<R: BetterBufRead> BitReaderBuilder<R> {
@@ -214,6 +215,7 @@ impl<R: BetterBufRead> BitReaderBuilder<R> {
       reached_eof: false,
       bytes_into_eof_buffer: 0,
       bits_past_byte,
+      n_bytes_consumed: 0,
     }
   }
 
@@ -255,9 +257,15 @@ impl<R: BetterBufRead> BitReaderBuilder<R> {
     self.inner
   }
 
+  // the number of whole bytes consumed from the inner source so far
+  pub fn n_bytes_consumed(&self) -> usize {
+    self.n_bytes_consumed
+  }
+
   fn update(&mut self, final_bit_idx: usize) {
     let bytes_consumed = final_bit_idx / 8;
     self.inner.consume(bytes_consumed);
+    self.n_bytes_consumed += bytes_consumed;
     if self.reached_eof {
       self.bytes_into_eof_buffer += bytes_consumed;
     }
@@ -268,9 +276,18 @@ impl<R: BetterBufRead> BitReaderBuilder<R> {
     &mut self,
     f: F,
   ) -> PcoResult<Y> {
+    let start_byte_idx = self.n_bytes_consumed;
     let mut reader = self.build()?;
-    let res = f(&mut reader)?;
-    let final_bit_idx = reader.bit_idx_safe()?;
+    let with_byte_idx = |err: PcoError, reader: &BitReader| {
+      err.with_context(format!(
+        "at byte {}",
+        start_byte_idx + reader.bit_idx() / 8
+      ))
+    };
+    let res = f(&mut reader).map_err(|err| with_byte_idx(err, &reader))?;
+    let final_bit_idx = reader
+      .bit_idx_safe()
+      .map_err(|err| with_byte_idx(err, &reader))?;
     self.update(final_bit_idx);
     Ok(res)
   }
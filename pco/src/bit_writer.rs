@@ -45,6 +45,7 @@ pub struct BitWriter<W: Write> {
   pub buf: Vec<u8>,
   pub stale_byte_idx: usize,
   pub bits_past_byte: Bitlen,
+  n_bytes_flushed: usize,
   dst: W,
 }
 
@@ -54,6 +55,7 @@ impl<W: Write> BitWriter<W> {
       buf: vec![0; size],
       stale_byte_idx: 0,
       bits_past_byte: 0,
+      n_bytes_flushed: 0,
       dst,
     }
   }
@@ -143,6 +145,7 @@ impl<W: Write> BitWriter<W> {
     let n_bytes = self.stale_byte_idx;
 
     self.dst.write_all(&self.buf[..n_bytes])?;
+    self.n_bytes_flushed += n_bytes;
     self.buf[..n_bytes].fill(0);
     if n_bytes > 0 && self.bits_past_byte > 0 {
       // We need to keep track of the partially initialized byte.
@@ -154,6 +157,11 @@ impl<W: Write> BitWriter<W> {
     Ok(())
   }
 
+  // the number of bytes written to the destination so far
+  pub fn n_bytes_flushed(&self) -> usize {
+    self.n_bytes_flushed
+  }
+
   pub fn into_inner(self) -> W {
     self.dst
   }
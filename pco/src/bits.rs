@@ -17,6 +17,7 @@ pub fn lowest_bits<U: ReadWriteUint>(x: U, n: Bitlen) -> U {
   }
 }
 
+#[cfg(feature = "compress")]
 pub fn bits_to_encode_offset<L: Latent>(max_offset: L) -> Bitlen {
   L::BITS - max_offset.leading_zeros()
 }
@@ -1,5 +1,11 @@
-use crate::constants::{Bitlen, DEFAULT_MAX_PAGE_N};
+use crate::constants::{
+  Bitlen, DEFAULT_FLOAT_QUANT_REQUIRED_BITS_SAVED_PER_NUM,
+  DEFAULT_LOOKBACK_REQUIRED_BYTE_SAVINGS_PER_N, DEFAULT_MAX_PAGE_N,
+};
+use crate::data_types::Number;
 use crate::errors::{PcoError, PcoResult};
+use crate::metadata::format_version::FormatVersion;
+use crate::metadata::DynLatents;
 use crate::DEFAULT_COMPRESSION_LEVEL;
 
 /// Specifies how Pco should choose a [`mode`][crate::metadata::Mode] to compress this
@@ -24,9 +30,26 @@ pub enum ModeSpec {
   ///
   /// Only applies to floating-point types.
   TryFloatMult(f64),
-  /// Tries using `FloatQuant` mode with `k` bits of quantization.
+  /// Tries using `FloatMultLossy` mode with a given `base`: like `FloatMult`,
+  /// but discarding each number's adjustment.
   ///
+  /// This is lossy: each number is reconstructed as its nearest multiple of
+  /// `base`, computed as `mult * base` in floating point, losing any precision
+  /// finer than `base`.
+  /// In exchange, there is no adjustment latent variable to store.
+  /// The chunk meta records the `FloatMultLossy` mode, so decompressors can
+  /// tell the data was lossy; this requires format version 4.
+  /// If Pco falls back to `Classic` mode, compression is lossless.
+  /// Use [`ChunkCompressor::is_lossy`][crate::wrapped::ChunkCompressor::is_lossy]
+  /// or [`ChunkDecision::lossy`][crate::standalone::ChunkDecision::lossy] to
+  /// find out which happened.
   /// Only applies to floating-point types.
+  TryFloatMultLossy(f64),
+  /// Tries using `FloatQuant` mode with `k` bits of quantization.
+  ///
+  /// This skips detection, so it gives the same mode for every chunk.
+  /// Only applies to floating-point types, and `k` may not exceed the
+  /// float's precision bits (e.g. 23 for `f32`).
   TryFloatQuant(Bitlen),
   /// Tries using `IntMult` mode with a given `base`.
   ///
@@ -62,6 +85,8 @@ pub enum DeltaSpec {
   /// For instance, 1st order is just regular delta encoding, 2nd is
   /// deltas-of-deltas, etc.
   /// It is legal to use 0th order, but it is identical to `None`.
+  /// For chunks of n numbers with n no greater than the order, the order is
+  /// reduced to n - 1.
   TryConsecutive(usize),
   /// Tries delta encoding according to an extra latent variable of "lookback".
   ///
@@ -104,9 +129,108 @@ pub struct ChunkConfig {
   /// [`ChunkDecompressor`][crate::wrapped::ChunkDecompressor] and read the
   /// delta encoding it chose.
   pub delta_spec: DeltaSpec,
+  /// Whether the chosen delta encoding should also apply to secondary latent
+  /// variables, such as the adjustments of int mult or float mult mode
+  /// (default: false).
+  ///
+  /// The format stores delta encoding per latent variable, but the secondary
+  /// latents either share the primary latents' delta encoding or use none.
+  /// This has no effect if the mode has no secondary latents or no delta
+  /// encoding is chosen.
+  /// Requires format version 3 or newer.
+  pub secondary_uses_delta: bool,
+  /// How many bytes per number lookback delta encoding must save over the
+  /// best alternative for `DeltaSpec::Auto` to choose it (default: 0.25).
+  ///
+  /// Lookback encoding is slow to decompress, so Pco only picks it when the
+  /// estimated savings are substantial.
+  /// Lower this to choose lookback more eagerly on repetitive data, or set it
+  /// to infinity to never choose it automatically.
+  /// Negative values are allowed, but NaN is an InvalidArgument error.
+  /// This has no effect with other delta specs.
+  pub lookback_required_byte_savings_per_n: f32,
+  /// How many bits per number float quant mode must be estimated to save for
+  /// `ModeSpec::Auto` to choose it (default: 1.5).
+  ///
+  /// Lower this to detect float quantization more eagerly, e.g. if your
+  /// data narrowly misses detection, or set it to infinity to never choose
+  /// it automatically.
+  /// To always use float quant mode with a known number of bits instead, use
+  /// `ModeSpec::TryFloatQuant`.
+  /// NaN is an InvalidArgument error.
+  /// This has no effect with other mode specs.
+  pub float_quant_required_bits_saved_per_num: f64,
+  /// The seed for randomly sampling numbers when choosing a mode with
+  /// `ModeSpec::Auto` (default: 0).
+  ///
+  /// Mode selection only looks at a sample of the chunk's numbers.
+  /// Given the same numbers, config, and seed, the sample and therefore the
+  /// chosen mode are always the same, including across releases that don't
+  /// change the compression algorithm.
+  /// Changing the seed can be used to check how sensitive mode selection is
+  /// to the sample.
+  pub sampling_seed: u64,
+  /// Whether subnormal (a.k.a. denormal) floats should be included in the
+  /// sample used to choose a float mode with `ModeSpec::Auto` (default:
+  /// false).
+  ///
+  /// By default, subnormals are excluded from the sample, since they're
+  /// uncommon and don't have the same precision as other floats.
+  /// But if your data has many subnormals, excluding them can lead Pco to
+  /// choose a mode that only suits the rest of the data.
+  /// When included, the sample is scaled by a power of 2 so that subnormals
+  /// become normal while detecting a float mult base.
+  /// This has no effect on integer data or with other mode specs.
+  pub sample_subnormals: bool,
+  /// The minimum number of bins to keep for the primary latent variable
+  /// (default: `None`, meaning no floor).
+  ///
+  /// Pco normally merges bins whenever it estimates that doing so improves
+  /// compression, which can occasionally merge distinct clusters in
+  /// multimodal data.
+  /// Setting this preserves more of the distribution's structure at the cost
+  /// of more metadata.
+  /// The floor is limited by how many bins the sample and ANS table size
+  /// allow, and may not exceed [`MAX_BINS`][crate::MAX_BINS].
+  pub min_bins: Option<usize>,
+  /// Whether to spend extra compression time optimizing bins a second time
+  /// (default: false).
+  ///
+  /// Bins are optimized for an estimated ANS table size, which quantizing
+  /// their weights may change.
+  /// When it does, this optimizes bins again for the actual table size and
+  /// keeps whichever result is estimated to be smaller.
+  /// This occasionally improves compression ratio slightly, so it may be
+  /// worthwhile when compression time doesn't matter, e.g. for archival.
+  pub reoptimize_bins: bool,
+  /// Lower bounds for the primary latent variable's bins, to use instead of
+  /// computing a histogram of the chunk (default: `None`).
+  ///
+  /// If you already know the distribution of your numbers, e.g. quantiles
+  /// from a prior pass, this saves partially sorting each chunk.
+  /// Each bin starts at one bound and extends up to the next; bins are still
+  /// merged afterward whenever that improves compression.
+  /// The bounds must be strictly increasing, start at or below the smallest
+  /// number, have the same latent type as the numbers, and number no more
+  /// than [`MAX_BINS`][crate::MAX_BINS].
+  /// Since other modes and delta encodings change the latents, this requires
+  /// `ModeSpec::Classic` and `DeltaSpec::None`.
+  /// Use [`with_bin_boundaries`][ChunkConfig::with_bin_boundaries] to set
+  /// these from numbers.
+  pub bin_boundaries: Option<DynLatents>,
   /// Specifies how the chunk should be split into pages (default: equal pages
   /// up to 2^18 numbers each).
   pub paging_spec: PagingSpec,
+  /// Specifies the newest format version the chunk may use (default: `None`,
+  /// meaning the current format version).
+  ///
+  /// This lets you write data that older versions of pco can decompress.
+  /// Pco will avoid any modes or delta encodings the version doesn't support,
+  /// and will return an InvalidArgument error if such a mode or delta encoding
+  /// was explicitly requested.
+  /// When compressing with a lower-level API, the chunk is also constrained by
+  /// the format version of the file compressor.
+  pub format_version: Option<u8>,
 }
 
 impl Default for ChunkConfig {
@@ -115,12 +239,33 @@ impl Default for ChunkConfig {
       compression_level: DEFAULT_COMPRESSION_LEVEL,
       mode_spec: ModeSpec::default(),
       delta_spec: DeltaSpec::default(),
+      secondary_uses_delta: false,
+      lookback_required_byte_savings_per_n: DEFAULT_LOOKBACK_REQUIRED_BYTE_SAVINGS_PER_N,
+      float_quant_required_bits_saved_per_num: DEFAULT_FLOAT_QUANT_REQUIRED_BITS_SAVED_PER_NUM,
+      sampling_seed: 0,
+      sample_subnormals: false,
+      min_bins: None,
+      reoptimize_bins: false,
+      bin_boundaries: None,
       paging_spec: PagingSpec::EqualPagesUpTo(DEFAULT_MAX_PAGE_N),
+      format_version: None,
     }
   }
 }
 
 impl ChunkConfig {
+  /// Returns an otherwise default config that uses classic mode and no delta
+  /// encoding.
+  ///
+  /// This skips all sampling and searching for a mode or delta encoding,
+  /// giving the lowest compression latency at the cost of compression ratio
+  /// on data with special structure.
+  pub fn classic() -> Self {
+    Self::default()
+      .with_mode_spec(ModeSpec::Classic)
+      .with_delta_spec(DeltaSpec::None)
+  }
+
   /// Sets [`compression_level`][ChunkConfig::compression_level].
   pub fn with_compression_level(mut self, level: usize) -> Self {
     self.compression_level = level;
@@ -139,14 +284,84 @@ impl ChunkConfig {
     self
   }
 
+  /// Sets [`secondary_uses_delta`][ChunkConfig::secondary_uses_delta].
+  pub fn with_secondary_uses_delta(mut self, secondary_uses_delta: bool) -> Self {
+    self.secondary_uses_delta = secondary_uses_delta;
+    self
+  }
+
+  /// Sets
+  /// [`lookback_required_byte_savings_per_n`][ChunkConfig::lookback_required_byte_savings_per_n].
+  pub fn with_lookback_required_byte_savings_per_n(mut self, savings: f32) -> Self {
+    self.lookback_required_byte_savings_per_n = savings;
+    self
+  }
+
+  /// Sets
+  /// [`float_quant_required_bits_saved_per_num`][ChunkConfig::float_quant_required_bits_saved_per_num].
+  pub fn with_float_quant_required_bits_saved_per_num(mut self, bits_saved: f64) -> Self {
+    self.float_quant_required_bits_saved_per_num = bits_saved;
+    self
+  }
+
+  /// Sets [`sampling_seed`][ChunkConfig::sampling_seed].
+  pub fn with_sampling_seed(mut self, seed: u64) -> Self {
+    self.sampling_seed = seed;
+    self
+  }
+
+  /// Sets [`sample_subnormals`][ChunkConfig::sample_subnormals].
+  pub fn with_sample_subnormals(mut self, sample_subnormals: bool) -> Self {
+    self.sample_subnormals = sample_subnormals;
+    self
+  }
+
+  /// Sets [`min_bins`][ChunkConfig::min_bins].
+  pub fn with_min_bins(mut self, min_bins: Option<usize>) -> Self {
+    self.min_bins = min_bins;
+    self
+  }
+
+  /// Sets [`reoptimize_bins`][ChunkConfig::reoptimize_bins].
+  pub fn with_reoptimize_bins(mut self, reoptimize_bins: bool) -> Self {
+    self.reoptimize_bins = reoptimize_bins;
+    self
+  }
+
+  /// Sets [`bin_boundaries`][ChunkConfig::bin_boundaries] to the latents of
+  /// the given numbers.
+  pub fn with_bin_boundaries<T: Number>(mut self, boundaries: Option<&[T]>) -> Self {
+    self.bin_boundaries = boundaries.map(|nums| {
+      let latents = nums.iter().map(|&num| num.to_latent_ordered()).collect();
+      DynLatents::new(latents).unwrap()
+    });
+    self
+  }
+
   /// Sets [`paging_spec`][ChunkConfig::paging_spec].
   pub fn with_paging_spec(mut self, paging_spec: PagingSpec) -> Self {
     self.paging_spec = paging_spec;
     self
   }
+
+  /// Sets [`format_version`][field@ChunkConfig::format_version].
+  pub fn with_format_version(mut self, format_version: Option<u8>) -> Self {
+    self.format_version = format_version;
+    self
+  }
+
+  pub(crate) fn format_version(&self) -> PcoResult<FormatVersion> {
+    match self.format_version {
+      Some(version) => FormatVersion::new(version),
+      None => Ok(FormatVersion::default()),
+    }
+  }
 }
 
 /// `PagingSpec` specifies how a chunk is split into pages.
+///
+/// The split depends only on the spec and the count of numbers, so it is the
+/// same no matter how chunks or pages get scheduled for compression.
 #[derive(Clone, Debug)]
 #[non_exhaustive]
 pub enum PagingSpec {
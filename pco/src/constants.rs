@@ -9,7 +9,7 @@ pub(crate) type Weight = u32;
 pub(crate) type DeltaLookback = u32;
 
 // compatibility
-pub const CURRENT_FORMAT_VERSION: u8 = 3;
+pub const CURRENT_FORMAT_VERSION: u8 = 4;
 
 // bit lengths
 pub const BITS_TO_ENCODE_ANS_SIZE_LOG: Bitlen = 4;
@@ -38,20 +38,37 @@ pub const PAGE_PADDING: usize =
 // cutoffs and legal parameter values
 pub const MAX_ANS_BITS: Bitlen = 14;
 pub const MAX_ANS_BYTES: usize = MAX_ANS_BITS.div_ceil(8) as usize;
+#[cfg(feature = "compress")]
 pub const LIMITED_UNOPTIMIZED_BINS_LOG: Bitlen = 6;
 pub const MAX_COMPRESSION_LEVEL: usize = 12;
+// The compressor keeps ANS tables smaller than MAX_ANS_BITS allows, so they
+// fit into L1 (or at least L2) cache and decompress quickly.
+#[cfg(feature = "compress")]
+pub const MAX_COMPRESSOR_ANS_BITS: Bitlen = 12;
+// Every bin needs at least one ANS state.
+#[cfg(feature = "compress")]
+pub const MAX_BINS: usize = 1 << MAX_COMPRESSOR_ANS_BITS;
+#[cfg(feature = "compress")]
 pub const MAX_DELTA_ENCODING_ORDER: usize = 7;
+#[cfg(feature = "compress")]
 pub const MAX_ENTRIES: usize = 1 << 24;
 pub const MAX_SUPPORTED_PRECISION: Bitlen = 128;
 pub const MAX_SUPPORTED_PRECISION_BYTES: usize = (MAX_SUPPORTED_PRECISION / 8) as usize;
+#[cfg(feature = "compress")]
 pub const MULT_REQUIRED_BITS_SAVED_PER_NUM: f64 = 0.5;
-pub const QUANT_REQUIRED_BITS_SAVED_PER_NUM: f64 = 1.5;
+#[cfg(feature = "compress")]
 pub const CLASSIC_MEMORIZABLE_BINS_LOG: Bitlen = 8;
 
 // defaults
 pub const DEFAULT_COMPRESSION_LEVEL: usize = 8;
 // if you modify default page size, update docs for PagingSpec
 pub const DEFAULT_MAX_PAGE_N: usize = 1 << 18;
+// if you modify this, update docs for ChunkConfig
+#[cfg(feature = "compress")]
+pub const DEFAULT_LOOKBACK_REQUIRED_BYTE_SAVINGS_PER_N: f32 = 0.25;
+// if you modify this, update docs for ChunkConfig
+#[cfg(feature = "compress")]
+pub const DEFAULT_FLOAT_QUANT_REQUIRED_BITS_SAVED_PER_NUM: f64 = 1.5;
 
 // important parts of the format specification
 pub const ANS_INTERLEAVING: usize = 4;
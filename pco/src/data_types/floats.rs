@@ -1,19 +1,30 @@
 use std::mem;
+use std::num::FpCategory;
 
 use half::f16;
 
+#[cfg(feature = "compress")]
 use super::ModeAndLatents;
+#[cfg(feature = "compress")]
 use crate::chunk_config::ModeSpec;
+#[cfg(feature = "compress")]
 use crate::compression_intermediates::Bid;
 use crate::constants::Bitlen;
-use crate::data_types::{split_latents_classic, Float, Latent, Number};
+#[cfg(feature = "compress")]
+use crate::data_types::split_latents_classic;
+use crate::data_types::{Float, Latent, Number};
 use crate::describers::LatentDescriber;
+#[cfg(feature = "compress")]
 use crate::errors::{PcoError, PcoResult};
+#[cfg(feature = "compress")]
 use crate::float_mult_utils::FloatMultConfig;
 use crate::metadata::per_latent_var::PerLatentVar;
 use crate::metadata::{ChunkMeta, DynLatents, Mode};
-use crate::{describers, float_mult_utils, float_quant_utils, sampling, ChunkConfig};
+use crate::{describers, float_mult_utils, float_quant_utils};
+#[cfg(feature = "compress")]
+use crate::{sampling, ChunkConfig};
 
+#[cfg(feature = "compress")]
 fn filter_sample<F: Float>(num: &F) -> Option<F> {
   // We can compress infinities, nans, and baby floats, but we can't learn
   // the mode from them.
@@ -26,6 +37,16 @@ fn filter_sample<F: Float>(num: &F) -> Option<F> {
   None
 }
 
+#[cfg(feature = "compress")]
+fn filter_sample_w_subnormals<F: Float>(num: &F) -> Option<F> {
+  if num.is_subnormal() {
+    Some(num.abs())
+  } else {
+    filter_sample(num)
+  }
+}
+
+#[cfg(feature = "compress")]
 fn choose_mode_and_split_latents<F: Float>(
   nums: &[F],
   chunk_config: &ChunkConfig,
@@ -40,9 +61,19 @@ fn choose_mode_and_split_latents<F: Float>(
         split_fn: Box::new(|nums| split_latents_classic(nums)),
       });
 
-      if let Some(sample) = sampling::choose_sample(nums, filter_sample) {
+      let filter: fn(&F) -> Option<F> = if chunk_config.sample_subnormals {
+        filter_sample_w_subnormals
+      } else {
+        filter_sample
+      };
+      if let Some(sample) = sampling::choose_sample(nums, filter, chunk_config.sampling_seed) {
         bids.extend(float_mult_utils::compute_bid(&sample));
-        bids.extend(float_quant_utils::compute_bid(&sample));
+        if chunk_config.format_version()?.supports_float_quant() {
+          bids.extend(float_quant_utils::compute_bid(
+            &sample,
+            chunk_config.float_quant_required_bits_saved_per_num,
+          ));
+        }
       }
 
       let winning_bid = choose_winning_bid(bids);
@@ -60,6 +91,23 @@ fn choose_mode_and_split_latents<F: Float>(
       let latents = float_mult_utils::split_latents(nums, float_mult_config);
       Ok((mode, latents))
     }
+    ModeSpec::TryFloatMultLossy(base_f64) => {
+      let base = F::from_f64(base_f64);
+      let mode = Mode::float_mult_lossy(base);
+      let float_mult_config = FloatMultConfig {
+        base,
+        inv_base: base.inv(),
+      };
+      let latents = float_mult_utils::split_latents_lossy(nums, float_mult_config);
+      Ok((mode, latents))
+    }
+    ModeSpec::TryFloatQuant(k) if k > F::PRECISION_BITS => {
+      Err(PcoError::invalid_argument(format!(
+        "float quant bits may not exceed the float's precision of {} (was {})",
+        F::PRECISION_BITS,
+        k,
+      )))
+    }
     ModeSpec::TryFloatQuant(k) => Ok((
       Mode::FloatQuant(k),
       float_quant_utils::split_latents(nums, k),
@@ -71,6 +119,7 @@ fn choose_mode_and_split_latents<F: Float>(
 }
 
 // one day we might reuse this for int modes
+#[cfg(feature = "compress")]
 fn choose_winning_bid<T: Number>(bids: Vec<Bid<T>>) -> Bid<T> {
   bids
     .into_iter()
@@ -121,6 +170,11 @@ macro_rules! impl_float {
         self.is_normal()
       }
 
+      #[inline]
+      fn is_subnormal(self) -> bool {
+        self.is_subnormal()
+      }
+
       #[inline]
       fn is_sign_positive_(&self) -> bool {
         self.is_sign_positive()
@@ -237,6 +291,11 @@ impl Float for f16 {
     self.is_normal()
   }
 
+  #[inline]
+  fn is_subnormal(self) -> bool {
+    self.classify() == FpCategory::Subnormal
+  }
+
   #[inline]
   fn is_sign_positive_(&self) -> bool {
     self.is_sign_positive()
@@ -328,7 +387,7 @@ macro_rules! impl_float_number {
       fn mode_is_valid(mode: Mode) -> bool {
         match mode {
           Mode::Classic => true,
-          Mode::FloatMult(dyn_latent) => {
+          Mode::FloatMult(dyn_latent) | Mode::FloatMultLossy(dyn_latent) => {
             let base_latent = *dyn_latent.downcast_ref::<Self::L>().unwrap();
             Self::from_latent_ordered(base_latent).is_normal()
           }
@@ -336,6 +395,7 @@ macro_rules! impl_float_number {
           _ => false,
         }
       }
+      #[cfg(feature = "compress")]
       fn choose_mode_and_split_latents(
         nums: &[Self],
         config: &ChunkConfig,
@@ -372,6 +432,10 @@ macro_rules! impl_float_number {
             float_mult_utils::join_latents(base, primary, secondary)
           }
           Mode::FloatQuant(k) => float_quant_utils::join_latents::<Self>(k, primary, secondary),
+          Mode::FloatMultLossy(dyn_latent) => {
+            let base = Self::from_latent_ordered(*dyn_latent.downcast_ref::<Self::L>().unwrap());
+            float_mult_utils::join_latents_lossy(base, primary)
+          }
           _ => unreachable!("impossible mode for floats"),
         }
       }
@@ -418,6 +482,62 @@ mod tests {
     assert_eq!(mode, Mode::FloatQuant(k));
   }
 
+  #[test]
+  fn test_float_quant_config() -> PcoResult<()> {
+    let lowest_num_bits = 1.0_f32.to_bits();
+    let nums = (0..1000)
+      .map(|i| f32::from_bits(lowest_num_bits + (i << 10)))
+      .collect::<Vec<_>>();
+    let mode_with_threshold = |threshold: f64| -> PcoResult<Mode> {
+      let config = ChunkConfig::default().with_float_quant_required_bits_saved_per_num(threshold);
+      Ok(choose_mode_and_split_latents(&nums, &config)?.0)
+    };
+    assert_eq!(
+      mode_with_threshold(1.5)?,
+      Mode::FloatQuant(10)
+    );
+    assert!(!matches!(
+      mode_with_threshold(f64::INFINITY)?,
+      Mode::FloatQuant(_)
+    ));
+
+    // forcing float quant skips detection entirely
+    let config = ChunkConfig::default().with_mode_spec(ModeSpec::TryFloatQuant(3));
+    assert_eq!(
+      choose_mode_and_split_latents(&nums, &config)?.0,
+      Mode::FloatQuant(3)
+    );
+    let config = ChunkConfig::default().with_mode_spec(ModeSpec::TryFloatQuant(23));
+    assert!(choose_mode_and_split_latents(&nums, &config).is_ok());
+    let config = ChunkConfig::default().with_mode_spec(ModeSpec::TryFloatQuant(24));
+    assert!(choose_mode_and_split_latents(&nums, &config).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_sample_subnormals() -> PcoResult<()> {
+    // subnormals whose low bits are all 0, plus a few normal numbers
+    let nums = (0..1000)
+      .map(|i| {
+        if i % 10 == 0 {
+          i as f32
+        } else {
+          f32::from_bits((i * 997 % 4096) << 8)
+        }
+      })
+      .collect::<Vec<_>>();
+    let mode_when_sampling = |sample_subnormals: bool| -> PcoResult<Mode> {
+      let config = ChunkConfig::default().with_sample_subnormals(sample_subnormals);
+      Ok(choose_mode_and_split_latents(&nums, &config)?.0)
+    };
+    assert_eq!(mode_when_sampling(false)?, Mode::Classic);
+    assert_eq!(
+      mode_when_sampling(true)?,
+      Mode::FloatQuant(8)
+    );
+    Ok(())
+  }
+
   #[test]
   fn test_float_ordering() {
     assert!(f32::NEG_INFINITY.to_latent_ordered() < (-0.0_f32).to_latent_ordered());
@@ -10,10 +10,12 @@ pub use split_latents::SplitLatents;
 
 use crate::constants::Bitlen;
 use crate::describers::LatentDescriber;
+#[cfg(feature = "compress")]
 use crate::errors::PcoResult;
 use crate::metadata::dyn_latents::DynLatents;
 use crate::metadata::per_latent_var::PerLatentVar;
 use crate::metadata::{ChunkMeta, Mode};
+#[cfg(feature = "compress")]
 use crate::ChunkConfig;
 
 mod dynamic;
@@ -22,10 +24,13 @@ mod signeds;
 mod split_latents;
 mod unsigneds;
 
+#[cfg(feature = "compress")]
 pub(crate) type ModeAndLatents = (Mode, SplitLatents);
 
 /// This is used internally for compressing and decompressing with
 /// float modes.
+// Most of these are only needed to choose float modes during compression.
+#[cfg_attr(not(feature = "compress"), allow(dead_code))]
 pub(crate) trait Float:
   Add<Output = Self>
   + AddAssign
@@ -58,6 +63,7 @@ pub(crate) trait Float:
   fn from_f64(x: f64) -> Self;
   fn to_f64(self) -> f64;
   fn is_normal(self) -> bool;
+  fn is_subnormal(self) -> bool;
   fn is_sign_positive_(&self) -> bool;
   /// Returns the float's exponent. For instance, for f32 this should be
   /// between -127 and +126.
@@ -86,6 +92,13 @@ pub(crate) trait Float:
 /// Under the hood, when numbers are encoded or decoded, they go through their
 /// corresponding `Latent` representation.
 /// Metadata stores numbers as their latent representations.
+///
+/// Each latent type (`u16`, `u32`, and `u64`) is also a [`Number`] whose
+/// latent conversion is the identity.
+/// So latents computed ahead of time can be compressed directly as numbers of
+/// that type, and decompressing them as that type returns the latents.
+/// Pass [`ModeSpec::Classic`][crate::ModeSpec::Classic] to also skip mode
+/// detection.
 pub trait Latent:
   Add<Output = Self>
   + AddAssign
@@ -173,6 +186,7 @@ pub trait Number: Copy + Debug + Display + Default + PartialEq + Send + Sync + '
   /// * latents: a primary and optionally secondary latent variable, each of
   ///   which contains a latent per num in `nums`. Primary must be of the same
   ///   latent type as T.
+  #[cfg(feature = "compress")]
   fn choose_mode_and_split_latents(
     nums: &[Self],
     config: &ChunkConfig,
@@ -186,6 +200,7 @@ pub trait Number: Copy + Debug + Display + Default + PartialEq + Send + Sync + '
   fn transmute_to_latent(self) -> Self::L;
 }
 
+#[cfg(feature = "compress")]
 pub(crate) fn split_latents_classic<T: Number>(nums: &[T]) -> SplitLatents {
   let primary = DynLatents::new(nums.iter().map(|&x| x.to_latent_ordered()).collect()).unwrap();
   SplitLatents {
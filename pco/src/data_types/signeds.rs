@@ -1,11 +1,16 @@
 use std::mem;
 
-use crate::data_types::{unsigneds, ModeAndLatents, Number};
+use crate::data_types::Number;
+#[cfg(feature = "compress")]
+use crate::data_types::{unsigneds, ModeAndLatents};
 use crate::describers::LatentDescriber;
+#[cfg(feature = "compress")]
 use crate::errors::PcoResult;
 use crate::metadata::per_latent_var::PerLatentVar;
 use crate::metadata::{ChunkMeta, DynLatents, Mode};
-use crate::{describers, int_mult_utils, ChunkConfig};
+#[cfg(feature = "compress")]
+use crate::ChunkConfig;
+use crate::{describers, int_mult_utils};
 
 macro_rules! impl_signed {
   ($t: ty, $latent: ty, $header_byte: expr) => {
@@ -27,6 +32,7 @@ macro_rules! impl_signed {
           _ => false,
         }
       }
+      #[cfg(feature = "compress")]
       fn choose_mode_and_split_latents(
         nums: &[Self],
         config: &ChunkConfig,
@@ -1,19 +1,33 @@
+#[cfg(feature = "compress")]
 use super::ModeAndLatents;
 use crate::constants::Bitlen;
-use crate::data_types::{split_latents_classic, Latent, Number};
+#[cfg(feature = "compress")]
+use crate::data_types::split_latents_classic;
+use crate::data_types::{Latent, Number};
 use crate::describers::LatentDescriber;
+#[cfg(feature = "compress")]
 use crate::errors::{PcoError, PcoResult};
 use crate::metadata::per_latent_var::PerLatentVar;
-use crate::metadata::{ChunkMeta, DynLatent, DynLatents, Mode};
-use crate::{describers, int_mult_utils, ChunkConfig, ModeSpec};
-
+#[cfg(feature = "compress")]
+use crate::metadata::DynLatent;
+use crate::metadata::{ChunkMeta, DynLatents, Mode};
+use crate::{describers, int_mult_utils};
+#[cfg(feature = "compress")]
+use crate::{ChunkConfig, ModeSpec};
+
+#[cfg(feature = "compress")]
 pub fn choose_mode_and_split_latents<T: Number>(
   nums: &[T],
   config: &ChunkConfig,
 ) -> PcoResult<ModeAndLatents> {
   match config.mode_spec {
     ModeSpec::Auto => {
-      if let Some(base) = int_mult_utils::choose_base(nums) {
+      let maybe_base = if config.format_version()?.used_old_gcds() {
+        None
+      } else {
+        int_mult_utils::choose_base(nums, config.sampling_seed)
+      };
+      if let Some(base) = maybe_base {
         let mode = Mode::IntMult(DynLatent::new(base).unwrap());
         let latents = int_mult_utils::split_latents(nums, base);
         Ok((mode, latents))
@@ -23,9 +37,9 @@ pub fn choose_mode_and_split_latents<T: Number>(
     }
 
     ModeSpec::Classic => Ok((Mode::Classic, split_latents_classic(nums))),
-    ModeSpec::TryFloatMult(_) | ModeSpec::TryFloatQuant(_) => Err(PcoError::invalid_argument(
-      "unable to use float mode for ints",
-    )),
+    ModeSpec::TryFloatMult(_) | ModeSpec::TryFloatMultLossy(_) | ModeSpec::TryFloatQuant(_) => Err(
+      PcoError::invalid_argument("unable to use float mode for ints"),
+    ),
     ModeSpec::TryIntMult(base_u64) => {
       let base = T::L::from_u64(base_u64);
       let mode = Mode::IntMult(DynLatent::new(base).unwrap());
@@ -72,6 +86,7 @@ macro_rules! impl_latent {
   };
 }
 
+impl_latent!(u8);
 impl_latent!(u16);
 impl_latent!(u32);
 impl_latent!(u64);
@@ -96,6 +111,7 @@ macro_rules! impl_unsigned_number {
           _ => false,
         }
       }
+      #[cfg(feature = "compress")]
       fn choose_mode_and_split_latents(
         nums: &[Self],
         config: &ChunkConfig,
@@ -136,3 +152,4 @@ macro_rules! impl_unsigned_number {
 impl_unsigned_number!(u32, 1);
 impl_unsigned_number!(u64, 2);
 impl_unsigned_number!(u16, 7);
+impl_unsigned_number!(u8, 10);
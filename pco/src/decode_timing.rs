@@ -0,0 +1,68 @@
+// Accumulates the time spent in each phase of latent decoding, so benchmarks
+// can tell where decompression time goes.
+// This is only enabled with the decode_timing feature, since reading the
+// clock on every batch slows down decompression considerably.
+
+#[cfg(feature = "decode_timing")]
+mod tracking {
+  use std::sync::atomic::{AtomicU64, Ordering};
+  use std::time::{Duration, Instant};
+
+  static ANS_NANOS: AtomicU64 = AtomicU64::new(0);
+  static OFFSETS_NANOS: AtomicU64 = AtomicU64::new(0);
+
+  /// Time spent decoding latents since the last call to
+  /// [`take_decode_timing`], summed over all threads.
+  #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+  pub struct DecodeTiming {
+    /// Time spent decoding ANS symbols into bins.
+    pub ans: Duration,
+    /// Time spent reading offset bits and adding them to bin lower bounds.
+    pub offsets: Duration,
+  }
+
+  /// Returns the decode time accumulated so far and resets it to zero.
+  ///
+  /// Only available with the decode_timing feature, which should only be
+  /// used for benchmarking.
+  pub fn take_decode_timing() -> DecodeTiming {
+    DecodeTiming {
+      ans: Duration::from_nanos(ANS_NANOS.swap(0, Ordering::Relaxed)),
+      offsets: Duration::from_nanos(OFFSETS_NANOS.swap(0, Ordering::Relaxed)),
+    }
+  }
+
+  fn time<R, F: FnOnce() -> R>(nanos: &AtomicU64, f: F) -> R {
+    let t = Instant::now();
+    let res = f();
+    nanos.fetch_add(
+      t.elapsed().as_nanos() as u64,
+      Ordering::Relaxed,
+    );
+    res
+  }
+
+  pub fn time_ans<R, F: FnOnce() -> R>(f: F) -> R {
+    time(&ANS_NANOS, f)
+  }
+
+  pub fn time_offsets<R, F: FnOnce() -> R>(f: F) -> R {
+    time(&OFFSETS_NANOS, f)
+  }
+}
+
+#[cfg(feature = "decode_timing")]
+pub use tracking::*;
+
+// Without the feature, we just run the closure.
+#[cfg(not(feature = "decode_timing"))]
+#[inline(always)]
+pub fn time_ans<R, F: FnOnce() -> R>(f: F) -> R {
+  f()
+}
+
+#[cfg(not(feature = "decode_timing"))]
+#[inline(always)]
+pub fn time_offsets<R, F: FnOnce() -> R>(f: F) -> R {
+  f()
+}
@@ -0,0 +1,59 @@
+use crate::errors::{PcoError, PcoResult};
+
+/// All configurations available for a decompressor.
+///
+/// These are useful guards when decompressing untrusted data, since a
+/// malicious file may otherwise claim to contain a huge count of numbers and
+/// cause large allocations.
+/// Exceeding any of these limits results in an InvalidArgument error, raised
+/// before any memory is allocated for the offending numbers.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct DecompressorConfig {
+  /// The maximum count of numbers allowed in a single chunk (default: `None`,
+  /// meaning no limit).
+  ///
+  /// For wrapped decompressors, this limits the count of numbers in each
+  /// page, since that is the only count pco is given.
+  pub max_n: Option<u64>,
+  /// The maximum count of numbers allowed in a whole standalone file
+  /// (default: `None`, meaning no limit).
+  ///
+  /// This applies to the file's size hint and to the total count decompressed
+  /// by the simple standalone APIs.
+  /// It has no effect on wrapped decompressors, since the wrapping format
+  /// is responsible for counting numbers across chunks.
+  pub max_file_n: Option<u64>,
+}
+
+impl DecompressorConfig {
+  /// Sets [`max_n`][DecompressorConfig::max_n].
+  pub fn with_max_n(mut self, max_n: Option<u64>) -> Self {
+    self.max_n = max_n;
+    self
+  }
+
+  /// Sets [`max_file_n`][DecompressorConfig::max_file_n].
+  pub fn with_max_file_n(mut self, max_file_n: Option<u64>) -> Self {
+    self.max_file_n = max_file_n;
+    self
+  }
+
+  pub(crate) fn check_n(&self, n: usize) -> PcoResult<()> {
+    check_limit(n, self.max_n, "max_n")
+  }
+
+  pub(crate) fn check_file_n(&self, file_n: usize) -> PcoResult<()> {
+    check_limit(file_n, self.max_file_n, "max_file_n")
+  }
+}
+
+fn check_limit(n: usize, limit: Option<u64>, name: &str) -> PcoResult<()> {
+  match limit {
+    Some(limit) if n as u64 > limit => Err(PcoError::invalid_argument(format!(
+      "count of numbers ({}) exceeds {} ({})",
+      n, name, limit,
+    ))),
+    _ => Ok(()),
+  }
+}
@@ -1,13 +1,21 @@
-use crate::constants::{Bitlen, DeltaLookback};
+#[cfg(feature = "compress")]
+use crate::constants::Bitlen;
+use crate::constants::DeltaLookback;
 use crate::data_types::Latent;
+#[cfg(feature = "compress")]
 use crate::macros::match_latent_enum;
 use crate::metadata::delta_encoding::DeltaLookbackConfig;
 use crate::metadata::dyn_latents::DynLatents;
+#[cfg(feature = "compress")]
 use crate::metadata::DeltaEncoding;
 use crate::FULL_BATCH_N;
+use std::cmp;
+#[cfg(feature = "compress")]
 use std::mem::MaybeUninit;
+#[cfg(feature = "compress")]
 use std::ops::Range;
-use std::{array, cmp, mem};
+#[cfg(feature = "compress")]
+use std::{array, mem};
 
 pub type DeltaState = DynLatents;
 
@@ -23,6 +31,7 @@ pub fn toggle_center_in_place<L: Latent>(latents: &mut [L]) {
   }
 }
 
+#[cfg(feature = "compress")]
 fn first_order_encode_consecutive_in_place<L: Latent>(latents: &mut [L]) {
   if latents.is_empty() {
     return;
@@ -38,6 +47,7 @@ fn first_order_encode_consecutive_in_place<L: Latent>(latents: &mut [L]) {
 // latents in this case) at the front of the latents.
 // Using the front instead of the back is preferable because it makes the lookback
 // encode function simpler and faster.
+#[cfg(feature = "compress")]
 #[inline(never)]
 fn encode_consecutive_in_place<L: Latent>(order: usize, mut latents: &mut [L]) -> Vec<L> {
   // TODO this function could be made faster by doing all steps on mini batches
@@ -76,15 +86,20 @@ pub(crate) fn decode_consecutive_in_place<L: Latent>(delta_moments: &mut [L], la
 // * brute force: just try the most recent few latents
 // * repeating: try the most recent lookbacks we actually used
 // * hash: look up similar values by hash
+#[cfg(feature = "compress")]
 const PROPOSED_LOOKBACKS: usize = 16;
+#[cfg(feature = "compress")]
 const BRUTE_LOOKBACKS: usize = 6;
+#[cfg(feature = "compress")]
 const REPEATING_LOOKBACKS: usize = 4;
 // To help locate similar latents for lookback encoding, we hash each latent at
 // different "coarsenesses" and write them into a vector. e.g. a coarseness
 // of 8 means that (l >> 8) gets hashed, so we can lookup recent values by
 // quotient by 256.
+#[cfg(feature = "compress")]
 const COARSENESSES: [Bitlen; 2] = [0, 8];
 
+#[cfg(feature = "compress")]
 fn lookback_hash_lookup(
   l: u64,
   i: usize,
@@ -125,6 +140,7 @@ fn lookback_hash_lookup(
   }
 }
 
+#[cfg(feature = "compress")]
 #[inline(never)]
 fn find_best_lookback<L: Latent>(
   l: L,
@@ -154,6 +170,7 @@ fn find_best_lookback<L: Latent>(
   best_lookback
 }
 
+#[cfg(feature = "compress")]
 #[inline(never)]
 fn choose_lookbacks<L: Latent>(config: DeltaLookbackConfig, latents: &[L]) -> Vec<DeltaLookback> {
   let state_n = config.state_n();
@@ -214,6 +231,7 @@ fn choose_lookbacks<L: Latent>(config: DeltaLookbackConfig, latents: &[L]) -> Ve
 // case) at the front of the latents.
 // Using the front instead of the back is preferable because it means we don't
 // need an extra copy of the latents in this case.
+#[cfg(feature = "compress")]
 #[inline(never)]
 fn encode_with_lookbacks_in_place<L: Latent>(
   config: DeltaLookbackConfig,
@@ -297,6 +315,7 @@ pub fn decode_with_lookbacks_in_place<L: Latent>(
   has_oob_lookbacks
 }
 
+#[cfg(feature = "compress")]
 pub fn compute_delta_latent_var(
   delta_encoding: DeltaEncoding,
   primary_latents: &mut DynLatents,
@@ -317,6 +336,7 @@ pub fn compute_delta_latent_var(
   }
 }
 
+#[cfg(feature = "compress")]
 pub fn encode_in_place(
   delta_encoding: DeltaEncoding,
   delta_latents: Option<&DynLatents>,
@@ -112,21 +112,7 @@ pub(crate) fn match_float_modes<F: Float>(
 ) -> Option<PerLatentVar<LatentDescriber>> {
   match meta.mode {
     Mode::FloatMult(dyn_latent) => {
-      let base_latent = *dyn_latent.downcast_ref::<F::L>().unwrap();
-      let base_string = F::from_latent_ordered(base_latent).to_string();
-      let primary: LatentDescriber = if matches!(meta.delta_encoding, DeltaEncoding::None) {
-        Box::new(FloatMultDescriber {
-          base_string,
-          phantom: PhantomData::<F>,
-        })
-      } else {
-        Box::new(IntDescriber {
-          description: format!("multiplier delta [x{}]", base_string),
-          units: "x".to_string(),
-          center: F::L::MID,
-          is_signed: true,
-        })
-      };
+      let primary = float_mult_primary_describer::<F>(meta, dyn_latent);
 
       let secondary: LatentDescriber = if meta
         .delta_encoding
@@ -187,10 +173,36 @@ pub(crate) fn match_float_modes<F: Float>(
         secondary: Some(secondary),
       })
     }
+    Mode::FloatMultLossy(dyn_latent) => Some(PerLatentVar {
+      delta: delta_latent_describer(meta.delta_encoding),
+      primary: float_mult_primary_describer::<F>(meta, dyn_latent),
+      secondary: None,
+    }),
     _ => None,
   }
 }
 
+fn float_mult_primary_describer<F: Float>(
+  meta: &ChunkMeta,
+  base_latent: DynLatent,
+) -> LatentDescriber {
+  let base_latent = *base_latent.downcast_ref::<F::L>().unwrap();
+  let base_string = F::from_latent_ordered(base_latent).to_string();
+  if matches!(meta.delta_encoding, DeltaEncoding::None) {
+    Box::new(FloatMultDescriber {
+      base_string,
+      phantom: PhantomData::<F>,
+    })
+  } else {
+    Box::new(IntDescriber {
+      description: format!("multiplier delta [x{}]", base_string),
+      units: "x".to_string(),
+      center: F::L::MID,
+      is_signed: true,
+    })
+  }
+}
+
 #[derive(Default)]
 struct ClassicDescriber<T: Number>(PhantomData<T>);
 
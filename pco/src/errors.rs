@@ -55,6 +55,13 @@ impl PcoError {
   pub(crate) fn invalid_argument<S: AsRef<str>>(message: S) -> Self {
     Self::new(ErrorKind::InvalidArgument, message)
   }
+
+  // Appends where the error occurred, e.g. a byte offset or the part of the
+  // file being read. Outer callers add broader context after inner ones.
+  pub(crate) fn with_context<S: AsRef<str>>(mut self, context: S) -> Self {
+    self.message = format!("{}; {}", self.message, context.as_ref());
+    self
+  }
 }
 
 impl Display for PcoError {
@@ -76,6 +83,18 @@ impl From<io::Error> for PcoError {
   }
 }
 
+impl From<PcoError> for io::Error {
+  fn from(err: PcoError) -> Self {
+    let kind = match err.kind {
+      ErrorKind::Io(kind) => kind,
+      ErrorKind::InsufficientData => io::ErrorKind::UnexpectedEof,
+      ErrorKind::InvalidArgument => io::ErrorKind::InvalidInput,
+      ErrorKind::Compatibility | ErrorKind::Corruption => io::ErrorKind::InvalidData,
+    };
+    io::Error::new(kind, err)
+  }
+}
+
 impl Error for PcoError {}
 
 pub type PcoResult<T> = Result<T, PcoError>;
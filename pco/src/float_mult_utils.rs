@@ -1,12 +1,21 @@
+#[cfg(feature = "compress")]
 use std::cmp::{max, min};
+#[cfg(feature = "compress")]
 use std::mem;
 
+#[cfg(feature = "compress")]
 use crate::compression_intermediates::Bid;
+#[cfg(feature = "compress")]
 use crate::constants::{Bitlen, MULT_REQUIRED_BITS_SAVED_PER_NUM};
+#[cfg(feature = "compress")]
 use crate::data_types::SplitLatents;
 use crate::data_types::{Float, Latent};
-use crate::metadata::{DynLatents, Mode};
+use crate::metadata::DynLatents;
+#[cfg(feature = "compress")]
+use crate::metadata::Mode;
+#[cfg(feature = "compress")]
 use crate::sampling::PrimaryLatentAndSavings;
+#[cfg(feature = "compress")]
 use crate::{int_mult_utils, sampling};
 
 #[inline(never)]
@@ -25,6 +34,14 @@ pub(crate) fn join_latents<F: Float>(
   }
 }
 
+#[inline(never)]
+pub(crate) fn join_latents_lossy<F: Float>(base: F, primary: &mut [F::L]) {
+  for mult_and_dst in primary.iter_mut() {
+    *mult_and_dst = (F::int_float_from_latent(*mult_and_dst) * base).to_latent_ordered();
+  }
+}
+
+#[cfg(feature = "compress")]
 pub(crate) fn split_latents<F: Float>(page_nums: &[F], config: FloatMultConfig<F>) -> SplitLatents {
   let FloatMultConfig { base, inv_base } = config;
   let n = page_nums.len();
@@ -55,6 +72,25 @@ pub(crate) fn split_latents<F: Float>(page_nums: &[F], config: FloatMultConfig<F
   }
 }
 
+// Like split_latents, but without adjustments, so that each number gets
+// reconstructed as exactly mult * base.
+#[cfg(feature = "compress")]
+pub(crate) fn split_latents_lossy<F: Float>(
+  page_nums: &[F],
+  config: FloatMultConfig<F>,
+) -> SplitLatents {
+  let FloatMultConfig { inv_base, .. } = config;
+  let primary = page_nums
+    .iter()
+    .map(|&num| F::int_float_to_latent((num * inv_base).round()))
+    .collect::<Vec<_>>();
+
+  SplitLatents {
+    primary: DynLatents::new(primary).unwrap(),
+    secondary: None,
+  }
+}
+
 // The rest of this file concerns automatically detecting the float `base`
 // such that `x = mult * base + adj * ULP` usefully splits a delta `x` into
 // latent variables `mult` and `adj` (if such a `base` exists).
@@ -71,30 +107,41 @@ pub(crate) fn split_latents<F: Float>(page_nums: &[F], config: FloatMultConfig<F
 // such a large range and must be determined so precisely.
 // So instead we use an approximate Euclidean algorithm on pairs of floats.
 
+#[cfg(feature = "compress")]
 const REQUIRED_PRECISION_BITS: Bitlen = 6;
+#[cfg(feature = "compress")]
 const SNAP_THRESHOLD_ABSOLUTE: f64 = 0.02;
+#[cfg(feature = "compress")]
 const SNAP_THRESHOLD_DECIMAL_RELATIVE: f64 = 0.01;
+#[cfg(feature = "compress")]
 const INTERESTING_TRAILING_ZEROS: u32 = 5;
+#[cfg(feature = "compress")]
 const REQUIRED_TRAILING_ZEROS_FREQUENCY: f64 = 0.5;
+#[cfg(feature = "compress")]
 const REQUIRED_GCD_PAIR_FREQUENCY: f64 = 0.001;
 
+#[cfg(feature = "compress")]
 fn insignificant_float_to<F: Float>(x: F) -> F {
   let spare_precision_bits = F::PRECISION_BITS.saturating_sub(REQUIRED_PRECISION_BITS) as i32;
   x * F::exp2(-spare_precision_bits)
 }
 
+#[cfg(feature = "compress")]
 fn is_approx_zero<F: Float>(small: F, big: F) -> bool {
   small <= insignificant_float_to(big)
 }
 
+#[cfg(feature = "compress")]
 fn is_small_remainder<F: Float>(remainder: F, original: F) -> bool {
   remainder <= original * F::exp2(-16)
 }
 
+#[cfg(feature = "compress")]
 fn is_imprecise<F: Float>(value: F, err: F) -> bool {
   value <= err * F::exp2(REQUIRED_PRECISION_BITS as i32)
 }
 
+#[cfg(feature = "compress")]
 fn approx_pair_gcd<F: Float>(greater: F, lesser: F) -> Option<F> {
   if is_approx_zero(lesser, greater) || lesser == greater {
     return None;
@@ -137,6 +184,7 @@ fn approx_pair_gcd<F: Float>(greater: F, lesser: F) -> Option<F> {
   }
 }
 
+#[cfg(feature = "compress")]
 #[inline(never)]
 fn choose_config_by_trailing_zeros<F: Float>(sample: &[F]) -> Option<FloatMultConfig<F>> {
   let precision_bits = F::PRECISION_BITS;
@@ -189,6 +237,7 @@ fn choose_config_by_trailing_zeros<F: Float>(sample: &[F]) -> Option<FloatMultCo
   }
 }
 
+#[cfg(feature = "compress")]
 #[inline(never)]
 fn approx_sample_gcd_euclidean<F: Float>(sample: &[F]) -> Option<F> {
   let mut gcds = Vec::new();
@@ -224,6 +273,7 @@ fn approx_sample_gcd_euclidean<F: Float>(sample: &[F]) -> Option<F> {
   None
 }
 
+#[cfg(feature = "compress")]
 fn choose_config_by_euclidean<F: Float>(sample: &[F]) -> Option<FloatMultConfig<F>> {
   let base = approx_sample_gcd_euclidean(sample)?;
   let base = center_sample_base(base, sample);
@@ -231,6 +281,7 @@ fn choose_config_by_euclidean<F: Float>(sample: &[F]) -> Option<FloatMultConfig<
   Some(config)
 }
 
+#[cfg(feature = "compress")]
 #[inline(never)]
 fn center_sample_base<F: Float>(base: F, sample: &[F]) -> F {
   // Go back through the sample, holding all mults fixed, and adjust the gcd to
@@ -254,6 +305,7 @@ fn center_sample_base<F: Float>(base: F, sample: &[F]) -> F {
   base - tweak_sum / tweak_weight
 }
 
+#[cfg(feature = "compress")]
 fn snap_to_int_reciprocal<F: Float>(base: F) -> FloatMultConfig<F> {
   let inv_base = base.inv();
   let round_inv_base = inv_base.round();
@@ -270,6 +322,7 @@ fn snap_to_int_reciprocal<F: Float>(base: F) -> FloatMultConfig<F> {
   }
 }
 
+#[cfg(feature = "compress")]
 fn bits_saved_per_num_over_classic<F: Float>(
   config: FloatMultConfig<F>,
   sample: &[F],
@@ -309,12 +362,14 @@ fn bits_saved_per_num_over_classic<F: Float>(
   }
 }
 
+#[cfg(feature = "compress")]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) struct FloatMultConfig<F: Float> {
   pub base: F,
   pub inv_base: F,
 }
 
+#[cfg(feature = "compress")]
 impl<F: Float> FloatMultConfig<F> {
   fn from_base(base: F) -> Self {
     Self {
@@ -331,10 +386,41 @@ impl<F: Float> FloatMultConfig<F> {
   }
 }
 
+// Subnormals lack an implicit leading bit, which our exponent and trailing
+// zero arithmetic assumes, so we scale the sample by a power of 2 that makes
+// every subnormal normal. This is exact unless a large number would overflow,
+// in which case the sample is too spread out for a useful base anyway.
+#[cfg(feature = "compress")]
+fn choose_config_by_scaled_trailing_zeros<F: Float>(sample: &[F]) -> Option<FloatMultConfig<F>> {
+  let scale = F::exp2(F::PRECISION_BITS as i32);
+  let scaled_sample = sample
+    .iter()
+    .map(|&x| {
+      let scaled = x * scale;
+      (scaled <= F::MAX_FOR_SAMPLING).then_some(scaled)
+    })
+    .collect::<Option<Vec<_>>>()?;
+  let scaled_config = choose_config_by_trailing_zeros(&scaled_sample)?;
+  let config = FloatMultConfig::from_base(scaled_config.base * scale.inv());
+  // bases this small have no finite inverse
+  if config.inv_base.is_normal() {
+    Some(config)
+  } else {
+    None
+  }
+}
+
+#[cfg(feature = "compress")]
 fn choose_config<F: Float>(sample: &[F]) -> Option<FloatMultConfig<F>> {
-  choose_config_by_trailing_zeros(sample).or_else(|| choose_config_by_euclidean(sample))
+  let config = if sample.iter().any(|x| x.is_subnormal()) {
+    choose_config_by_scaled_trailing_zeros(sample)
+  } else {
+    choose_config_by_trailing_zeros(sample)
+  };
+  config.or_else(|| choose_config_by_euclidean(sample))
 }
 
+#[cfg(feature = "compress")]
 pub(crate) fn compute_bid<F: Float>(sample: &[F]) -> Option<Bid<F>> {
   choose_config(sample).and_then(|config| {
     let bits_saved_per_num = bits_saved_per_num_over_classic(config, sample)?;
@@ -644,6 +730,19 @@ mod test {
         inv_base: 10.0,
       })
     );
+    let subnormal_base = f32::MIN_POSITIVE * 0.75;
+    let subnormal_mults = (0..100)
+      .map(|i| (i % 7) as f32 * subnormal_base)
+      .collect::<Vec<_>>();
+    assert_eq!(
+      choose_config(&subnormal_mults).map(|config| config.base),
+      Some(subnormal_base)
+    );
+    // too small to have a finite inverse
+    let tiny_mults = (0..100)
+      .map(|i| (i % 7) as f32 * f32::from_bits(3))
+      .collect::<Vec<_>>();
+    assert_eq!(choose_config(&tiny_mults), None);
     // just check this last one terminates
     let mut big_nums = vec![f32::MAX; 10];
     big_nums.resize(20, f32::MAX * 0.6);
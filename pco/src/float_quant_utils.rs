@@ -1,10 +1,17 @@
+#[cfg(feature = "compress")]
 use crate::compression_intermediates::Bid;
-use crate::constants::{Bitlen, QUANT_REQUIRED_BITS_SAVED_PER_NUM};
+use crate::constants::Bitlen;
+#[cfg(feature = "compress")]
 use crate::data_types::SplitLatents;
 use crate::data_types::{Float, Latent};
+#[cfg(feature = "compress")]
 use crate::int_mult_utils;
-use crate::metadata::{DynLatents, Mode};
+use crate::metadata::DynLatents;
+#[cfg(feature = "compress")]
+use crate::metadata::Mode;
+#[cfg(feature = "compress")]
 use crate::sampling::{self, PrimaryLatentAndSavings};
+#[cfg(feature = "compress")]
 use std::cmp;
 
 #[inline(never)]
@@ -33,6 +40,7 @@ pub(crate) fn join_latents<F: Float>(
   }
 }
 
+#[cfg(feature = "compress")]
 pub(crate) fn split_latents<F: Float>(page_nums: &[F], k: Bitlen) -> SplitLatents {
   let n = page_nums.len();
   let uninit_vec = || unsafe {
@@ -67,7 +75,11 @@ pub(crate) fn split_latents<F: Float>(page_nums: &[F], k: Bitlen) -> SplitLatent
   }
 }
 
-pub(crate) fn compute_bid<F: Float>(sample: &[F]) -> Option<Bid<F>> {
+#[cfg(feature = "compress")]
+pub(crate) fn compute_bid<F: Float>(
+  sample: &[F],
+  required_bits_saved_per_num: f64,
+) -> Option<Bid<F>> {
   let (k, bits_saved_per_infrequent_primary) = estimate_best_k_and_bits_saved(sample);
   let bits_saved_per_num = sampling::est_bits_saved_per_num(sample, |x| {
     let primary = x.to_latent_bits() >> k;
@@ -76,7 +88,7 @@ pub(crate) fn compute_bid<F: Float>(sample: &[F]) -> Option<Bid<F>> {
       bits_saved: bits_saved_per_infrequent_primary,
     }
   });
-  if bits_saved_per_num > QUANT_REQUIRED_BITS_SAVED_PER_NUM {
+  if bits_saved_per_num > required_bits_saved_per_num {
     Some(Bid {
       mode: Mode::FloatQuant(k),
       bits_saved_per_num,
@@ -87,6 +99,7 @@ pub(crate) fn compute_bid<F: Float>(sample: &[F]) -> Option<Bid<F>> {
   }
 }
 
+#[cfg(feature = "compress")]
 fn estimate_best_k_and_bits_saved_from_hist(
   cumulative_hist: &[u32],
   sample_len: usize,
@@ -125,6 +138,7 @@ fn estimate_best_k_and_bits_saved_from_hist(
   (best_k, best_bits_saved)
 }
 
+#[cfg(feature = "compress")]
 pub(crate) fn estimate_best_k_and_bits_saved<F: Float>(sample: &[F]) -> (Bitlen, f64) {
   let mut hist = vec![0; (F::PRECISION_BITS + 1) as usize];
   for x in sample {
@@ -147,6 +161,7 @@ pub(crate) fn estimate_best_k_and_bits_saved<F: Float>(sample: &[F]) -> (Bitlen,
 
 #[cfg(test)]
 mod test {
+  use crate::constants::DEFAULT_FLOAT_QUANT_REQUIRED_BITS_SAVED_PER_NUM;
   use crate::data_types::Number;
 
   use super::*;
@@ -255,7 +270,11 @@ mod test {
   fn test_compute_bid() {
     // the larger numbers in this sample have 23 - 6 = 17 bits of quantization
     let sample = (0..100).map(|x| x as f32).collect::<Vec<_>>();
-    let bid = compute_bid(&sample).unwrap();
+    let bid = compute_bid(
+      &sample,
+      DEFAULT_FLOAT_QUANT_REQUIRED_BITS_SAVED_PER_NUM,
+    )
+    .unwrap();
     assert!(matches!(bid.mode, Mode::FloatQuant(17)));
     assert_eq!(bid.bits_saved_per_num, 17.0);
 
@@ -263,14 +282,21 @@ mod test {
     let mut sample = (0..100).map(|x| x as f32).collect::<Vec<_>>();
     sample[0] += 0.1;
     sample[37] -= 0.1;
-    let bid = compute_bid(&sample).unwrap();
+    let bid = compute_bid(
+      &sample,
+      DEFAULT_FLOAT_QUANT_REQUIRED_BITS_SAVED_PER_NUM,
+    )
+    .unwrap();
     assert!(matches!(bid.mode, Mode::FloatQuant(17)));
     assert!(bid.bits_saved_per_num < 17.0);
     assert!(bid.bits_saved_per_num > 15.0);
 
     // the primary latent in this dataset has too few values and would be easily memorizable
     let sample = [0.0_f32, 1.0].repeat(50);
-    let bid = compute_bid(&sample);
+    let bid = compute_bid(
+      &sample,
+      DEFAULT_FLOAT_QUANT_REQUIRED_BITS_SAVED_PER_NUM,
+    );
     assert!(bid.is_none());
   }
 }
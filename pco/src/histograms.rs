@@ -2,6 +2,7 @@ use std::cmp::{max, min};
 
 use crate::constants::Bitlen;
 use crate::data_types::Latent;
+use crate::errors::{PcoError, PcoResult};
 use crate::sort_utils;
 
 #[derive(Clone, Copy, Debug)]
@@ -251,8 +252,8 @@ impl<L: Latent> HistogramBuilder<L> {
       bad_pivot_limit -= 1;
 
       if bad_pivot_limit == 0 {
-        sort_utils::heapsort(lhs);
-        sort_utils::heapsort(rhs);
+        sort_utils::sort(lhs);
+        sort_utils::sort(rhs);
         self.apply_sorted(latents);
         return;
       }
@@ -297,6 +298,39 @@ pub fn histogram<L: Latent>(latents: &mut [L], n_bins_log: Bitlen) -> Vec<Histog
   state.dst
 }
 
+// Like `histogram`, but with bins given by their lower bounds instead of
+// chosen from the data, so no sorting is needed.
+// The lower bounds must be strictly increasing.
+// Empty bins are dropped, and the rest are tightened to the latents they
+// contain.
+pub fn histogram_from_lowers<L: Latent>(
+  latents: &[L],
+  lowers: &[L],
+) -> PcoResult<Vec<HistogramBin<L>>> {
+  let mut bins = lowers
+    .iter()
+    .map(|_| HistogramBin {
+      count: 0,
+      lower: L::MAX,
+      upper: L::ZERO,
+    })
+    .collect::<Vec<_>>();
+  for &l in latents {
+    let bin_idx = lowers.partition_point(|&lower| lower <= l);
+    if bin_idx == 0 {
+      return Err(PcoError::invalid_argument(
+        "bin boundaries must start at or below the smallest number",
+      ));
+    }
+    let bin = &mut bins[bin_idx - 1];
+    bin.count += 1;
+    bin.lower = min(bin.lower, l);
+    bin.upper = max(bin.upper, l);
+  }
+  bins.retain(|bin| bin.count > 0);
+  Ok(bins)
+}
+
 #[cfg(test)]
 mod tests {
   use rand::seq::SliceRandom;
@@ -496,4 +530,25 @@ mod tests {
       );
     }
   }
+
+  #[test]
+  fn test_histogram_from_lowers() -> PcoResult<()> {
+    let latents = [9_u32, 0, 5, 3, 12, 4, 9];
+    assert_eq!(
+      histogram_from_lowers(&latents, &[0, 4, 6, 10])?,
+      vec![
+        make_bin(2, 0, 3),
+        make_bin(2, 4, 5),
+        make_bin(2, 9, 9),
+        make_bin(1, 12, 12),
+      ]
+    );
+    // empty bins are dropped
+    assert_eq!(
+      histogram_from_lowers(&latents, &[0, 1, 2, 20])?,
+      vec![make_bin(1, 0, 0), make_bin(6, 3, 12)]
+    );
+    assert!(histogram_from_lowers(&latents, &[1, 4]).is_err());
+    Ok(())
+  }
 }
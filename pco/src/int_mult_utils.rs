@@ -1,18 +1,28 @@
+#[cfg(feature = "compress")]
 use std::cmp::min;
+#[cfg(feature = "compress")]
 use std::collections::HashMap;
+#[cfg(feature = "compress")]
 use std::f64::consts::PI;
+#[cfg(feature = "compress")]
 use std::mem;
 
+#[cfg(feature = "compress")]
 use crate::constants::MULT_REQUIRED_BITS_SAVED_PER_NUM;
-use crate::data_types::SplitLatents;
-use crate::data_types::{Latent, Number};
+use crate::data_types::Latent;
+#[cfg(feature = "compress")]
+use crate::data_types::{Number, SplitLatents};
 use crate::metadata::DynLatents;
+#[cfg(feature = "compress")]
 use crate::sampling::{self, PrimaryLatentAndSavings};
 
 // riemann zeta function
+#[cfg(feature = "compress")]
 const ZETA_OF_2: f64 = PI * PI / 6.0;
+#[cfg(feature = "compress")]
 const LCB_RATIO: f64 = 1.0;
 
+#[cfg(feature = "compress")]
 #[inline(never)]
 pub fn split_latents<T: Number>(nums: &[T], base: T::L) -> SplitLatents {
   let n = nums.len();
@@ -43,6 +53,7 @@ pub(crate) fn join_latents<L: Latent>(base: L, primary: &mut [L], secondary: Opt
   }
 }
 
+#[cfg(feature = "compress")]
 fn calc_gcd<L: Latent>(mut x: L, mut y: L) -> L {
   if x == L::ZERO {
     return y;
@@ -58,6 +69,7 @@ fn calc_gcd<L: Latent>(mut x: L, mut y: L) -> L {
   }
 }
 
+#[cfg(feature = "compress")]
 fn solve_root_by_false_position<F: Fn(f64) -> f64>(f: F, mut lb: f64, mut ub: f64) -> Option<f64> {
   const X_TOLERANCE: f64 = 1E-4;
   let mut flb = f(lb);
@@ -85,6 +97,7 @@ fn solve_root_by_false_position<F: Fn(f64) -> f64>(f: F, mut lb: f64, mut ub: f6
   Some((lb + ub) / 2.0)
 }
 
+#[cfg(feature = "compress")]
 fn calc_triple_gcd<L: Latent>(triple: &[L]) -> L {
   let mut a = triple[0];
   let mut b = triple[1];
@@ -103,6 +116,7 @@ fn calc_triple_gcd<L: Latent>(triple: &[L]) -> L {
   calc_gcd(b - a, c - a)
 }
 
+#[cfg(feature = "compress")]
 fn single_category_entropy(p: f64) -> f64 {
   if p == 0.0 || p == 1.0 {
     0.0
@@ -111,11 +125,13 @@ fn single_category_entropy(p: f64) -> f64 {
   }
 }
 
+#[cfg(feature = "compress")]
 pub(crate) fn worse_case_categorical_entropy(concentrated_p: f64, n_categories_m1: f64) -> f64 {
   single_category_entropy(concentrated_p)
     + n_categories_m1 * single_category_entropy((1.0 - concentrated_p) / n_categories_m1)
 }
 
+#[cfg(feature = "compress")]
 fn filter_score_triple_gcd(gcd: f64, triples_w_gcd: usize, total_triples: usize) -> Option<f64> {
   let triples_w_gcd = triples_w_gcd as f64;
   let total_triples = total_triples as f64;
@@ -187,6 +203,7 @@ fn filter_score_triple_gcd(gcd: f64, triples_w_gcd: usize, total_triples: usize)
   Some(worst_case_bits_saved)
 }
 
+#[cfg(feature = "compress")]
 fn most_prominent_gcd<L: Latent>(triple_gcds: &[L], total_triples: usize) -> Option<(L, f64)> {
   let mut counts = HashMap::new();
   for &gcd in triple_gcds {
@@ -205,6 +222,7 @@ fn most_prominent_gcd<L: Latent>(triple_gcds: &[L], total_triples: usize) -> Opt
   Some(gcd_and_score)
 }
 
+#[cfg(feature = "compress")]
 pub fn choose_candidate_base<L: Latent>(sample: &mut [L]) -> Option<(L, f64)> {
   let triple_gcds = sample
     .chunks_exact(3)
@@ -215,8 +233,13 @@ pub fn choose_candidate_base<L: Latent>(sample: &mut [L]) -> Option<(L, f64)> {
   most_prominent_gcd(&triple_gcds, sample.len() / 3)
 }
 
-pub fn choose_base<T: Number>(nums: &[T]) -> Option<T::L> {
-  let mut sample = sampling::choose_sample(nums, |num| Some(num.to_latent_ordered()))?;
+#[cfg(feature = "compress")]
+pub fn choose_base<T: Number>(nums: &[T], seed: u64) -> Option<T::L> {
+  let mut sample = sampling::choose_sample(
+    nums,
+    |num| Some(num.to_latent_ordered()),
+    seed,
+  )?;
   let (candidate, bits_saved_per_adj) = choose_candidate_base(&mut sample)?;
 
   if sampling::est_bits_saved_per_num(&sample, |x| PrimaryLatentAndSavings {
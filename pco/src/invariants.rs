@@ -0,0 +1,143 @@
+// Checks of internal invariants during compression, compiled in only with
+// the debug_invariants feature. Each panics with details on violation, since
+// a violation means a bug in pco rather than bad input.
+
+use crate::ans::AnsState;
+use crate::compression_intermediates::DissectedPageVar;
+use crate::constants::{Bitlen, Weight};
+use crate::data_types::Latent;
+use crate::metadata::Bin;
+
+fn fits_in_bits<L: Latent>(x: L, bits: Bitlen) -> bool {
+  bits >= L::BITS || x < (L::ONE << bits)
+}
+
+pub fn check_page_ns(n_per_page: &[usize], chunk_n: usize) {
+  let total_n: usize = n_per_page.iter().sum();
+  assert_eq!(
+    total_n, chunk_n,
+    "page counts {:?} do not sum to chunk count",
+    n_per_page,
+  );
+}
+
+pub fn check_trained_bins<L: Latent>(
+  bins: &[Bin<L>],
+  ans_size_log: Bitlen,
+  counts: &[Weight],
+  latents: &[L],
+) {
+  let total_count = counts.iter().map(|&count| count as usize).sum::<usize>();
+  assert_eq!(
+    total_count,
+    latents.len(),
+    "bin counts {:?} do not sum to the latent count",
+    counts,
+  );
+
+  if bins.is_empty() {
+    return;
+  }
+  let total_weight = bins.iter().map(|bin| bin.weight as usize).sum::<usize>();
+  assert_eq!(
+    total_weight,
+    1 << ans_size_log,
+    "bin weights do not sum to the ANS table size for size log {}",
+    ans_size_log,
+  );
+
+  for (i, &latent) in latents.iter().enumerate() {
+    let in_some_bin = bins
+      .iter()
+      .any(|bin| latent >= bin.lower && fits_in_bits(latent - bin.lower, bin.offset_bits));
+    assert!(
+      in_some_bin,
+      "latent {} at index {} is not covered by any bin",
+      latent, i,
+    );
+  }
+}
+
+// The ANS table size is also the default state.
+pub fn check_dissected_page_var<L: Latent>(dissected: &DissectedPageVar, table_size: AnsState) {
+  for (i, &state) in dissected.ans_final_states.iter().enumerate() {
+    assert!(
+      state >= table_size && state < 2 * table_size,
+      "final ANS state {} of interleaved state {} is outside [{}, {})",
+      state,
+      i,
+      table_size,
+      2 * table_size,
+    );
+  }
+
+  for (i, (&val, &bits)) in dissected
+    .ans_vals
+    .iter()
+    .zip(&dissected.ans_bits)
+    .enumerate()
+  {
+    assert!(
+      fits_in_bits(val, bits),
+      "ANS value {} at index {} does not fit in {} bits",
+      val,
+      i,
+      bits,
+    );
+  }
+
+  let offsets = dissected.offsets.downcast_ref::<L>().unwrap();
+  for (i, (&offset, &bits)) in offsets.iter().zip(&dissected.offset_bits).enumerate() {
+    assert!(
+      fits_in_bits(offset, bits),
+      "offset {} at index {} does not fit in {} bits",
+      offset,
+      i,
+      bits,
+    );
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_valid_bins_pass() {
+    let bins = vec![
+      Bin {
+        weight: 3,
+        lower: 0_u32,
+        offset_bits: 2,
+      },
+      Bin {
+        weight: 1,
+        lower: 100,
+        offset_bits: 0,
+      },
+    ];
+    check_trained_bins(&bins, 2, &[2, 1], &[0, 3, 100]);
+  }
+
+  #[test]
+  #[should_panic(expected = "not covered by any bin")]
+  fn test_uncovered_latent_panics() {
+    let bins = vec![Bin {
+      weight: 1,
+      lower: 0_u32,
+      offset_bits: 2,
+    }];
+    check_trained_bins(&bins, 0, &[1], &[4]);
+  }
+
+  #[test]
+  #[should_panic(expected = "do not sum to the ANS table size")]
+  fn test_bad_weights_panic() {
+    let bins = vec![Bin {
+      weight: 3,
+      lower: 0_u32,
+      offset_bits: 2,
+    }];
+    check_trained_bins(&bins, 2, &[1], &[1]);
+  }
+}
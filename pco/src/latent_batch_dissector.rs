@@ -1,24 +1,24 @@
 use std::cmp::min;
 
-use crate::ans::{AnsState, Symbol};
+use crate::ans::{AnsState, EntropyEncoder, Symbol};
+use crate::bits;
 use crate::compression_intermediates::DissectedPageVar;
 use crate::compression_table::CompressionTable;
 use crate::constants::{Bitlen, ANS_INTERLEAVING, FULL_BATCH_N};
 use crate::data_types::Latent;
-use crate::{ans, bits};
 
-pub struct LatentBatchDissector<'a, L: Latent> {
+pub struct LatentBatchDissector<'a, L: Latent, E: EntropyEncoder> {
   // immutable
   table: &'a CompressionTable<L>,
-  encoder: &'a ans::Encoder,
+  encoder: &'a E,
 
   // mutable
   lower_scratch: [L; FULL_BATCH_N],
   symbol_scratch: [Symbol; FULL_BATCH_N],
 }
 
-impl<'a, L: Latent> LatentBatchDissector<'a, L> {
-  pub fn new(table: &'a CompressionTable<L>, encoder: &'a ans::Encoder) -> Self {
+impl<'a, L: Latent, E: EntropyEncoder> LatentBatchDissector<'a, L, E> {
+  pub fn new(table: &'a CompressionTable<L>, encoder: &'a E) -> Self {
     Self {
       table,
       encoder,
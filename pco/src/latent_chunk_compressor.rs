@@ -1,3 +1,4 @@
+use crate::ans::{Ans, EntropyCoder, EntropyEncoder};
 use crate::bit_writer::BitWriter;
 use crate::compression_intermediates::BinCompressionInfo;
 use crate::compression_intermediates::DissectedPageVar;
@@ -5,12 +6,14 @@ use crate::compression_table::CompressionTable;
 use crate::constants::{Bitlen, Weight, ANS_INTERLEAVING, PAGE_PADDING};
 use crate::data_types::Latent;
 use crate::errors::PcoResult;
+#[cfg(feature = "debug_invariants")]
+use crate::invariants;
 use crate::latent_batch_dissector::LatentBatchDissector;
 use crate::macros::{define_latent_enum, match_latent_enum};
 use crate::metadata::dyn_latents::DynLatents;
 use crate::metadata::{bins, Bin};
 use crate::read_write_uint::ReadWriteUint;
-use crate::{ans, bit_reader, bit_writer, read_write_uint, FULL_BATCH_N};
+use crate::{bit_reader, bit_writer, read_write_uint, FULL_BATCH_N};
 use std::io::Write;
 use std::ops::Range;
 
@@ -79,9 +82,9 @@ pub(crate) struct TrainedBins<L: Latent> {
 }
 
 #[derive(Clone, Debug)]
-pub struct LatentChunkCompressor<L: Latent> {
+pub struct LatentChunkCompressor<L: Latent, C: EntropyCoder = Ans> {
   table: CompressionTable<L>,
-  pub encoder: ans::Encoder,
+  pub encoder: C::Encoder,
   pub avg_bits_per_latent: f64,
   is_trivial: bool,
   needs_ans: bool,
@@ -89,14 +92,13 @@ pub struct LatentChunkCompressor<L: Latent> {
   latents: Vec<L>,
 }
 
-impl<L: Latent> LatentChunkCompressor<L> {
+impl<L: Latent, C: EntropyCoder> LatentChunkCompressor<L, C> {
   pub(crate) fn new(trained: TrainedBins<L>, bins: &[Bin<L>], latents: Vec<L>) -> PcoResult<Self> {
     let needs_ans = bins.len() != 1;
 
     let table = CompressionTable::from(trained.infos);
     let weights = bins::weights(bins);
-    let ans_spec = ans::Spec::from_weights(trained.ans_size_log, weights)?;
-    let encoder = ans::Encoder::new(&ans_spec);
+    let encoder = C::Encoder::from_weights(trained.ans_size_log, weights)?;
 
     let max_bits_per_offset = bins::max_offset_bits(bins);
     let max_u64s_per_offset = read_write_uint::calc_max_u64s_for_writing(max_bits_per_offset);
@@ -143,9 +145,38 @@ impl<L: Latent> LatentChunkCompressor<L> {
       let base_i = batch_idx * FULL_BATCH_N;
       lbd.dissect_latent_batch(batch, base_i, &mut dissected_page_var)
     }
+    #[cfg(feature = "debug_invariants")]
+    invariants::check_dissected_page_var::<L>(
+      &dissected_page_var,
+      self.encoder.default_state(),
+    );
     dissected_page_var
   }
 
+  // the exact number of bits write_dissected_batch will write across all
+  // batches of the page
+  pub fn dissected_page_bit_size(&self, dissected_page_var: &DissectedPageVar) -> usize {
+    let ans_bit_size = if self.needs_ans {
+      dissected_page_var
+        .ans_bits
+        .iter()
+        .map(|&bits| bits as usize)
+        .sum::<usize>()
+    } else {
+      0
+    };
+    let offset_bit_size = if self.max_u64s_per_offset == 0 {
+      0
+    } else {
+      dissected_page_var
+        .offset_bits
+        .iter()
+        .map(|&bits| bits as usize)
+        .sum::<usize>()
+    };
+    ans_bit_size + offset_bit_size
+  }
+
   pub fn write_dissected_batch<W: Write>(
     &self,
     dissected_page_var: &DissectedPageVar,
@@ -1,12 +1,12 @@
 use std::fmt::Debug;
 
-use crate::ans::{AnsState, Spec};
+use crate::ans::{Ans, EntropyCoder, EntropyDecoder};
 use crate::bit_reader::BitReader;
-use crate::constants::{Bitlen, DeltaLookback, ANS_INTERLEAVING, FULL_BATCH_N};
+use crate::constants::{Bitlen, DeltaLookback, FULL_BATCH_N};
 use crate::data_types::Latent;
 use crate::errors::{PcoError, PcoResult};
 use crate::metadata::{bins, Bin, DeltaEncoding, DynLatents};
-use crate::{ans, bit_reader, delta, read_write_uint};
+use crate::{bit_reader, decode_timing, delta, read_write_uint};
 
 // Default here is meaningless and should only be used to fill in empty
 // vectors.
@@ -26,49 +26,38 @@ impl<L: Latent> BinDecompressionInfo<L> {
 }
 
 #[derive(Clone, Debug)]
-struct State<L: Latent> {
+struct State<L: Latent, D: EntropyDecoder> {
   // scratch needs no backup
   offset_bits_csum_scratch: [Bitlen; FULL_BATCH_N],
   offset_bits_scratch: [Bitlen; FULL_BATCH_N],
   lowers_scratch: [L; FULL_BATCH_N],
 
-  ans_state_idxs: [AnsState; ANS_INTERLEAVING],
+  entropy_state: D::State,
   delta_state: Vec<L>,
   delta_state_pos: usize,
 }
 
-impl<L: Latent> State<L> {
-  #[inline]
-  fn set_scratch(&mut self, i: usize, offset_bit_idx: Bitlen, info: &BinDecompressionInfo<L>) {
-    unsafe {
-      *self.offset_bits_csum_scratch.get_unchecked_mut(i) = offset_bit_idx;
-      *self.offset_bits_scratch.get_unchecked_mut(i) = info.offset_bits;
-      *self.lowers_scratch.get_unchecked_mut(i) = info.lower;
-    };
-  }
-}
-
 // LatentBatchDecompressor does the main work of decoding bytes into Latents
 #[derive(Clone, Debug)]
-pub struct LatentPageDecompressor<L: Latent> {
+pub struct LatentPageDecompressor<L: Latent, C: EntropyCoder = Ans> {
   // known information about this latent variable
   u64s_per_offset: usize,
   infos: Vec<BinDecompressionInfo<L>>,
   needs_ans: bool,
-  decoder: ans::Decoder,
+  decoder: C::Decoder,
   delta_encoding: DeltaEncoding,
   pub maybe_constant_value: Option<L>,
 
   // mutable state
-  state: State<L>,
+  state: State<L, C::Decoder>,
 }
 
-impl<L: Latent> LatentPageDecompressor<L> {
+impl<L: Latent, C: EntropyCoder> LatentPageDecompressor<L, C> {
   pub fn new(
     ans_size_log: Bitlen,
     bins: &[Bin<L>],
     delta_encoding: DeltaEncoding,
-    ans_final_state_idxs: [AnsState; ANS_INTERLEAVING],
+    entropy_state: <C::Decoder as EntropyDecoder>::State,
     stored_delta_state: Vec<L>,
   ) -> PcoResult<Self> {
     let u64s_per_offset = read_write_uint::calc_max_u64s(bins::max_offset_bits(bins));
@@ -77,8 +66,7 @@ impl<L: Latent> LatentPageDecompressor<L> {
       .map(BinDecompressionInfo::new)
       .collect::<Vec<_>>();
     let weights = bins::weights(bins);
-    let ans_spec = Spec::from_weights(ans_size_log, weights)?;
-    let decoder = ans::Decoder::new(&ans_spec);
+    let decoder = C::Decoder::from_weights(ans_size_log, weights)?;
 
     let (working_delta_state, delta_state_pos) = match delta_encoding {
       DeltaEncoding::None | DeltaEncoding::Consecutive(_) => (stored_delta_state, 0),
@@ -91,7 +79,7 @@ impl<L: Latent> LatentPageDecompressor<L> {
       offset_bits_csum_scratch: [0; FULL_BATCH_N],
       offset_bits_scratch: [0; FULL_BATCH_N],
       lowers_scratch: [L::ZERO; FULL_BATCH_N],
-      ans_state_idxs: ans_final_state_idxs,
+      entropy_state,
       delta_state: working_delta_state,
       delta_state_pos,
     };
@@ -127,79 +115,28 @@ impl<L: Latent> LatentPageDecompressor<L> {
     })
   }
 
-  // This implementation handles only a full batch, but is faster.
+  // Decodes each latent's bin and fills in the scratch needed to read its
+  // offset, in the same pass.
   #[inline(never)]
-  unsafe fn decompress_full_ans_symbols(&mut self, reader: &mut BitReader) {
-    // At each iteration, this loads a single u64 and has all ANS decoders
-    // read a single symbol from it.
-    // Therefore it requires that ANS_INTERLEAVING * MAX_BITS_PER_ANS <= 57.
-    // Additionally, we're unpacking all ANS states using the fact that
-    // ANS_INTERLEAVING == 4.
-    let src = reader.src;
-    let mut stale_byte_idx = reader.stale_byte_idx;
-    let mut bits_past_byte = reader.bits_past_byte;
-    let mut offset_bit_idx = 0;
-    let [mut state_idx_0, mut state_idx_1, mut state_idx_2, mut state_idx_3] =
-      self.state.ans_state_idxs;
+  unsafe fn decompress_bins(&mut self, reader: &mut BitReader, batch_n: usize) {
     let infos = self.infos.as_slice();
-    let ans_nodes = self.decoder.nodes.as_slice();
-    for base_i in (0..FULL_BATCH_N).step_by(ANS_INTERLEAVING) {
-      stale_byte_idx += bits_past_byte as usize / 8;
-      bits_past_byte %= 8;
-      let packed = bit_reader::u64_at(src, stale_byte_idx);
-      // I hate that I have to do this with a macro, but it gives a serious
-      // performance gain. If I use a [AnsState; 4] for the state_idxs instead
-      // of separate identifiers, it tries to repeatedly load and write to
-      // the array instead of keeping the states in registers.
-      macro_rules! handle_single_symbol {
-        ($j: expr, $state_idx: ident) => {
-          let i = base_i + $j;
-          let node = unsafe { ans_nodes.get_unchecked($state_idx as usize) };
-          let ans_val = (packed >> bits_past_byte) as AnsState & ((1 << node.bits_to_read) - 1);
-          let info = unsafe { infos.get_unchecked(node.symbol as usize) };
-          self.state.set_scratch(i, offset_bit_idx, info);
-          bits_past_byte += node.bits_to_read;
-          offset_bit_idx += info.offset_bits;
-          $state_idx = node.next_state_idx_base + ans_val;
-        };
-      }
-      handle_single_symbol!(0, state_idx_0);
-      handle_single_symbol!(1, state_idx_1);
-      handle_single_symbol!(2, state_idx_2);
-      handle_single_symbol!(3, state_idx_3);
-    }
-
-    reader.stale_byte_idx = stale_byte_idx;
-    reader.bits_past_byte = bits_past_byte;
-    self.state.ans_state_idxs = [state_idx_0, state_idx_1, state_idx_2, state_idx_3];
-  }
-
-  // This implementation handles arbitrary batch size and looks simpler, but is
-  // slower, so we only use it at the end of the page.
-  #[inline(never)]
-  unsafe fn decompress_ans_symbols(&mut self, reader: &mut BitReader, batch_n: usize) {
-    let src = reader.src;
-    let mut stale_byte_idx = reader.stale_byte_idx;
-    let mut bits_past_byte = reader.bits_past_byte;
+    let state = &mut self.state;
     let mut offset_bit_idx = 0;
-    let mut state_idxs = self.state.ans_state_idxs;
-    for i in 0..batch_n {
-      let j = i % 4;
-      stale_byte_idx += bits_past_byte as usize / 8;
-      bits_past_byte %= 8;
-      let packed = bit_reader::u64_at(src, stale_byte_idx);
-      let node = unsafe { self.decoder.nodes.get_unchecked(state_idxs[j] as usize) };
-      let ans_val = (packed >> bits_past_byte) as AnsState & ((1 << node.bits_to_read) - 1);
-      let info = &self.infos[node.symbol as usize];
-      self.state.set_scratch(i, offset_bit_idx, info);
-      bits_past_byte += node.bits_to_read;
-      offset_bit_idx += info.offset_bits;
-      state_idxs[j] = node.next_state_idx_base + ans_val;
-    }
-
-    reader.stale_byte_idx = stale_byte_idx;
-    reader.bits_past_byte = bits_past_byte;
-    self.state.ans_state_idxs = state_idxs;
+    self.decoder.decode_batch(
+      reader,
+      &mut state.entropy_state,
+      batch_n,
+      |i, symbol| {
+        // in range for any input; see EntropyDecoder::decode_batch
+        unsafe {
+          let info = infos.get_unchecked(symbol as usize);
+          *state.offset_bits_csum_scratch.get_unchecked_mut(i) = offset_bit_idx;
+          *state.offset_bits_scratch.get_unchecked_mut(i) = info.offset_bits;
+          *state.lowers_scratch.get_unchecked_mut(i) = info.lower;
+          offset_bit_idx += info.offset_bits;
+        }
+      },
+    );
   }
 
   #[inline(never)]
@@ -250,30 +187,28 @@ impl<L: Latent> LatentPageDecompressor<L> {
       let batch_n = dst.len();
       assert!(batch_n <= FULL_BATCH_N);
 
-      if batch_n == FULL_BATCH_N {
-        self.decompress_full_ans_symbols(reader);
-      } else {
-        self.decompress_ans_symbols(reader, batch_n);
-      }
+      decode_timing::time_ans(|| self.decompress_bins(reader, batch_n));
     }
 
-    // this assertion saves some unnecessary specializations in the compiled assembly
-    assert!(self.u64s_per_offset <= read_write_uint::calc_max_u64s(L::BITS));
-    match self.u64s_per_offset {
-      0 => {
-        dst.copy_from_slice(&self.state.lowers_scratch[..dst.len()]);
-        return;
+    decode_timing::time_offsets(|| {
+      // this assertion saves some unnecessary specializations in the compiled assembly
+      assert!(self.u64s_per_offset <= read_write_uint::calc_max_u64s(L::BITS));
+      match self.u64s_per_offset {
+        0 => {
+          dst.copy_from_slice(&self.state.lowers_scratch[..dst.len()]);
+          return;
+        }
+        1 => self.decompress_offsets::<1>(reader, dst),
+        2 => self.decompress_offsets::<2>(reader, dst),
+        3 => self.decompress_offsets::<3>(reader, dst),
+        _ => panic!(
+          "[LatentBatchDecompressor] data type too large (extra u64's {} > 2)",
+          self.u64s_per_offset
+        ),
       }
-      1 => self.decompress_offsets::<1>(reader, dst),
-      2 => self.decompress_offsets::<2>(reader, dst),
-      3 => self.decompress_offsets::<3>(reader, dst),
-      _ => panic!(
-        "[LatentBatchDecompressor] data type too large (extra u64's {} > 2)",
-        self.u64s_per_offset
-      ),
-    }
 
-    self.add_lowers(dst);
+      self.add_lowers(dst);
+    });
   }
 
   pub unsafe fn decompress_batch(
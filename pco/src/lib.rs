@@ -16,8 +16,16 @@
 #[cfg(doctest)]
 struct ReadmeDoctest;
 
+#[cfg(feature = "compress")]
 pub use chunk_config::{ChunkConfig, DeltaSpec, ModeSpec, PagingSpec};
-pub use constants::{DEFAULT_COMPRESSION_LEVEL, DEFAULT_MAX_PAGE_N, FULL_BATCH_N};
+#[cfg(feature = "compress")]
+pub use constants::MAX_BINS;
+pub use constants::{
+  DEFAULT_COMPRESSION_LEVEL, DEFAULT_MAX_PAGE_N, FULL_BATCH_N, MAX_COMPRESSION_LEVEL,
+};
+#[cfg(feature = "decode_timing")]
+pub use decode_timing::{take_decode_timing, DecodeTiming};
+pub use decompressor_config::DecompressorConfig;
 pub use progress::Progress;
 
 pub mod data_types;
@@ -32,26 +40,40 @@ pub mod standalone;
 pub mod wrapped;
 
 mod ans;
+#[cfg(feature = "compress")]
 mod bin_optimization;
 mod bit_reader;
+#[cfg(feature = "compress")]
 mod bit_writer;
 mod bits;
+#[cfg(feature = "compress")]
 mod chunk_config;
+#[cfg(feature = "compress")]
 mod compression_intermediates;
+#[cfg(feature = "compress")]
 mod compression_table;
 mod constants;
+mod decode_timing;
+mod decompressor_config;
 mod delta;
 mod float_mult_utils;
 mod float_quant_utils;
+#[cfg(feature = "compress")]
 mod histograms;
 mod int_mult_utils;
+#[cfg(feature = "debug_invariants")]
+mod invariants;
+#[cfg(feature = "compress")]
 mod latent_batch_dissector;
+#[cfg(feature = "compress")]
 mod latent_chunk_compressor;
 mod latent_page_decompressor;
 mod macros;
 mod progress;
 mod read_write_uint;
+#[cfg(feature = "compress")]
 mod sampling;
+#[cfg(feature = "compress")]
 mod sort_utils;
 
 #[cfg(test)]
@@ -30,6 +30,7 @@ dtype_dispatch::build_dtype_macros!(
     I16 => i16,
     I32 => i32,
     I64 => i64,
+    U8 => u8,
     U16 => u16,
     U32 => u32,
     U64 => u64,
@@ -52,6 +53,7 @@ dtype_dispatch::build_dtype_macros!(
 
   Latent,
   {
+    U8 => u8,
     U16 => u16,
     U32 => u32,
     U64 => u64,
@@ -61,3 +63,4 @@ dtype_dispatch::build_dtype_macros!(
 pub(crate) use define_latent_enum;
 pub(crate) use define_number_enum;
 pub(crate) use match_latent_enum;
+pub(crate) use match_number_enum;
@@ -1,11 +1,13 @@
+#[cfg(feature = "compress")]
 use crate::bits::bits_to_encode_offset_bits;
+#[cfg(feature = "compress")]
 use crate::compression_intermediates::BinCompressionInfo;
 use crate::constants::{Bitlen, Weight};
 use crate::data_types::Latent;
 
 /// Part of [`ChunkLatentVarMeta`][`crate::metadata::ChunkLatentVarMeta`] representing
 /// a numerical range.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Bin<L: Latent> {
   /// The number of occurrences of this bin in the asymmetric numeral system
   /// table.
@@ -17,16 +19,19 @@ pub struct Bin<L: Latent> {
 }
 
 impl<L: Latent> Bin<L> {
+  #[cfg(feature = "compress")]
   pub(crate) fn exact_bit_size(ans_size_log: Bitlen) -> Bitlen {
     ans_size_log + L::BITS + bits_to_encode_offset_bits::<L>()
   }
 
+  #[cfg(feature = "compress")]
   #[inline]
   pub(crate) fn worst_case_bits_per_latent(&self, ans_size_log: Bitlen) -> Bitlen {
     self.offset_bits + ans_size_log - self.weight.ilog2()
   }
 }
 
+#[cfg(feature = "compress")]
 impl<L: Latent> From<BinCompressionInfo<L>> for Bin<L> {
   fn from(info: BinCompressionInfo<L>) -> Self {
     Bin {
@@ -20,6 +20,7 @@ pub fn weights<L: Latent>(bins: &[Bin<L>]) -> Vec<Weight> {
   bins.iter().map(|bin| bin.weight).collect()
 }
 
+#[cfg(feature = "compress")]
 pub fn avg_bits_per_latent<L: Latent>(bins: &[Bin<L>], ans_size_log: Bitlen) -> f64 {
   let total_weight = (1 << ans_size_log) as f64;
   bins
@@ -1,20 +1,38 @@
+#[cfg(feature = "compress")]
 use std::io::Write;
 
 use better_io::BetterBufRead;
 
 use crate::bit_reader::BitReaderBuilder;
+#[cfg(feature = "compress")]
 use crate::bit_writer::BitWriter;
-use crate::constants::DeltaLookback;
-use crate::data_types::LatentType;
+use crate::constants::{DeltaLookback, Weight};
+use crate::data_types::{Latent, LatentType, Number};
 use crate::errors::{PcoError, PcoResult};
+use crate::macros::match_latent_enum;
+use crate::metadata::bin::Bin;
+use crate::metadata::bins;
 use crate::metadata::chunk_latent_var::ChunkLatentVarMeta;
 use crate::metadata::delta_encoding::DeltaEncoding;
+use crate::metadata::dyn_bins::DynBins;
+use crate::metadata::dyn_latents::DynLatents;
 use crate::metadata::format_version::FormatVersion;
-use crate::metadata::per_latent_var::PerLatentVar;
+use crate::metadata::per_latent_var::{LatentVarKey, PerLatentVar};
 use crate::metadata::Mode;
 
+// Returns the inclusive range of latents covered by the bins, widened to u128
+// so that bin upper bounds can't overflow.
+fn bins_latent_range<L: Latent>(bins: &[Bin<L>]) -> Option<(u128, u128)> {
+  let lower = bins.iter().map(|bin| bin.lower.to_u64() as u128).min()?;
+  let upper = bins
+    .iter()
+    .map(|bin| bin.lower.to_u64() as u128 + ((1_u128 << bin.offset_bits) - 1))
+    .max()?;
+  Some((lower, upper))
+}
+
 /// The metadata of a pco chunk.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct ChunkMeta {
   /// The formula `pco` used to compress each number at a low level.
   pub mode: Mode,
@@ -27,18 +45,268 @@ pub struct ChunkMeta {
 }
 
 impl ChunkMeta {
-  pub(crate) fn exact_size(&self) -> usize {
+  #[cfg(feature = "compress")]
+  pub(crate) fn exact_size(&self, version: &FormatVersion) -> usize {
     let bits_for_latent_vars = self
       .per_latent_var
       .as_ref()
       .map(|_, var_meta| var_meta.exact_bit_size())
       .sum();
     let n_bits = self.mode.exact_bit_size() as usize
-      + self.delta_encoding.exact_bit_size() as usize
+      + self.delta_encoding.exact_bit_size(version) as usize
       + bits_for_latent_vars;
     n_bits.div_ceil(8)
   }
 
+  /// Returns the smallest and largest numbers this chunk could contain,
+  /// according to the bins of its latent variables.
+  ///
+  /// The bounds are conservative: every number in the chunk lies within
+  /// them, but bins may extend past the true min and max.
+  /// Floats are bounded in their total ordering, so NaNs of either sign are
+  /// accounted for.
+  ///
+  /// Returns `None` if the bounds can't be derived from the metadata alone:
+  /// when delta encoding or float mult mode was used, when the chunk has no
+  /// bins, or when `T` doesn't match the chunk's latent type.
+  pub fn value_range<T: Number>(&self) -> Option<(T, T)> {
+    if self.delta_encoding != DeltaEncoding::None || !T::mode_is_valid(self.mode) {
+      return None;
+    }
+
+    let primary_bins = self.per_latent_var.primary.bins.downcast_ref::<T::L>()?;
+    let (primary_lower, primary_upper) = bins_latent_range(primary_bins)?;
+    self.value_range_from_primary(primary_lower, primary_upper)
+  }
+
+  // Like `value_range`, but also handles consecutive delta encoding, given the
+  // count of numbers in a page and the page's delta moments for the primary
+  // latent variable.
+  pub(crate) fn page_value_range<T: Number>(
+    &self,
+    page_n: usize,
+    primary_delta_moments: &DynLatents,
+  ) -> Option<(T, T)> {
+    let order = match self.delta_encoding {
+      DeltaEncoding::None => return self.value_range(),
+      DeltaEncoding::Consecutive(config) => config.order,
+      DeltaEncoding::Lookback(_) => return None,
+    };
+    let secondary_uses_delta = self.per_latent_var.secondary.is_some()
+      && self.delta_encoding.for_latent_var(LatentVarKey::Secondary) != DeltaEncoding::None;
+    if secondary_uses_delta || !T::mode_is_valid(self.mode) {
+      return None;
+    }
+
+    let moments = primary_delta_moments.downcast_ref::<T::L>()?;
+    if moments.len() != order {
+      return None;
+    }
+
+    // We bound each order of deltas, starting from the highest, as signed
+    // integers without wrapping.
+    // Every value at a lower order is its moment plus a sum of at most
+    // (count - 1) values of the order above.
+    let mid = T::L::MID.to_u64() as i128;
+    let primary_bins = self.per_latent_var.primary.bins.downcast_ref::<T::L>()?;
+    let n_deltas = page_n.saturating_sub(order);
+    let (mut lower, mut upper) = if n_deltas == 0 {
+      (0, 0)
+    } else {
+      let (lower, upper) = bins_latent_range(primary_bins)?;
+      (lower as i128 - mid, upper as i128 - mid)
+    };
+    for (moment_idx, &moment) in moments.iter().enumerate().rev() {
+      let moment = if moment_idx == 0 {
+        moment.to_u64() as i128
+      } else {
+        // higher order moments are wrapped differences
+        moment.toggle_center().to_u64() as i128 - mid
+      };
+      let n_terms = page_n.saturating_sub(moment_idx + 1) as i128;
+      lower = moment.checked_add(n_terms.checked_mul(lower.min(0))?)?;
+      upper = moment.checked_add(n_terms.checked_mul(upper.max(0))?)?;
+    }
+
+    // if the bounds don't wrap, neither do the latents they bound
+    let latent_max = T::L::MAX.to_u64() as i128;
+    if lower < 0 || upper > latent_max {
+      return None;
+    }
+    self.value_range_from_primary(lower as u128, upper as u128)
+  }
+
+  // Converts an inclusive range of primary latents into numbers, accounting
+  // for the mode.
+  fn value_range_from_primary<T: Number>(
+    &self,
+    primary_lower: u128,
+    primary_upper: u128,
+  ) -> Option<(T, T)> {
+    let latent_max = T::L::MAX.to_u64() as u128;
+    let (lower, upper) = match self.mode {
+      Mode::Classic => (primary_lower, primary_upper),
+      Mode::IntMult(base) => {
+        let base = base.downcast_ref::<T::L>()?.to_u64() as u128;
+        let secondary_bins = self
+          .per_latent_var
+          .secondary
+          .as_ref()?
+          .bins
+          .downcast_ref::<T::L>()?;
+        let (secondary_lower, secondary_upper) = bins_latent_range(secondary_bins)?;
+        (
+          primary_lower * base + secondary_lower,
+          primary_upper * base + secondary_upper.min(base - 1),
+        )
+      }
+      Mode::FloatQuant(k) => {
+        let primary_upper = primary_upper.min(latent_max >> k);
+        (
+          primary_lower << k,
+          (primary_upper << k) + ((1_u128 << k) - 1),
+        )
+      }
+      _ => return None,
+    };
+
+    let to_num =
+      |latent: u128| T::from_latent_ordered(T::L::from_u64(latent.min(latent_max) as u64));
+    Some((to_num(lower), to_num(upper)))
+  }
+
+  /// Returns the lower bound and weight of each primary bin, with the lower
+  /// bound converted into the number space of `T`.
+  ///
+  /// The bounds are the smallest numbers each bin could produce, and bins
+  /// are in increasing order of their primary latents.
+  /// Weights are relative to `1 << ans_size_log` of the primary latent
+  /// variable, so they approximate the fraction of numbers in each bin.
+  /// In int mult and float mult modes, each bound accounts for the smallest
+  /// adjustment in the chunk.
+  ///
+  /// Returns `None` for delta-encoded chunks, since their primary bins hold
+  /// deltas between numbers rather than the numbers themselves, and for
+  /// number types that don't match the chunk's mode.
+  pub fn bins_as_numbers<T: Number>(&self) -> Option<Vec<(T, Weight)>> {
+    if self.delta_encoding != DeltaEncoding::None || !T::mode_is_valid(self.mode) {
+      return None;
+    }
+
+    let primary_bins = self.per_latent_var.primary.bins.downcast_ref::<T::L>()?;
+    let secondary_lower = || {
+      let secondary_bins = self
+        .per_latent_var
+        .secondary
+        .as_ref()?
+        .bins
+        .downcast_ref::<T::L>()?;
+      bins_latent_range(secondary_bins).map(|(lower, _)| lower)
+    };
+    // each number's latent is (primary * mult + add) << shift
+    let (mult, add, shift) = match self.mode {
+      Mode::Classic => (1, 0, 0),
+      Mode::IntMult(base) => {
+        let base = base.downcast_ref::<T::L>()?.to_u64() as u128;
+        (base, secondary_lower()?, 0)
+      }
+      Mode::FloatMultLossy(_) => {
+        let mut latents = primary_bins.iter().map(|bin| bin.lower).collect::<Vec<_>>();
+        T::join_latents(self.mode, &mut latents, None);
+        let res = latents
+          .into_iter()
+          .zip(primary_bins)
+          .map(|(latent, bin)| (T::from_latent_ordered(latent), bin.weight))
+          .collect();
+        return Some(res);
+      }
+      Mode::FloatMult(_) => {
+        // float mult joins its latents in floating point, so we join each
+        // bin's lower bound the same way decompression would
+        let mut latents = primary_bins.iter().map(|bin| bin.lower).collect::<Vec<_>>();
+        let adj = T::L::from_u64(secondary_lower()? as u64);
+        let adjustments = DynLatents::new(vec![adj; latents.len()])?;
+        T::join_latents(self.mode, &mut latents, Some(&adjustments));
+        let res = latents
+          .into_iter()
+          .zip(primary_bins)
+          .map(|(latent, bin)| (T::from_latent_ordered(latent), bin.weight))
+          .collect();
+        return Some(res);
+      }
+      Mode::FloatQuant(k) => (1, 0, k),
+    };
+
+    let latent_max = T::L::MAX.to_u64() as u128;
+    let res = primary_bins
+      .iter()
+      .map(|bin| {
+        let latent = ((bin.lower.to_u64() as u128 * mult + add) << shift).min(latent_max);
+        let num = T::from_latent_ordered(T::L::from_u64(latent as u64));
+        (num, bin.weight)
+      })
+      .collect();
+    Some(res)
+  }
+
+  /// Returns a human-readable description of what each latent variable
+  /// represents, e.g. `"multiplier [x0.1]"` for the primary latent of float
+  /// mult mode.
+  ///
+  /// These are the same descriptions given by
+  /// [`Number::get_latent_describers`][crate::data_types::Number::get_latent_describers].
+  /// `T` must be the number type the chunk was compressed with, or this may
+  /// panic.
+  pub fn describe<T: Number>(&self) -> PerLatentVar<String> {
+    T::get_latent_describers(self).map(|_, describer| describer.latent_var())
+  }
+
+  /// Returns a rough estimate of the cost of decoding each number in the
+  /// chunk, in arbitrary units, without decoding anything.
+  ///
+  /// A classic mode chunk with no delta encoding and a few bins costs about
+  /// 1 per number.
+  /// Each latent variable adds to the cost according to how many offset bits
+  /// its bins need, and delta encoding adds to the cost of each latent
+  /// variable it applies to.
+  /// Latent variables with a single bin and no offsets are nearly free.
+  /// This is only meant for comparing chunks, e.g. for deciding which data
+  /// to decode eagerly; actual decode speed varies by machine.
+  pub fn decode_cost_per_num(&self) -> f64 {
+    self
+      .per_latent_var
+      .as_ref()
+      .map(|key, var_meta| {
+        let (is_trivial, avg_offset_bits) = match_latent_enum!(
+          &var_meta.bins,
+          DynBins<L>(bins) => {
+            let total_weight = bins.iter().map(|bin| bin.weight as f64).sum::<f64>();
+            let avg_offset_bits = bins
+              .iter()
+              .map(|bin| bin.weight as f64 * bin.offset_bits as f64)
+              .sum::<f64>()
+              / total_weight.max(1.0);
+            (bins::are_trivial(bins), avg_offset_bits)
+          }
+        );
+        let delta_cost = match self.delta_encoding.for_latent_var(key) {
+          DeltaEncoding::None => 0.0,
+          DeltaEncoding::Consecutive(config) => 0.1 * config.order as f64,
+          DeltaEncoding::Lookback(_) => 0.5,
+        };
+        let decode_cost = if is_trivial {
+          0.1
+        } else {
+          // ANS decoding dominates, and reading offsets gets slower as they
+          // get wider
+          0.8 + avg_offset_bits / 64.0
+        };
+        decode_cost + delta_cost
+      })
+      .sum()
+  }
+
+  #[cfg(feature = "compress")]
   pub(crate) fn exact_page_meta_size(&self) -> usize {
     let bit_size = self
       .per_latent_var
@@ -126,9 +394,14 @@ impl ChunkMeta {
     })
   }
 
-  pub(crate) unsafe fn write_to<W: Write>(&self, writer: &mut BitWriter<W>) -> PcoResult<()> {
+  #[cfg(feature = "compress")]
+  pub(crate) unsafe fn write_to<W: Write>(
+    &self,
+    version: &FormatVersion,
+    writer: &mut BitWriter<W>,
+  ) -> PcoResult<()> {
     self.mode.write_to(writer);
-    self.delta_encoding.write_to(writer);
+    self.delta_encoding.write_to(version, writer);
 
     writer.flush()?;
 
@@ -146,22 +419,23 @@ impl ChunkMeta {
 mod tests {
   use super::*;
   use crate::constants::ANS_INTERLEAVING;
-  use crate::data_types::Latent;
-  use crate::macros::match_latent_enum;
   use crate::metadata::delta_encoding::DeltaConsecutiveConfig;
   use crate::metadata::dyn_bins::DynBins;
   use crate::metadata::dyn_latents::DynLatents;
   use crate::metadata::page::PageMeta;
   use crate::metadata::page_latent_var::PageLatentVarMeta;
-  use crate::metadata::{Bin, DynLatent};
+  use crate::metadata::DynLatent;
+  use crate::wrapped::FileCompressor;
+  use crate::{ChunkConfig, DeltaSpec, ModeSpec};
 
   fn check_exact_sizes(meta: &ChunkMeta) -> PcoResult<()> {
     let buffer_size = 8192;
     let mut dst = Vec::new();
     let mut writer = BitWriter::new(&mut dst, buffer_size);
-    unsafe { meta.write_to(&mut writer)? };
+    let version = FormatVersion::default();
+    unsafe { meta.write_to(&version, &mut writer)? };
     writer.flush()?;
-    assert_eq!(meta.exact_size(), dst.len());
+    assert_eq!(meta.exact_size(&version), dst.len());
 
     // page meta size
     let mut dst = Vec::new();
@@ -283,4 +557,314 @@ mod tests {
 
     check_exact_sizes(&meta)
   }
+
+  fn classic_meta(bins: DynBins) -> ChunkMeta {
+    ChunkMeta {
+      mode: Mode::Classic,
+      delta_encoding: DeltaEncoding::None,
+      per_latent_var: PerLatentVar {
+        delta: None,
+        primary: ChunkLatentVarMeta {
+          ans_size_log: 0,
+          bins,
+        },
+        secondary: None,
+      },
+    }
+  }
+
+  #[test]
+  fn value_range_classic() {
+    let bins = vec![
+      Bin {
+        weight: 1,
+        lower: 1_u32,
+        offset_bits: 2,
+      },
+      Bin {
+        weight: 1,
+        lower: 100_u32,
+        offset_bits: 3,
+      },
+    ];
+    let meta = classic_meta(DynBins::U32(bins));
+    assert_eq!(meta.value_range::<u32>(), Some((1, 107)));
+    assert_eq!(
+      meta.value_range::<i32>(),
+      Some((i32::MIN + 1, i32::MIN + 107))
+    );
+    assert_eq!(meta.value_range::<u64>(), None);
+
+    // bins may extend past the latent type's max
+    let bins = vec![Bin {
+      weight: 1,
+      lower: u32::MAX - 1,
+      offset_bits: 2,
+    }];
+    let meta = classic_meta(DynBins::U32(bins));
+    assert_eq!(
+      meta.value_range::<u32>(),
+      Some((u32::MAX - 1, u32::MAX))
+    );
+
+    assert_eq!(
+      classic_meta(DynBins::U32(vec![])).value_range::<u32>(),
+      None
+    );
+  }
+
+  #[test]
+  fn value_range_int_mult() {
+    let meta = ChunkMeta {
+      mode: Mode::IntMult(DynLatent::U64(10)),
+      delta_encoding: DeltaEncoding::None,
+      per_latent_var: PerLatentVar {
+        delta: None,
+        primary: ChunkLatentVarMeta {
+          ans_size_log: 0,
+          bins: DynBins::U64(vec![Bin {
+            weight: 1,
+            lower: 3,
+            offset_bits: 1,
+          }]),
+        },
+        secondary: Some(ChunkLatentVarMeta {
+          ans_size_log: 0,
+          bins: DynBins::U64(vec![Bin {
+            weight: 1,
+            lower: 2,
+            offset_bits: 4,
+          }]),
+        }),
+      },
+    };
+    assert_eq!(meta.value_range::<u64>(), Some((32, 49)));
+  }
+
+  #[test]
+  fn value_range_delta_encoded() {
+    let mut meta = classic_meta(DynBins::U32(vec![Bin {
+      weight: 1,
+      lower: 1_u32,
+      offset_bits: 2,
+    }]));
+    meta.delta_encoding = DeltaEncoding::Consecutive(DeltaConsecutiveConfig {
+      order: 1,
+      secondary_uses_delta: false,
+    });
+    assert_eq!(meta.value_range::<u32>(), None);
+
+    // deltas in [1, 4] (after toggling the center)
+    let mid = u32::MID;
+    meta.per_latent_var.primary.bins = DynBins::U32(vec![Bin {
+      weight: 1,
+      lower: mid + 1,
+      offset_bits: 2,
+    }]);
+    let moments = DynLatents::U32(vec![10]);
+    assert_eq!(
+      meta.page_value_range::<u32>(5, &moments),
+      Some((10, 26))
+    );
+    assert_eq!(
+      meta.page_value_range::<u32>(1, &moments),
+      Some((10, 10))
+    );
+    assert_eq!(
+      meta.page_value_range::<u32>(5, &DynLatents::U32(vec![])),
+      None
+    );
+
+    // deltas of -3 would wrap below 0 after 4 of them
+    meta.per_latent_var.primary.bins = DynBins::U32(vec![Bin {
+      weight: 1,
+      lower: mid - 3,
+      offset_bits: 0,
+    }]);
+    assert_eq!(
+      meta.page_value_range::<u32>(4, &moments),
+      Some((1, 10))
+    );
+    assert_eq!(
+      meta.page_value_range::<u32>(5, &moments),
+      None
+    );
+
+    // constant first order deltas of 2, given as a wrapped moment
+    meta.delta_encoding = DeltaEncoding::Consecutive(DeltaConsecutiveConfig {
+      order: 2,
+      secondary_uses_delta: false,
+    });
+    meta.per_latent_var.primary.bins = DynBins::U32(vec![Bin {
+      weight: 1,
+      lower: mid,
+      offset_bits: 0,
+    }]);
+    let moments = DynLatents::U32(vec![10, 2]);
+    assert_eq!(
+      meta.page_value_range::<u32>(5, &moments),
+      Some((10, 18))
+    );
+    let moments = DynLatents::U32(vec![10, 2_u32.wrapping_neg()]);
+    assert_eq!(
+      meta.page_value_range::<u32>(5, &moments),
+      Some((2, 10))
+    );
+  }
+
+  #[test]
+  fn value_range_compressed() -> PcoResult<()> {
+    let nums = (0..1000)
+      .map(|i| (i as f32 - 300.0) * 0.25)
+      .collect::<Vec<_>>();
+    for mode_spec in [
+      ModeSpec::Classic,
+      ModeSpec::TryFloatQuant(10),
+      ModeSpec::TryFloatMult(0.25),
+    ] {
+      let config = ChunkConfig::default()
+        .with_mode_spec(mode_spec)
+        .with_delta_spec(DeltaSpec::None);
+      let fc = FileCompressor::default();
+      let cc = fc.chunk_compressor(&nums, &config)?;
+      let meta = cc.meta();
+      match meta.mode {
+        Mode::FloatMult(_) => assert_eq!(meta.value_range::<f32>(), None),
+        _ => {
+          let (lower, upper) = meta.value_range::<f32>().unwrap();
+          assert!(lower <= -75.0, "{:?}", meta.mode);
+          assert!(upper >= 174.75, "{:?}", meta.mode);
+        }
+      }
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn hash_identical_chunk_metas() -> PcoResult<()> {
+    use std::collections::HashSet;
+
+    let fc = FileCompressor::default();
+    let config = ChunkConfig::default().with_mode_spec(ModeSpec::TryFloatMult(0.1));
+    let nums = (0..1000).map(|i| i as f64 * 0.1).collect::<Vec<_>>();
+    let other_nums = (0..1000).map(|i| i as f64 * 0.3).collect::<Vec<_>>();
+    let metas = [&nums, &nums, &other_nums]
+      .into_iter()
+      .map(|nums| Ok(fc.chunk_compressor(nums, &config)?.meta().clone()))
+      .collect::<PcoResult<Vec<_>>>()?;
+    assert!(matches!(metas[0].mode, Mode::FloatMult(_)));
+    assert_eq!(metas[0], metas[1]);
+    assert_ne!(metas[0], metas[2]);
+
+    let unique = metas.into_iter().collect::<HashSet<_>>();
+    assert_eq!(unique.len(), 2);
+    Ok(())
+  }
+
+  #[test]
+  fn describe_latent_vars() -> PcoResult<()> {
+    let fc = FileCompressor::default();
+    let config = ChunkConfig::default()
+      .with_mode_spec(ModeSpec::TryFloatMult(0.1))
+      .with_delta_spec(DeltaSpec::None);
+    let nums = (0..1000).map(|i| i as f64 * 0.1).collect::<Vec<_>>();
+    let cc = fc.chunk_compressor(&nums, &config)?;
+    let description = cc.meta().describe::<f64>();
+    assert_eq!(description.delta, None);
+    assert_eq!(description.primary, "multiplier [x0.1]");
+    assert_eq!(
+      description.secondary.as_deref(),
+      Some("adjustment")
+    );
+
+    let classic_meta = classic_meta(DynBins::U32(vec![]));
+    assert_eq!(
+      classic_meta.describe::<u32>().primary,
+      "primary"
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn bins_as_numbers_int_mult() {
+    let mut meta = classic_meta(DynBins::U32(vec![
+      Bin {
+        weight: 3,
+        lower: 10_u32,
+        offset_bits: 2,
+      },
+      Bin {
+        weight: 1,
+        lower: 20_u32,
+        offset_bits: 0,
+      },
+    ]));
+    assert_eq!(
+      meta.bins_as_numbers::<u32>(),
+      Some(vec![(10, 3), (20, 1)])
+    );
+
+    meta.mode = Mode::IntMult(DynLatent::new(7_u32).unwrap());
+    meta.per_latent_var.secondary = Some(ChunkLatentVarMeta {
+      ans_size_log: 0,
+      bins: DynBins::U32(vec![Bin {
+        weight: 1,
+        lower: 2_u32,
+        offset_bits: 2,
+      }]),
+    });
+    assert_eq!(
+      meta.bins_as_numbers::<u32>(),
+      Some(vec![(72, 3), (142, 1)])
+    );
+    assert_eq!(meta.bins_as_numbers::<f32>(), None);
+
+    meta.delta_encoding = DeltaEncoding::Consecutive(DeltaConsecutiveConfig {
+      order: 1,
+      secondary_uses_delta: false,
+    });
+    assert_eq!(meta.bins_as_numbers::<u32>(), None);
+  }
+
+  #[test]
+  fn bins_as_numbers_float_mult() -> PcoResult<()> {
+    let nums = (0..1000)
+      .map(|i| {
+        if i % 2 == 0 {
+          -3.0
+        } else {
+          (i % 50) as f64 * 0.1
+        }
+      })
+      .collect::<Vec<_>>();
+    let config = ChunkConfig::default()
+      .with_mode_spec(ModeSpec::TryFloatMult(0.1))
+      .with_delta_spec(DeltaSpec::None);
+    let fc = FileCompressor::default();
+    let cc = fc.chunk_compressor(&nums, &config)?;
+    let meta = cc.meta();
+    assert_eq!(meta.mode, Mode::float_mult(0.1_f64));
+    let bins = meta.bins_as_numbers::<f64>().unwrap();
+    let min = bins[0].0;
+    assert!((min - -3.0).abs() < 1e-9, "{}", min);
+    assert!(min <= -3.0);
+    assert!(bins.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    assert!(bins.iter().all(|&(x, _)| x <= 4.9 + 1e-9));
+    Ok(())
+  }
+
+  #[test]
+  fn bins_as_numbers_compressed() -> PcoResult<()> {
+    let nums = (0..1000)
+      .map(|i| if i % 2 == 0 { -3.5 } else { 1000.0 + i as f32 })
+      .collect::<Vec<_>>();
+    let config = ChunkConfig::classic();
+    let fc = FileCompressor::default();
+    let cc = fc.chunk_compressor(&nums, &config)?;
+    let bins = cc.meta().bins_as_numbers::<f32>().unwrap();
+    assert_eq!(bins[0].0, -3.5);
+    assert!(bins.len() >= 2);
+    assert!(bins.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    Ok(())
+  }
 }
@@ -1,17 +1,23 @@
 use crate::bit_reader::BitReaderBuilder;
+#[cfg(feature = "compress")]
 use crate::bit_writer::BitWriter;
 use crate::bits::bits_to_encode_offset_bits;
+#[cfg(feature = "compress")]
+use crate::constants::ANS_INTERLEAVING;
 use crate::constants::{
-  Bitlen, Weight, ANS_INTERLEAVING, BITS_TO_ENCODE_ANS_SIZE_LOG, BITS_TO_ENCODE_N_BINS,
-  FULL_BIN_BATCH_SIZE, MAX_ANS_BITS,
+  Bitlen, Weight, BITS_TO_ENCODE_ANS_SIZE_LOG, BITS_TO_ENCODE_N_BINS, FULL_BIN_BATCH_SIZE,
+  MAX_ANS_BITS,
 };
 use crate::data_types::{Latent, LatentType};
 use crate::errors::{PcoError, PcoResult};
 use crate::macros::match_latent_enum;
 use crate::metadata::dyn_bins::DynBins;
-use crate::metadata::{Bin, DeltaEncoding};
+use crate::metadata::Bin;
+#[cfg(feature = "compress")]
+use crate::metadata::DeltaEncoding;
 use better_io::BetterBufRead;
 use std::cmp::min;
+#[cfg(feature = "compress")]
 use std::io::Write;
 
 unsafe fn read_bin_batch<L: Latent, R: BetterBufRead>(
@@ -48,6 +54,7 @@ unsafe fn read_bin_batch<L: Latent, R: BetterBufRead>(
   Ok(())
 }
 
+#[cfg(feature = "compress")]
 unsafe fn write_bins<L: Latent, W: Write>(
   bins: &[Bin<L>],
   ans_size_log: Bitlen,
@@ -74,7 +81,7 @@ unsafe fn write_bins<L: Latent, W: Write>(
 /// corresponding to the actual numbers' (or deltas') bins.
 ///
 /// This is mainly useful for inspecting how compression was done.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct ChunkLatentVarMeta {
   /// The log2 of the number of the number of states in this chunk's tANS
   /// table.
@@ -144,6 +151,7 @@ impl ChunkLatentVarMeta {
     Ok(Self { bins, ans_size_log })
   }
 
+  #[cfg(feature = "compress")]
   pub(crate) unsafe fn write_to<W: Write>(&self, writer: &mut BitWriter<W>) -> PcoResult<()> {
     writer.write_bitlen(
       self.ans_size_log,
@@ -156,6 +164,7 @@ impl ChunkLatentVarMeta {
     Ok(())
   }
 
+  #[cfg(feature = "compress")]
   pub(crate) fn exact_bit_size(&self) -> usize {
     let total_bin_size = match_latent_enum!(
       &self.bins,
@@ -166,6 +175,7 @@ impl ChunkLatentVarMeta {
     BITS_TO_ENCODE_ANS_SIZE_LOG as usize + BITS_TO_ENCODE_N_BINS as usize + total_bin_size
   }
 
+  #[cfg(feature = "compress")]
   pub(crate) fn exact_page_meta_bit_size(&self, delta_encoding: DeltaEncoding) -> usize {
     let bits_per_latent = match_latent_enum!(
       &self.bins,
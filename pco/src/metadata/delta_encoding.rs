@@ -1,4 +1,5 @@
 use crate::bit_reader::BitReader;
+#[cfg(feature = "compress")]
 use crate::bit_writer::BitWriter;
 use crate::constants::{
   Bitlen, BITS_TO_ENCODE_DELTA_ENCODING_ORDER, BITS_TO_ENCODE_DELTA_ENCODING_VARIANT,
@@ -9,9 +10,10 @@ use crate::errors::{PcoError, PcoResult};
 use crate::metadata::delta_encoding::DeltaEncoding::*;
 use crate::metadata::format_version::FormatVersion;
 use crate::metadata::per_latent_var::LatentVarKey;
+#[cfg(feature = "compress")]
 use std::io::Write;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct DeltaConsecutiveConfig {
   /// The number of times consecutive deltas were taken.
   /// For instance, 2nd order delta encoding is delta-of-deltas.
@@ -21,7 +23,7 @@ pub struct DeltaConsecutiveConfig {
   pub secondary_uses_delta: bool,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct DeltaLookbackConfig {
   /// The log2 of the number of latents explicitly stored in page metadata
   /// to prepopulate the lookback window.
@@ -49,7 +51,7 @@ impl DeltaLookbackConfig {
 /// greatly reducing the entropy of the data distribution in some cases.
 /// This stage of processing happens after applying the
 /// [`Mode`][crate::metadata::Mode] during compression.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[non_exhaustive]
 pub enum DeltaEncoding {
   /// No delta encoding; the values are encoded as-is.
@@ -134,7 +136,30 @@ impl DeltaEncoding {
     Ok(res)
   }
 
-  pub(crate) unsafe fn write_to<W: Write>(&self, writer: &mut BitWriter<W>) {
+  #[cfg(feature = "compress")]
+  unsafe fn write_to_pre_v3<W: Write>(&self, writer: &mut BitWriter<W>) {
+    let order = match self {
+      None => 0,
+      Consecutive(config) if !config.secondary_uses_delta => config.order,
+      _ => unreachable!(
+        "delta encoding {:?} is not supported before format version 3",
+        self
+      ),
+    };
+    writer.write_usize(order, BITS_TO_ENCODE_DELTA_ENCODING_ORDER);
+  }
+
+  #[cfg(feature = "compress")]
+  pub(crate) unsafe fn write_to<W: Write>(
+    &self,
+    version: &FormatVersion,
+    writer: &mut BitWriter<W>,
+  ) {
+    if !version.supports_delta_variants() {
+      self.write_to_pre_v3(writer);
+      return;
+    }
+
     let variant = match self {
       None => 0,
       Consecutive(_) => 1,
@@ -187,6 +212,21 @@ impl DeltaEncoding {
     }
   }
 
+  #[cfg(feature = "compress")]
+  pub(crate) fn with_secondary_uses_delta(self, secondary_uses_delta: bool) -> Self {
+    match self {
+      None => None,
+      Consecutive(config) => Consecutive(DeltaConsecutiveConfig {
+        secondary_uses_delta,
+        ..config
+      }),
+      Lookback(config) => Lookback(DeltaLookbackConfig {
+        secondary_uses_delta,
+        ..config
+      }),
+    }
+  }
+
   pub(crate) fn for_latent_var(self, key: LatentVarKey) -> DeltaEncoding {
     if self.applies_to_latent_var(key) {
       self
@@ -203,7 +243,12 @@ impl DeltaEncoding {
     }
   }
 
-  pub(crate) fn exact_bit_size(&self) -> Bitlen {
+  #[cfg(feature = "compress")]
+  pub(crate) fn exact_bit_size(&self, version: &FormatVersion) -> Bitlen {
+    if !version.supports_delta_variants() {
+      return BITS_TO_ENCODE_DELTA_ENCODING_ORDER;
+    }
+
     let payload_bits = match self {
       None => 0,
       // For nontrivial encodings, we have a +1 bit for whether the
@@ -219,35 +264,38 @@ impl DeltaEncoding {
 mod tests {
   use crate::bit_writer::BitWriter;
   use crate::metadata::delta_encoding::{DeltaConsecutiveConfig, DeltaLookbackConfig};
+  use crate::metadata::format_version::FormatVersion;
   use crate::metadata::DeltaEncoding;
 
-  fn check_bit_size(encoding: DeltaEncoding) {
+  fn check_bit_size(encoding: DeltaEncoding, version: FormatVersion) {
     let mut bytes = Vec::new();
     let mut writer = BitWriter::new(&mut bytes, 100);
     unsafe {
-      encoding.write_to(&mut writer);
+      encoding.write_to(&version, &mut writer);
     }
     assert_eq!(
-      encoding.exact_bit_size() as usize,
+      encoding.exact_bit_size(&version) as usize,
       writer.bit_idx(),
     );
   }
 
   #[test]
   fn test_bit_size() {
-    check_bit_size(DeltaEncoding::None);
-    check_bit_size(DeltaEncoding::Consecutive(
-      DeltaConsecutiveConfig {
-        order: 3,
-        secondary_uses_delta: false,
-      },
-    ));
-    check_bit_size(DeltaEncoding::Lookback(
-      DeltaLookbackConfig {
+    let consecutive = DeltaEncoding::Consecutive(DeltaConsecutiveConfig {
+      order: 3,
+      secondary_uses_delta: false,
+    });
+    for version in [FormatVersion(2), FormatVersion::default()] {
+      check_bit_size(DeltaEncoding::None, version);
+      check_bit_size(consecutive, version);
+    }
+    check_bit_size(
+      DeltaEncoding::Lookback(DeltaLookbackConfig {
         window_n_log: 8,
         state_n_log: 1,
         secondary_uses_delta: true,
-      },
-    ));
+      }),
+      FormatVersion::default(),
+    );
   }
 }
@@ -3,6 +3,6 @@ use crate::macros::define_latent_enum;
 use crate::metadata::bins::Bins;
 
 define_latent_enum!(
-  #[derive(Clone, Debug, PartialEq, Eq)]
+  #[derive(Clone, Debug, PartialEq, Eq, Hash)]
   pub DynBins(Bins)
 );
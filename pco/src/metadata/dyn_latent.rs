@@ -1,18 +1,24 @@
 use crate::bit_reader::BitReader;
+#[cfg(feature = "compress")]
 use crate::bit_writer::BitWriter;
+#[cfg(feature = "compress")]
 use crate::constants::Bitlen;
 use crate::data_types::Latent;
-use crate::macros::{define_latent_enum, match_latent_enum};
+use crate::macros::define_latent_enum;
+#[cfg(feature = "compress")]
+use crate::macros::match_latent_enum;
+#[cfg(feature = "compress")]
 use std::io::Write;
 
 type Single<L> = L;
 
 define_latent_enum!(
-  #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+  #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
   pub DynLatent(Single)
 );
 
 impl DynLatent {
+  #[cfg(feature = "compress")]
   pub(crate) fn bits(&self) -> Bitlen {
     match_latent_enum!(
       &self,
@@ -26,6 +32,7 @@ impl DynLatent {
     DynLatent::new(reader.read_uint::<L>(L::BITS)).unwrap()
   }
 
+  #[cfg(feature = "compress")]
   pub(crate) unsafe fn write_uncompressed_to<W: Write>(&self, writer: &mut BitWriter<W>) {
     match_latent_enum!(
       &self,
@@ -1,15 +1,20 @@
 use crate::bit_reader::BitReader;
+#[cfg(feature = "compress")]
 use crate::bit_writer::BitWriter;
 use crate::data_types::Latent;
-use crate::macros::{define_latent_enum, match_latent_enum};
+use crate::macros::define_latent_enum;
+#[cfg(feature = "compress")]
+use crate::macros::match_latent_enum;
+#[cfg(feature = "compress")]
 use std::io::Write;
 
 define_latent_enum!(
-  #[derive(Clone, Debug, PartialEq, Eq)]
+  #[derive(Clone, Debug, PartialEq, Eq, Hash)]
   pub DynLatents(Vec)
 );
 
 impl DynLatents {
+  #[cfg(feature = "compress")]
   pub(crate) fn len(&self) -> usize {
     match_latent_enum!(
       self,
@@ -28,6 +33,7 @@ impl DynLatents {
     DynLatents::new(latents).unwrap()
   }
 
+  #[cfg(feature = "compress")]
   pub(crate) unsafe fn write_uncompressed_to<W: Write>(&self, writer: &mut BitWriter<W>) {
     match_latent_enum!(
       &self,
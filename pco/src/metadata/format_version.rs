@@ -1,6 +1,8 @@
+#[cfg(feature = "compress")]
 use std::io::Write;
 
 use crate::bit_reader::BitReader;
+#[cfg(feature = "compress")]
 use crate::bit_writer::BitWriter;
 use crate::constants::CURRENT_FORMAT_VERSION;
 use crate::errors::{PcoError, PcoResult};
@@ -15,7 +17,7 @@ use crate::errors::{PcoError, PcoResult};
 /// You will not need to manually instantiate this.
 /// However, in some circumstances you may want to inspect this during
 /// decompression.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct FormatVersion(pub u8);
 
 impl Default for FormatVersion {
@@ -25,6 +27,18 @@ impl Default for FormatVersion {
 }
 
 impl FormatVersion {
+  #[cfg(feature = "compress")]
+  pub(crate) fn new(version: u8) -> PcoResult<Self> {
+    if version > CURRENT_FORMAT_VERSION {
+      return Err(PcoError::invalid_argument(format!(
+        "format version ({}) exceeds max supported ({})",
+        version, CURRENT_FORMAT_VERSION,
+      )));
+    }
+
+    Ok(Self(version))
+  }
+
   pub(crate) fn read_from(reader: &mut BitReader) -> PcoResult<Self> {
     let version = reader.read_aligned_bytes(1)?[0];
     if version > CURRENT_FORMAT_VERSION {
@@ -37,8 +51,8 @@ impl FormatVersion {
     Ok(Self(version))
   }
 
+  #[cfg(feature = "compress")]
   pub(crate) fn write_to<W: Write>(&self, writer: &mut BitWriter<W>) -> PcoResult<usize> {
-    // in the future, we may want to allow the user to encode with their choice of a recent version
     writer.write_aligned_bytes(&[self.0])?;
     Ok(1)
   }
@@ -47,7 +61,26 @@ impl FormatVersion {
     self.0 == 0
   }
 
+  #[cfg(feature = "compress")]
+  pub(crate) fn supports_float_quant(&self) -> bool {
+    self.0 >= 2
+  }
+
+  #[cfg(feature = "compress")]
+  pub(crate) fn supports_16_bit_types(&self) -> bool {
+    self.0 >= 2
+  }
+
   pub(crate) fn supports_delta_variants(&self) -> bool {
     self.0 >= 3
   }
+
+  #[cfg(feature = "compress")]
+  pub(crate) fn supports_8_bit_types(&self) -> bool {
+    self.0 >= 4
+  }
+
+  pub(crate) fn supports_lossy_float_mult(&self) -> bool {
+    self.0 >= 4
+  }
 }
@@ -1,13 +1,17 @@
 use crate::bit_reader::BitReader;
+#[cfg(feature = "compress")]
 use crate::bit_writer::BitWriter;
 use crate::constants::{Bitlen, BITS_TO_ENCODE_MODE_VARIANT, BITS_TO_ENCODE_QUANTIZE_K};
-use crate::data_types::{Float, LatentType};
+#[cfg(feature = "compress")]
+use crate::data_types::Float;
+use crate::data_types::LatentType;
 use crate::errors::{PcoError, PcoResult};
 use crate::macros::match_latent_enum;
 use crate::metadata::dyn_latent::DynLatent;
 use crate::metadata::format_version::FormatVersion;
 use crate::metadata::Mode::*;
 use std::fmt::Debug;
+#[cfg(feature = "compress")]
 use std::io::Write;
 
 // Internally, here's how we should model each mode:
@@ -24,6 +28,9 @@ use std::io::Write;
 //   whose outputs get multiplied by the base and perturbed by floating point
 //   errors.
 //
+// FloatMultLossy: Like FloatMult, but the user has opted to discard the
+//   floating point errors, so only the smooth distribution remains.
+//
 // FloatQuant: The data is generated by first drawing from a smooth distribution
 //   on low-precision floats, then widening the result by adding
 //   less-significant bits drawn from a second, very low-entropy distribution
@@ -45,7 +52,7 @@ use std::io::Write;
 /// convey the correct intuition without dealing with implementation
 /// complexities.
 /// Slightly more rigorous formulas are in format.md.
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 #[non_exhaustive]
 pub enum Mode {
   /// Represents each number as a single latent: itself.
@@ -66,6 +73,9 @@ pub enum Mode {
   /// Only applies to floats.
   ///
   /// Formula: `num = mode.base * mult + adjustment ULPs`
+  ///
+  /// The base is stored as its latent bit pattern, so equality and hashing
+  /// compare bases bitwise (e.g. `0.0` and `-0.0` differ).
   FloatMult(DynLatent),
   /// Given a number of bits `k`, represents each number as two latents:
   /// quantums (effectively the first `TYPE_SIZE - k` bits) and an ULPs
@@ -76,6 +86,17 @@ pub enum Mode {
   /// Formula: `num = from_bits(quantums << k + adjustment)`
   /// (warning: this formula is especially simplified)
   FloatQuant(Bitlen),
+  /// Given a float `base`, represents each number as a single latent: a
+  /// multiplier on the base.
+  ///
+  /// This is the lossy counterpart of `FloatMult`, written only when
+  /// compressing with
+  /// [`TryFloatMultLossy`][crate::ModeSpec::TryFloatMultLossy]; any precision
+  /// finer than the base was discarded.
+  /// Only applies to floats.
+  ///
+  /// Formula: `num = mode.base * mult`
+  FloatMultLossy(DynLatent),
 }
 
 impl Mode {
@@ -113,6 +134,10 @@ impl Mode {
         let k = reader.read_bitlen(BITS_TO_ENCODE_QUANTIZE_K);
         FloatQuant(k)
       }
+      4 if version.supports_lossy_float_mult() => {
+        let base_latent = read_latent(reader);
+        FloatMultLossy(base_latent)
+      }
       value => {
         return Err(PcoError::corruption(format!(
           "unknown mode value {}",
@@ -123,12 +148,14 @@ impl Mode {
     Ok(mode)
   }
 
+  #[cfg(feature = "compress")]
   pub(crate) unsafe fn write_to<W: Write>(&self, writer: &mut BitWriter<W>) {
     let mode_value = match self {
       Classic => 0,
       IntMult(_) => 1,
       FloatMult { .. } => 2,
       FloatQuant { .. } => 3,
+      FloatMultLossy(_) => 4,
     };
     writer.write_bitlen(mode_value, BITS_TO_ENCODE_MODE_VARIANT);
     match self {
@@ -136,7 +163,7 @@ impl Mode {
       IntMult(base) => {
         base.write_uncompressed_to(writer);
       }
-      FloatMult(base_latent) => {
+      FloatMult(base_latent) | FloatMultLossy(base_latent) => {
         base_latent.write_uncompressed_to(writer);
       }
       &FloatQuant(k) => {
@@ -147,25 +174,32 @@ impl Mode {
 
   pub(crate) fn primary_latent_type(&self, number_latent_type: LatentType) -> LatentType {
     match self {
-      Classic | FloatMult(_) | FloatQuant(_) | IntMult(_) => number_latent_type,
+      Classic | FloatMult(_) | FloatQuant(_) | IntMult(_) | FloatMultLossy(_) => number_latent_type,
     }
   }
 
   pub(crate) fn secondary_latent_type(&self, number_latent_type: LatentType) -> Option<LatentType> {
     match self {
-      Classic => None,
+      Classic | FloatMultLossy(_) => None,
       FloatMult(_) | FloatQuant(_) | IntMult(_) => Some(number_latent_type),
     }
   }
 
+  #[cfg(feature = "compress")]
   pub(crate) fn float_mult<F: Float>(base: F) -> Self {
     FloatMult(DynLatent::new(base.to_latent_ordered()).unwrap())
   }
 
+  #[cfg(feature = "compress")]
+  pub(crate) fn float_mult_lossy<F: Float>(base: F) -> Self {
+    FloatMultLossy(DynLatent::new(base.to_latent_ordered()).unwrap())
+  }
+
+  #[cfg(feature = "compress")]
   pub(crate) fn exact_bit_size(&self) -> Bitlen {
     let payload_bits = match self {
       Classic => 0,
-      IntMult(base) | FloatMult(base) => base.bits(),
+      IntMult(base) | FloatMult(base) | FloatMultLossy(base) => base.bits(),
       FloatQuant(_) => BITS_TO_ENCODE_QUANTIZE_K,
     };
     BITS_TO_ENCODE_MODE_VARIANT + payload_bits
@@ -199,5 +233,8 @@ mod tests {
       DynLatent::new(77_u32).unwrap(),
     ));
     check_bit_size(Mode::FloatQuant(7));
+    check_bit_size(Mode::FloatMultLossy(
+      DynLatent::new(77_u32).unwrap(),
+    ));
   }
 }
@@ -1,7 +1,10 @@
+#[cfg(feature = "compress")]
 use std::io::Write;
 
 use crate::bit_reader::BitReader;
+#[cfg(feature = "compress")]
 use crate::bit_writer::BitWriter;
+#[cfg(feature = "compress")]
 use crate::constants::Bitlen;
 use crate::errors::PcoResult;
 use crate::metadata::page_latent_var::PageLatentVarMeta;
@@ -13,12 +16,13 @@ use crate::metadata::ChunkMeta;
 // Importantly, `n` and `compressed_body_size` might come from either the
 // chunk metadata parsing step (standalone mode) OR from the wrapping format
 // (wrapped mode).
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct PageMeta {
   pub per_latent_var: PerLatentVar<PageLatentVarMeta>,
 }
 
 impl PageMeta {
+  #[cfg(feature = "compress")]
   pub unsafe fn write_to<W: Write>(
     &self,
     ans_size_logs: PerLatentVar<Bitlen>,
@@ -1,20 +1,23 @@
 use crate::ans::AnsState;
 use crate::bit_reader::BitReader;
+#[cfg(feature = "compress")]
 use crate::bit_writer::BitWriter;
 use crate::constants::{Bitlen, ANS_INTERLEAVING};
 use crate::data_types::LatentType;
 use crate::delta::DeltaState;
 use crate::macros::match_latent_enum;
 use crate::metadata::dyn_latents::DynLatents;
+#[cfg(feature = "compress")]
 use std::io::Write;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct PageLatentVarMeta {
   pub delta_state: DeltaState,
   pub ans_final_state_idxs: [AnsState; ANS_INTERLEAVING],
 }
 
 impl PageLatentVarMeta {
+  #[cfg(feature = "compress")]
   pub unsafe fn write_to<W: Write>(&self, ans_size_log: Bitlen, writer: &mut BitWriter<W>) {
     self.delta_state.write_uncompressed_to(writer);
 
@@ -3,7 +3,7 @@ use std::iter::Sum;
 
 /// The possible kinds of latent variables present in a chunk.
 ///
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum LatentVarKey {
   /// Used by certain types of
   /// [delta encodings][crate::metadata::DeltaEncoding]. E.g. lookback delta
@@ -20,7 +20,7 @@ pub enum LatentVarKey {
 }
 
 /// A generic container holding a value for each applicable latent variable.
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct PerLatentVar<T> {
   pub delta: Option<T>,
   pub primary: T,
@@ -85,6 +85,7 @@ impl<T> PerLatentVar<T> {
     }
   }
 
+  #[cfg(feature = "compress")]
   pub(crate) fn as_mut(&mut self) -> PerLatentVar<&mut T> {
     PerLatentVar {
       delta: self.delta.as_mut(),
@@ -93,6 +94,7 @@ impl<T> PerLatentVar<T> {
     }
   }
 
+  #[cfg(feature = "compress")]
   pub(crate) fn get(&self, key: LatentVarKey) -> Option<&T> {
     match key {
       LatentVarKey::Delta => self.delta.as_ref(),
@@ -18,6 +18,7 @@ pub const fn calc_max_u64s(precision: Bitlen) -> usize {
   }
 }
 
+#[cfg(feature = "compress")]
 pub const fn calc_max_u64s_for_writing(precision: Bitlen) -> usize {
   // We need to be slightly more conservative during writing
   // due to how write_short_uints is implemented.
@@ -50,6 +51,7 @@ pub trait ReadWriteUint:
   const MAX_U64S: usize = calc_max_u64s(Self::BITS);
 
   fn from_u64(x: u64) -> Self;
+  #[cfg(feature = "compress")]
   fn to_u64(self) -> u64;
 }
 
@@ -62,6 +64,7 @@ impl ReadWriteUint for usize {
     x as Self
   }
 
+  #[cfg(feature = "compress")]
   #[inline]
   fn to_u64(self) -> u64 {
     self as u64
@@ -77,6 +80,7 @@ impl<L: Latent> ReadWriteUint for L {
     <Self as Latent>::from_u64(x)
   }
 
+  #[cfg(feature = "compress")]
   #[inline]
   fn to_u64(self) -> u64 {
     <Self as Latent>::to_u64(self)
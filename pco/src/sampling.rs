@@ -1,4 +1,6 @@
-use std::cmp::max;
+#[cfg(test)]
+use std::cell::Cell;
+use std::cmp::{max, min};
 use std::collections::HashMap;
 use std::fmt::Debug;
 
@@ -25,35 +27,66 @@ fn calc_sample_n(n: usize) -> Option<usize> {
   }
 }
 
+// how many random indices to draw and check at a time
+const SAMPLE_BATCH_SIZE: usize = 256;
+
+#[cfg(test)]
+thread_local! {
+  // how many times this thread has called choose_sample, so tests can check
+  // which configs skip sampling
+  pub static N_CHOOSE_SAMPLE_CALLS: Cell<usize> = const { Cell::new(0) };
+}
+
 #[inline(never)]
 pub fn choose_sample<T, S: Copy + Debug, Filter: Fn(&T) -> Option<S>>(
   nums: &[T],
   filter: Filter,
+  seed: u64,
 ) -> Option<Vec<S>> {
   // We can't modify the list, and copying it may be expensive, but we want to
   // sample a small fraction from it without replacement, so we keep a
   // bitpacked vector representing whether each one is used yet and just keep
   // resampling.
   // Maybe this is a bad idea, but it works for now.
+  #[cfg(test)]
+  N_CHOOSE_SAMPLE_CALLS.with(|n_calls| n_calls.set(n_calls.get() + 1));
   let target_sample_size = calc_sample_n(nums.len())?;
+  let max_iters = SAMPLING_PERSISTENCE * target_sample_size;
 
-  let mut rng = rand_xoshiro::Xoroshiro128PlusPlus::seed_from_u64(0);
+  let mut rng = rand_xoshiro::Xoroshiro128PlusPlus::seed_from_u64(seed);
   let mut visited = vec![0_u8; nums.len().div_ceil(8)];
   let mut res = Vec::with_capacity(target_sample_size);
+  let mut rand_idxs = [0_usize; SAMPLE_BATCH_SIZE];
+  let mut fresh_idxs = [0_usize; SAMPLE_BATCH_SIZE];
   let mut n_iters = 0;
-  while res.len() < target_sample_size && n_iters < SAMPLING_PERSISTENCE * target_sample_size {
-    let rand_idx = rng.next_u64() as usize % nums.len();
-    let visited_idx = rand_idx / 8;
-    let visited_bit = rand_idx % 8;
-    let mask = 1 << visited_bit;
-    let is_visited = visited[visited_idx] & mask;
-    if is_visited == 0 {
-      if let Some(x) = filter(&nums[rand_idx]) {
+  while res.len() < target_sample_size && n_iters < max_iters {
+    // We work in batches so that drawing indices and applying the filter are
+    // each tight loops. This consumes the RNG in the same order and yields
+    // the same sample as checking one index at a time would.
+    let batch_size = min(SAMPLE_BATCH_SIZE, max_iters - n_iters);
+    for rand_idx in &mut rand_idxs[..batch_size] {
+      *rand_idx = rng.next_u64() as usize % nums.len();
+    }
+
+    // visiting must stay sequential since the batch may contain duplicates
+    let mut n_fresh = 0;
+    for &rand_idx in &rand_idxs[..batch_size] {
+      let visited_idx = rand_idx / 8;
+      let mask = 1 << (rand_idx % 8);
+      fresh_idxs[n_fresh] = rand_idx;
+      n_fresh += (visited[visited_idx] & mask == 0) as usize;
+      visited[visited_idx] |= mask;
+    }
+
+    for &idx in &fresh_idxs[..n_fresh] {
+      if let Some(x) = filter(&nums[idx]) {
         res.push(x);
+        if res.len() == target_sample_size {
+          break;
+        }
       }
-      visited[visited_idx] |= mask;
     }
-    n_iters += 1;
+    n_iters += batch_size;
   }
 
   if res.len() >= MIN_SAMPLE {
@@ -119,16 +152,71 @@ mod tests {
     for i in 0..150 {
       nums.push(-i as f32);
     }
-    let mut sample = choose_sample(&nums, |&num| {
-      if num == 0.0 {
-        None
-      } else {
-        Some(num)
-      }
-    })
+    let mut sample = choose_sample(
+      &nums,
+      |&num| {
+        if num == 0.0 {
+          None
+        } else {
+          Some(num)
+        }
+      },
+      0,
+    )
     .unwrap();
     sample.sort_unstable_by(f32::total_cmp);
     assert_eq!(sample.len(), 13);
     assert_eq!(&sample[0..3], &[-147.0, -142.0, -119.0]);
   }
+
+  fn choose_sample_scalar<T, S: Copy, Filter: Fn(&T) -> Option<S>>(
+    nums: &[T],
+    filter: Filter,
+    seed: u64,
+  ) -> Option<Vec<S>> {
+    let target_sample_size = calc_sample_n(nums.len())?;
+    let mut rng = rand_xoshiro::Xoroshiro128PlusPlus::seed_from_u64(seed);
+    let mut visited = vec![false; nums.len()];
+    let mut res = Vec::new();
+    let mut n_iters = 0;
+    while res.len() < target_sample_size && n_iters < SAMPLING_PERSISTENCE * target_sample_size {
+      let rand_idx = rng.next_u64() as usize % nums.len();
+      if !visited[rand_idx] {
+        if let Some(x) = filter(&nums[rand_idx]) {
+          res.push(x);
+        }
+        visited[rand_idx] = true;
+      }
+      n_iters += 1;
+    }
+
+    if res.len() >= MIN_SAMPLE {
+      Some(res)
+    } else {
+      None
+    }
+  }
+
+  #[test]
+  fn test_choose_sample_matches_scalar() {
+    for n in [9, 10, 37, 300, 5000, 100000] {
+      let nums = (0..n).map(|i| (i * 7919) % 1013).collect::<Vec<u32>>();
+      let filters: [fn(&u32) -> Option<u32>; 3] = [
+        |&x| Some(x),
+        |&x| if x % 3 == 0 { Some(x) } else { None },
+        |&x| if x < 2 { Some(x) } else { None },
+      ];
+      for filter in filters {
+        for seed in [0, 1] {
+          assert_eq!(
+            choose_sample(&nums, filter, seed),
+            choose_sample_scalar(&nums, filter, seed),
+            "n={} seed={}",
+            n,
+            seed,
+          );
+        }
+      }
+    }
+  }
 }
@@ -2,6 +2,12 @@ use std::ptr;
 
 use crate::data_types::Latent;
 
+// Below this many latents, heapsort beats the overhead of radix sort's
+// counting passes and scratch allocation.
+const RADIX_SORT_THRESHOLD: usize = 256;
+const RADIX_BITS: u32 = 8;
+const RADIX: usize = 1 << RADIX_BITS;
+
 pub fn choose_pivot<L: Latent>(latents: &mut [L]) -> L {
   // Minimum length to choose the median-of-medians method.
   // Shorter slices use the simple median-of-three method.
@@ -167,3 +173,100 @@ pub fn heapsort<L: Latent>(latents: &mut [L]) {
     sift_down(&mut latents[..i], 0);
   }
 }
+
+#[inline]
+fn radix_digit<L: Latent>(latent: L, shift: u32) -> usize {
+  ((latent.to_u64() >> shift) as usize) % RADIX
+}
+
+fn radix_scatter<L: Latent>(src: &[L], dst: &mut [L], shift: u32, counts: &[usize; RADIX]) {
+  let mut offsets = [0_usize; RADIX];
+  let mut csum = 0;
+  for (offset, &count) in offsets.iter_mut().zip(counts.iter()) {
+    *offset = csum;
+    csum += count;
+  }
+
+  for &latent in src {
+    let digit = radix_digit(latent, shift);
+    dst[offsets[digit]] = latent;
+    offsets[digit] += 1;
+  }
+}
+
+// Least-significant-digit radix sort on bytes. Passes where every latent has
+// the same byte are skipped, which is common since latents are often small.
+fn radix_sort<L: Latent>(latents: &mut [L]) {
+  let n_passes = (L::BITS / RADIX_BITS) as usize;
+  let mut counts = vec![[0_usize; RADIX]; n_passes];
+  for &latent in latents.iter() {
+    for (pass, pass_counts) in counts.iter_mut().enumerate() {
+      pass_counts[radix_digit(latent, pass as u32 * RADIX_BITS)] += 1;
+    }
+  }
+
+  let mut scratch = vec![L::ZERO; latents.len()];
+  let mut sorted_in_scratch = false;
+  for (pass, pass_counts) in counts.iter().enumerate() {
+    if pass_counts.contains(&latents.len()) {
+      continue;
+    }
+
+    let shift = pass as u32 * RADIX_BITS;
+    if sorted_in_scratch {
+      radix_scatter(&scratch, latents, shift, pass_counts);
+    } else {
+      radix_scatter(latents, &mut scratch, shift, pass_counts);
+    }
+    sorted_in_scratch = !sorted_in_scratch;
+  }
+
+  if sorted_in_scratch {
+    latents.copy_from_slice(&scratch);
+  }
+}
+
+// Sorts the latents, using radix sort for long slices and heapsort
+// otherwise. Both guarantee good worst-case performance.
+pub fn sort<L: Latent>(latents: &mut [L]) {
+  if latents.len() >= RADIX_SORT_THRESHOLD {
+    radix_sort(latents);
+  } else {
+    heapsort(latents);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::Rng;
+  use rand_xoshiro::rand_core::SeedableRng;
+  use rand_xoshiro::Xoroshiro128PlusPlus;
+
+  use super::*;
+
+  fn check_sort<L: Latent>(latents: Vec<L>) {
+    let mut expected = latents.clone();
+    expected.sort_unstable();
+    let mut sorted = latents;
+    sort(&mut sorted);
+    assert_eq!(sorted, expected);
+  }
+
+  #[test]
+  fn test_sort_matches_std() {
+    let mut rng = Xoroshiro128PlusPlus::seed_from_u64(0);
+    for n in [
+      0,
+      1,
+      7,
+      RADIX_SORT_THRESHOLD - 1,
+      RADIX_SORT_THRESHOLD,
+      3000,
+    ] {
+      check_sort((0..n).map(|_| rng.gen::<u64>()).collect::<Vec<_>>());
+      check_sort((0..n).map(|_| rng.gen::<u32>() % 1000).collect::<Vec<_>>());
+      check_sort((0..n).map(|_| rng.gen::<u16>()).collect::<Vec<_>>());
+      check_sort(vec![77_u64 << 40; n]);
+    }
+  }
+}
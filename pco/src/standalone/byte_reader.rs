@@ -0,0 +1,173 @@
+use std::io::{self, Read};
+use std::mem;
+
+use better_io::BetterBufRead;
+
+use crate::data_types::{Latent, Number};
+use crate::errors::PcoResult;
+use crate::standalone::{ChunkDecompressor, FileDecompressor, MaybeChunkDecompressor};
+use crate::FULL_BATCH_N;
+
+#[allow(clippy::large_enum_variant)]
+enum State<T: Number, R: BetterBufRead> {
+  BetweenChunks(R),
+  InChunk(ChunkDecompressor<T, R>),
+  Finished,
+}
+
+/// Presents the numbers of a standalone .pco file as their little-endian
+/// bytes via [`Read`].
+///
+/// Numbers are decompressed a batch at a time as bytes are requested, so
+/// reads of any size (even smaller than a single number) are supported.
+///
+/// Example:
+/// ```
+/// use std::io::Read;
+/// use pco::standalone::{simple_compress, PcoByteReader};
+/// use pco::ChunkConfig;
+/// # use pco::errors::PcoResult;
+///
+/// # fn main() -> PcoResult<()> {
+/// let compressed = simple_compress(&[1_u16, 2, 3], &ChunkConfig::default())?;
+/// let mut reader = PcoByteReader::<u16, _>::new(compressed.as_slice())?;
+/// let mut bytes = Vec::new();
+/// reader.read_to_end(&mut bytes)?;
+/// assert_eq!(bytes, vec![1, 0, 2, 0, 3, 0]);
+/// # Ok(())
+/// # }
+/// ```
+pub struct PcoByteReader<T: Number, R: BetterBufRead> {
+  fd: FileDecompressor,
+  state: State<T, R>,
+  nums: Vec<T>,
+  bytes: Vec<u8>,
+  byte_idx: usize,
+}
+
+impl<T: Number, R: BetterBufRead> PcoByteReader<T, R> {
+  const NUM_BYTES: usize = T::L::BITS as usize / 8;
+
+  /// Reads the file header and returns a reader over the decompressed bytes.
+  ///
+  /// Will return an error if any corruptions, version incompatibilities, or
+  /// insufficient data are found in the header.
+  pub fn new(src: R) -> PcoResult<Self> {
+    let (fd, src) = FileDecompressor::new(src)?;
+    Ok(Self {
+      fd,
+      state: State::BetweenChunks(src),
+      nums: vec![T::default(); FULL_BATCH_N],
+      bytes: Vec::with_capacity(FULL_BATCH_N * Self::NUM_BYTES),
+      byte_idx: 0,
+    })
+  }
+
+  // Returns whether any bytes were buffered; false means we reached the end
+  // of the data.
+  fn refill(&mut self) -> PcoResult<bool> {
+    loop {
+      match mem::replace(&mut self.state, State::Finished) {
+        State::BetweenChunks(src) => match self.fd.chunk_decompressor::<T, R>(src)? {
+          MaybeChunkDecompressor::Some(cd) => self.state = State::InChunk(cd),
+          MaybeChunkDecompressor::EndOfData(_) => return Ok(false),
+        },
+        State::InChunk(mut cd) => {
+          let progress = cd.decompress(&mut self.nums)?;
+          self.state = if progress.finished {
+            State::BetweenChunks(cd.into_src())
+          } else {
+            State::InChunk(cd)
+          };
+
+          self.bytes.clear();
+          self.byte_idx = 0;
+          for &num in &self.nums[..progress.n_processed] {
+            let bits = num.transmute_to_latent().to_u64();
+            self
+              .bytes
+              .extend_from_slice(&bits.to_le_bytes()[..Self::NUM_BYTES]);
+          }
+          if !self.bytes.is_empty() {
+            return Ok(true);
+          }
+        }
+        State::Finished => return Ok(false),
+      }
+    }
+  }
+}
+
+impl<T: Number, R: BetterBufRead> Read for PcoByteReader<T, R> {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    if buf.is_empty() {
+      return Ok(0);
+    }
+
+    if self.byte_idx == self.bytes.len() && !self.refill()? {
+      return Ok(0);
+    }
+
+    let n_read = buf.len().min(self.bytes.len() - self.byte_idx);
+    buf[..n_read].copy_from_slice(&self.bytes[self.byte_idx..self.byte_idx + n_read]);
+    self.byte_idx += n_read;
+    Ok(n_read)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::standalone::{simple_compress, FileCompressor};
+  use crate::{ChunkConfig, PagingSpec};
+
+  fn check_bytes<T: Number>(reader_buf_size: usize, nums: &[T], expected: &[u8]) -> PcoResult<()> {
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(300));
+    let compressed = simple_compress(nums, &config)?;
+    let mut reader = PcoByteReader::<T, _>::new(compressed.as_slice())?;
+    let mut buf = vec![0; reader_buf_size];
+    let mut res = Vec::new();
+    loop {
+      let n_read = reader.read(&mut buf)?;
+      if n_read == 0 {
+        break;
+      }
+      res.extend_from_slice(&buf[..n_read]);
+    }
+    assert_eq!(res, expected);
+    Ok(())
+  }
+
+  #[test]
+  fn test_byte_reader() -> PcoResult<()> {
+    let nums = (0..1000).map(|i| i as f32 - 0.5).collect::<Vec<_>>();
+    let expected = nums
+      .iter()
+      .flat_map(|x| x.to_le_bytes())
+      .collect::<Vec<_>>();
+    for reader_buf_size in [1, 3, 4, 1000, 5000] {
+      check_bytes(reader_buf_size, &nums, &expected)?;
+    }
+
+    let nums = (0..1000).map(|i| i * 1000 - 77).collect::<Vec<i64>>();
+    let expected = nums
+      .iter()
+      .flat_map(|x| x.to_le_bytes())
+      .collect::<Vec<_>>();
+    check_bytes(7, &nums, &expected)?;
+    Ok(())
+  }
+
+  #[test]
+  fn test_byte_reader_empty() -> PcoResult<()> {
+    let mut compressed = Vec::new();
+    let fc = FileCompressor::default();
+    fc.write_header(&mut compressed)?;
+    fc.write_footer(&mut compressed)?;
+    let mut reader = PcoByteReader::<u32, _>::new(compressed.as_slice())?;
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    assert!(bytes.is_empty());
+    Ok(())
+  }
+}
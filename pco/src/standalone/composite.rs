@@ -0,0 +1,1216 @@
+#[cfg(feature = "compress")]
+use std::cmp::min;
+#[cfg(feature = "compress")]
+use std::collections::HashMap;
+#[cfg(feature = "compress")]
+use std::slice;
+
+#[cfg(feature = "compress")]
+use crate::chunk_config::ChunkConfig;
+use crate::data_types::{Latent, Number};
+use crate::errors::{PcoError, PcoResult};
+use crate::standalone::constants::{MAGIC_COMPOSITE_HEADER, MAGIC_HEADER};
+use crate::standalone::simple::decompress_with_rest;
+#[cfg(feature = "compress")]
+use crate::standalone::simple_compress;
+#[cfg(feature = "compress")]
+use crate::{DeltaSpec, ModeSpec, PagingSpec, DEFAULT_MAX_PAGE_N};
+
+// Composite formats combine one or more standalone files into a container.
+// A container starts with its own magic header and a byte identifying the
+// function that wrote it, followed by each part as a complete standalone
+// file.
+// Standalone files end with a termination byte, so the parts need no
+// lengths.
+
+// Identifies the function that wrote a container, so each decoder can
+// reject the others' output with a helpful error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum CompositeKind {
+  Transposed = 1,
+  Pair = 2,
+  Against = 3,
+  Sorted = 4,
+  Nullable = 5,
+  Runs = 6,
+  Fixed = 7,
+}
+
+impl CompositeKind {
+  fn from_byte(byte: u8) -> Option<Self> {
+    use CompositeKind::*;
+
+    [Transposed, Pair, Against, Sorted, Nullable, Runs, Fixed]
+      .into_iter()
+      .find(|&kind| kind as u8 == byte)
+  }
+
+  // the suffix of the simple_compress_* and simple_decompress_* functions
+  fn name(self) -> &'static str {
+    use CompositeKind::*;
+
+    match self {
+      Transposed => "transposed",
+      Pair => "pair",
+      Against => "against",
+      Sorted => "sorted",
+      Nullable => "nullable",
+      Runs => "runs",
+      Fixed => "fixed",
+    }
+  }
+}
+
+// Describes a container's kind byte for errors, given the byte if present.
+fn kind_error(kind_byte: Option<u8>, expected: &str) -> PcoError {
+  let Some(kind_byte) = kind_byte else {
+    return PcoError::insufficient_data("pco container ended before its kind byte");
+  };
+  match CompositeKind::from_byte(kind_byte) {
+    Some(kind) => PcoError::invalid_argument(format!(
+      "this is a pco container written by simple_compress_{0}, not {1}; \
+       decompress it with simple_decompress_{0}",
+      kind.name(),
+      expected,
+    )),
+    None => PcoError::compatibility(format!(
+      "unknown pco container kind byte: {}",
+      kind_byte,
+    )),
+  }
+}
+
+// Returns the error for giving a container to a function that expects a
+// standalone file.
+pub(crate) fn container_error(kind_byte: Option<u8>) -> PcoError {
+  kind_error(kind_byte, "a standalone pco file")
+}
+
+// Writes a container's header, then each part in order.
+#[cfg(feature = "compress")]
+struct CompositeWriter {
+  dst: Vec<u8>,
+}
+
+#[cfg(feature = "compress")]
+impl CompositeWriter {
+  fn new(kind: CompositeKind) -> Self {
+    let mut dst = MAGIC_COMPOSITE_HEADER.to_vec();
+    dst.push(kind as u8);
+    Self { dst }
+  }
+
+  fn write_part<T: Number>(&mut self, nums: &[T], config: &ChunkConfig) -> PcoResult<()> {
+    self.dst.extend(simple_compress(nums, config)?);
+    Ok(())
+  }
+
+  fn finish(self) -> Vec<u8> {
+    self.dst
+  }
+}
+
+// Checks a container's header, then reads each part in the order it was
+// written.
+struct CompositeReader<'a> {
+  src: &'a [u8],
+}
+
+impl<'a> CompositeReader<'a> {
+  fn new(src: &'a [u8], kind: CompositeKind) -> PcoResult<Self> {
+    let expected = format!(
+      "a container written by simple_compress_{}",
+      kind.name()
+    );
+    if src.starts_with(&MAGIC_HEADER) {
+      return Err(PcoError::invalid_argument(format!(
+        "this is a standalone pco file, not {}; decompress it with simple_decompress",
+        expected,
+      )));
+    }
+
+    let header_len = MAGIC_COMPOSITE_HEADER.len();
+    if src.len() < header_len {
+      return Err(PcoError::insufficient_data(
+        "pco container ended before its magic header",
+      ));
+    }
+    let header = &src[..header_len];
+    if header != MAGIC_COMPOSITE_HEADER {
+      return Err(PcoError::corruption(format!(
+        "magic header does not match {:?} (\"pco+\"); instead found {:?}, so this is \
+         not a pco container",
+        MAGIC_COMPOSITE_HEADER, header,
+      )));
+    }
+
+    let kind_byte = src.get(header_len).copied();
+    if kind_byte != Some(kind as u8) {
+      return Err(kind_error(kind_byte, &expected));
+    }
+    Ok(Self {
+      src: &src[header_len + 1..],
+    })
+  }
+
+  fn read_part<T: Number>(&mut self) -> PcoResult<Vec<T>> {
+    let (nums, rest) = decompress_with_rest::<T>(self.src)?;
+    self.src = rest;
+    Ok(nums)
+  }
+
+  // Reads a part that must contain exactly one number.
+  fn read_single<T: Number>(&mut self, name: &str) -> PcoResult<T> {
+    let nums = self.read_part::<T>()?;
+    match nums.as_slice() {
+      &[num] => Ok(num),
+      _ => Err(PcoError::corruption(format!(
+        "expected a single {} but found {} numbers",
+        name,
+        nums.len(),
+      ))),
+    }
+  }
+}
+
+// The config for small parts like widths, which only need to share the
+// caller's format version.
+#[cfg(feature = "compress")]
+fn scalar_config(config: &ChunkConfig) -> ChunkConfig {
+  ChunkConfig::default().with_format_version(config.format_version)
+}
+
+fn transpose<T: Number>(nums: &[T], n_cols: usize) -> Vec<T> {
+  if nums.is_empty() {
+    return Vec::new();
+  }
+
+  let n_rows = nums.len() / n_cols;
+  let mut res = Vec::with_capacity(nums.len());
+  for col_idx in 0..n_cols {
+    res.extend((0..n_rows).map(|row_idx| nums[row_idx * n_cols + col_idx]));
+  }
+  res
+}
+
+fn check_width(n: usize, width: usize) -> PcoResult<()> {
+  if width == 0 {
+    return Err(PcoError::invalid_argument(
+      "width must be positive",
+    ));
+  }
+  if n % width != 0 {
+    return Err(PcoError::invalid_argument(format!(
+      "count of numbers ({}) is not a multiple of the width ({})",
+      n, width,
+    )));
+  }
+  Ok(())
+}
+
+/// Compresses a row-major 2D array with the given width (count of columns)
+/// column by column.
+///
+/// This usually improves compression when each column is more homogeneous
+/// than each row.
+/// The width is stored alongside the data, so
+/// [`simple_decompress_transposed`] can restore the original row-major order
+/// and return the width.
+/// Will return an error if the compressor config is invalid or the count of
+/// numbers is not a multiple of the width.
+#[cfg(feature = "compress")]
+pub fn simple_compress_transposed<T: Number>(
+  nums: &[T],
+  width: usize,
+  config: &ChunkConfig,
+) -> PcoResult<Vec<u8>> {
+  check_width(nums.len(), width)?;
+  let mut writer = CompositeWriter::new(CompositeKind::Transposed);
+  writer.write_part(&[width as u64], &scalar_config(config))?;
+  writer.write_part(&transpose(nums, width), config)?;
+  Ok(writer.finish())
+}
+
+/// Takes in compressed bytes produced by [`simple_compress_transposed`] and
+/// returns the numbers in their original row-major order, along with the
+/// width.
+///
+/// Only accepts the output of [`simple_compress_transposed`]; given any other
+/// pco data, returns an error naming the function that can decompress it.
+/// Will return an error if there are any compatibility, corruption,
+/// or insufficient data issues.
+pub fn simple_decompress_transposed<T: Number>(src: &[u8]) -> PcoResult<(Vec<T>, usize)> {
+  let mut reader = CompositeReader::new(src, CompositeKind::Transposed)?;
+  let width = reader.read_single::<u64>("width")? as usize;
+  let columns = reader.read_part::<T>()?;
+  check_width(columns.len(), width).map_err(|e| PcoError::corruption(e.message))?;
+  let n_rows = columns.len() / width;
+  Ok((transpose(&columns, n_rows), width))
+}
+
+/// Compresses fixed-length records of `N` numbers each by flattening them
+/// into a single sequence of numbers.
+///
+/// The record length is stored alongside the data, so
+/// [`simple_decompress_fixed`] can check that it restores records of the
+/// same length.
+/// Will return an error if the compressor config is invalid or `N` is 0.
+#[cfg(feature = "compress")]
+pub fn simple_compress_fixed<T: Number, const N: usize>(
+  records: &[[T; N]],
+  config: &ChunkConfig,
+) -> PcoResult<Vec<u8>> {
+  if N == 0 {
+    return Err(PcoError::invalid_argument(
+      "record length must be positive",
+    ));
+  }
+
+  // arrays are laid out contiguously, so this is the same memory viewed as
+  // a flat slice
+  let nums = unsafe {
+    slice::from_raw_parts(
+      records.as_ptr() as *const T,
+      records.len() * N,
+    )
+  };
+  let mut writer = CompositeWriter::new(CompositeKind::Fixed);
+  writer.write_part(&[N as u64], &scalar_config(config))?;
+  writer.write_part(nums, config)?;
+  Ok(writer.finish())
+}
+
+/// Takes in compressed bytes produced by [`simple_compress_fixed`] and returns
+/// the fixed-length records of `N` numbers each.
+///
+/// Only accepts the output of [`simple_compress_fixed`]; given any other pco
+/// data, returns an error naming the function that can decompress it.
+/// Will return an error if `N` differs from the stored record length, or if
+/// there are any compatibility, corruption, or insufficient data issues.
+pub fn simple_decompress_fixed<T: Number, const N: usize>(src: &[u8]) -> PcoResult<Vec<[T; N]>> {
+  let mut reader = CompositeReader::new(src, CompositeKind::Fixed)?;
+  let record_len = reader.read_single::<u64>("record length")?;
+  if record_len != N as u64 {
+    return Err(PcoError::invalid_argument(format!(
+      "records were compressed with length {} but length {} was requested",
+      record_len, N,
+    )));
+  }
+
+  let nums = reader.read_part::<T>()?;
+  if nums.len() % N != 0 {
+    return Err(PcoError::corruption(format!(
+      "count of numbers ({}) is not a multiple of the record length ({})",
+      nums.len(),
+      N,
+    )));
+  }
+
+  Ok(
+    nums
+      .chunks_exact(N)
+      .map(|record| record.try_into().unwrap())
+      .collect(),
+  )
+}
+
+// We take differences in latent space so they're lossless for any type.
+// Toggling the center makes small negative differences small numbers too.
+#[cfg(feature = "compress")]
+fn latent_diff<T: Number>(base: T, num: T) -> T {
+  let diff = num
+    .to_latent_ordered()
+    .wrapping_sub(base.to_latent_ordered());
+  T::from_latent_ordered(diff.toggle_center())
+}
+
+fn add_latent_diff<T: Number>(base: T, diff: T) -> T {
+  let diff = diff.to_latent_ordered().toggle_center();
+  T::from_latent_ordered(base.to_latent_ordered().wrapping_add(diff))
+}
+
+/// **experimental** Compresses two equal-length, correlated sequences of
+/// numbers together.
+///
+/// The first sequence is compressed as usual, and the second is compressed
+/// as its differences from the first, using the same chunk boundaries.
+/// This can beat compressing them independently when the pairs are close in
+/// value, e.g. `(x, y)` coordinates or the bid and ask prices of a quote.
+/// Use [`simple_decompress_pair`] to restore both sequences.
+///
+/// Will return an error if the compressor config is invalid or the sequences
+/// have different lengths.
+#[cfg(feature = "compress")]
+pub fn simple_compress_pair<T: Number>(
+  xs: &[T],
+  ys: &[T],
+  config: &ChunkConfig,
+) -> PcoResult<Vec<u8>> {
+  if xs.len() != ys.len() {
+    return Err(PcoError::invalid_argument(format!(
+      "paired sequences must have equal lengths (were {} and {})",
+      xs.len(),
+      ys.len(),
+    )));
+  }
+
+  let mut nums = Vec::with_capacity(2 * xs.len());
+  nums.extend_from_slice(xs);
+  nums.extend(xs.iter().zip(ys).map(|(&x, &y)| latent_diff(x, y)));
+
+  let mut n_per_page = config.paging_spec.n_per_page(xs.len())?;
+  n_per_page.extend_from_within(..);
+  let config = config
+    .clone()
+    .with_paging_spec(PagingSpec::Exact(n_per_page));
+  let mut writer = CompositeWriter::new(CompositeKind::Pair);
+  writer.write_part(&nums, &config)?;
+  Ok(writer.finish())
+}
+
+/// **experimental** Takes in compressed bytes produced by
+/// [`simple_compress_pair`] and returns both sequences of numbers.
+///
+/// Only accepts the output of [`simple_compress_pair`]; given any other pco
+/// data, returns an error naming the function that can decompress it.
+/// Will return an error if there are any compatibility, corruption,
+/// or insufficient data issues.
+pub fn simple_decompress_pair<T: Number>(src: &[u8]) -> PcoResult<(Vec<T>, Vec<T>)> {
+  let mut xs = CompositeReader::new(src, CompositeKind::Pair)?.read_part::<T>()?;
+  if xs.len() % 2 != 0 {
+    return Err(PcoError::corruption(format!(
+      "paired data must have an even count of numbers (was {})",
+      xs.len(),
+    )));
+  }
+
+  let diffs = xs.split_off(xs.len() / 2);
+  let ys = xs
+    .iter()
+    .zip(diffs)
+    .map(|(&x, diff)| add_latent_diff(x, diff))
+    .collect();
+  Ok((xs, ys))
+}
+
+fn check_reference_len(n: usize, reference_n: usize) -> PcoResult<()> {
+  if n != reference_n {
+    return Err(PcoError::invalid_argument(format!(
+      "reference length ({}) does not match count of numbers ({})",
+      reference_n, n,
+    )));
+  }
+  Ok(())
+}
+
+/// Compresses numbers as their differences from a reference sequence of the
+/// same length, which is not stored.
+///
+/// This suits successive versions of the same data: if only a few numbers
+/// changed since the reference, nearly all differences are 0 and the
+/// compressed bytes are tiny.
+/// Differences are taken in latent space, so this is lossless for any type.
+/// Use [`simple_decompress_against`] with the same reference to undo this.
+///
+/// Will return an error if the compressor config is invalid or the reference
+/// has a different length.
+#[cfg(feature = "compress")]
+pub fn simple_compress_against<T: Number>(
+  nums: &[T],
+  reference: &[T],
+  config: &ChunkConfig,
+) -> PcoResult<Vec<u8>> {
+  check_reference_len(nums.len(), reference.len())?;
+  let diffs = reference
+    .iter()
+    .zip(nums)
+    .map(|(&base, &num)| latent_diff(base, num))
+    .collect::<Vec<_>>();
+  let mut writer = CompositeWriter::new(CompositeKind::Against);
+  writer.write_part(&diffs, config)?;
+  Ok(writer.finish())
+}
+
+/// Takes in compressed bytes produced by [`simple_compress_against`] and the
+/// same reference, and returns the original numbers.
+///
+/// Only accepts the output of [`simple_compress_against`]; given any other
+/// pco data, returns an error naming the function that can decompress it.
+/// Will return an error if there are any compatibility, corruption,
+/// or insufficient data issues, or if the reference length does not match
+/// the count of numbers.
+pub fn simple_decompress_against<T: Number>(src: &[u8], reference: &[T]) -> PcoResult<Vec<T>> {
+  let mut nums = CompositeReader::new(src, CompositeKind::Against)?.read_part::<T>()?;
+  check_reference_len(nums.len(), reference.len())?;
+  for (num, &base) in nums.iter_mut().zip(reference) {
+    *num = add_latent_diff(base, *num);
+  }
+  Ok(nums)
+}
+
+// A Fenwick tree over which of `0..n` are still unused, for converting
+// permutations to and from their Lehmer codes in O(n log n).
+struct UnusedIndices {
+  tree: Vec<u32>,
+}
+
+impl UnusedIndices {
+  fn new(n: usize) -> Self {
+    // every index starts unused, so each node counts its whole range
+    let tree = (1..=n).map(|i| (i & i.wrapping_neg()) as u32).collect();
+    Self { tree }
+  }
+
+  #[cfg(feature = "compress")]
+  fn count_below(&self, idx: usize) -> u32 {
+    let mut res = 0;
+    let mut i = idx;
+    while i > 0 {
+      res += self.tree[i - 1];
+      i &= i - 1;
+    }
+    res
+  }
+
+  // Returns the unused index with exactly `rank` unused indices below it, or
+  // `n` if there are not that many.
+  fn find_by_rank(&self, rank: u32) -> usize {
+    let n = self.tree.len();
+    let mut res = 0;
+    let mut remaining = rank;
+    let mut step = if n == 0 { 0 } else { 1 << n.ilog2() };
+    while step > 0 {
+      if res + step <= n && self.tree[res + step - 1] <= remaining {
+        res += step;
+        remaining -= self.tree[res - 1];
+      }
+      step >>= 1;
+    }
+    res
+  }
+
+  fn remove(&mut self, idx: usize) {
+    let mut i = idx + 1;
+    while i <= self.tree.len() {
+      self.tree[i - 1] -= 1;
+      i += i & i.wrapping_neg();
+    }
+  }
+}
+
+// The Lehmer code's `i`th entry is uniform over `0..n - i`, so each page takes
+// a quarter of what remains, letting each chunk's bins fit a range that
+// shrinks by at most a quarter.
+// Smaller pages fit the ranges more closely but cost more chunk metadata.
+#[cfg(feature = "compress")]
+fn lehmer_n_per_page(n: usize) -> Vec<usize> {
+  const MIN_PAGE_N: usize = 1 << 8;
+  let mut res = Vec::new();
+  let mut remaining = n;
+  while remaining > MIN_PAGE_N {
+    let page_n = min(remaining / 4, DEFAULT_MAX_PAGE_N);
+    res.push(page_n);
+    remaining -= page_n;
+  }
+  if remaining > 0 {
+    res.push(remaining);
+  }
+  res
+}
+
+/// Sorts the numbers and compresses them along with the permutation needed
+/// to restore their original order.
+///
+/// Floats are sorted by their total ordering.
+/// Sorted data often compresses far better.
+/// The permutation is stored after the sorted numbers as its Lehmer code,
+/// whose `i`th entry is the rank of the `i`th sorted number's original index
+/// among the indices not yet used.
+/// For a random permutation, this takes close to the optimal `log2(n!) / n`
+/// bits per number, so sorting only pays off when it saves more than that.
+/// Use [`simple_decompress_sorted`] to undo this.
+///
+/// Will return an error if the compressor config is invalid or there are more
+/// than `u32::MAX` numbers.
+#[cfg(feature = "compress")]
+pub fn simple_compress_sorted<T: Number>(nums: &[T], config: &ChunkConfig) -> PcoResult<Vec<u8>> {
+  if nums.len() > u32::MAX as usize {
+    return Err(PcoError::invalid_argument(format!(
+      "cannot sort more than {} numbers (was {})",
+      u32::MAX,
+      nums.len(),
+    )));
+  }
+
+  let mut permutation = (0..nums.len() as u32).collect::<Vec<_>>();
+  permutation.sort_by_key(|&i| nums[i as usize].to_latent_ordered());
+  let sorted = permutation
+    .iter()
+    .map(|&i| nums[i as usize])
+    .collect::<Vec<_>>();
+  let mut writer = CompositeWriter::new(CompositeKind::Sorted);
+  writer.write_part(&sorted, config)?;
+
+  let mut unused = UnusedIndices::new(nums.len());
+  let lehmer_code = permutation
+    .iter()
+    .map(|&i| {
+      let i = i as usize;
+      let rank = unused.count_below(i);
+      unused.remove(i);
+      rank
+    })
+    .collect::<Vec<_>>();
+  // the code is nearly uniform within each page, so detecting modes or delta
+  // encodings would only waste time
+  let lehmer_config = scalar_config(config)
+    .with_compression_level(config.compression_level)
+    .with_mode_spec(ModeSpec::Classic)
+    .with_delta_spec(DeltaSpec::None)
+    .with_paging_spec(PagingSpec::Exact(lehmer_n_per_page(
+      nums.len(),
+    )));
+  writer.write_part(&lehmer_code, &lehmer_config)?;
+  Ok(writer.finish())
+}
+
+/// Takes in compressed bytes produced by [`simple_compress_sorted`] and
+/// returns the numbers in their original order.
+///
+/// Only accepts the output of [`simple_compress_sorted`]; given any other pco
+/// data, returns an error naming the function that can decompress it.
+/// Will return an error if there are any compatibility, corruption,
+/// or insufficient data issues.
+pub fn simple_decompress_sorted<T: Number>(src: &[u8]) -> PcoResult<Vec<T>> {
+  let mut reader = CompositeReader::new(src, CompositeKind::Sorted)?;
+  let sorted = reader.read_part::<T>()?;
+  let lehmer_code = reader.read_part::<u32>()?;
+  let n = sorted.len();
+  if lehmer_code.len() != n {
+    return Err(PcoError::corruption(format!(
+      "permutation length ({}) does not match count of numbers ({})",
+      lehmer_code.len(),
+      n,
+    )));
+  }
+
+  let mut res = vec![T::default(); n];
+  let mut unused = UnusedIndices::new(n);
+  for (&num, &rank) in sorted.iter().zip(&lehmer_code) {
+    let i = unused.find_by_rank(rank);
+    if i >= n {
+      return Err(PcoError::corruption(format!(
+        "permutation rank {} exceeded the count of unused indices",
+        rank,
+      )));
+    }
+    unused.remove(i);
+    res[i] = num;
+  }
+  Ok(res)
+}
+
+// Run lengths of the validity, alternating between valid and null runs and
+// always starting with a (possibly empty) valid run.
+#[cfg(feature = "compress")]
+fn validity_runs(validity: &[bool]) -> Vec<u64> {
+  let mut runs = Vec::new();
+  let mut current_is_valid = true;
+  let mut current_run = 0;
+  for &is_valid in validity {
+    if is_valid != current_is_valid {
+      runs.push(current_run);
+      current_is_valid = is_valid;
+      current_run = 0;
+    }
+    current_run += 1;
+  }
+  runs.push(current_run);
+  runs
+}
+
+// Writes the validity runs, then the non-null numbers.
+// The caller must ensure the validity has one entry per number.
+#[cfg(feature = "compress")]
+fn write_nullable_parts<T: Number>(
+  writer: &mut CompositeWriter,
+  nums: &[T],
+  validity: &[bool],
+  config: &ChunkConfig,
+) -> PcoResult<()> {
+  let runs_config = scalar_config(config).with_compression_level(config.compression_level);
+  writer.write_part(&validity_runs(validity), &runs_config)?;
+
+  let valid_nums = nums
+    .iter()
+    .zip(validity)
+    .filter_map(|(&num, &is_valid)| is_valid.then_some(num))
+    .collect::<Vec<_>>();
+  writer.write_part(&valid_nums, config)
+}
+
+fn read_nullable_parts<T: Number>(reader: &mut CompositeReader) -> PcoResult<(Vec<T>, Vec<bool>)> {
+  let runs = reader.read_part::<u64>()?;
+  let valid_nums = reader.read_part::<T>()?;
+
+  // Run lengths come straight from the input, so we validate them before
+  // allocating anything based on them.
+  let mut n = 0_usize;
+  let mut n_valid = 0_usize;
+  for (run_idx, &run) in runs.iter().enumerate() {
+    n = usize::try_from(run)
+      .ok()
+      .and_then(|run| n.checked_add(run))
+      .ok_or_else(|| PcoError::corruption("validity run lengths overflow"))?;
+    if run_idx % 2 == 0 {
+      // can't overflow, since the valid runs sum to at most n
+      n_valid += run as usize;
+    }
+  }
+  if n_valid != valid_nums.len() {
+    return Err(PcoError::corruption(format!(
+      "count of non-null numbers ({}) does not match validity ({})",
+      valid_nums.len(),
+      n_valid,
+    )));
+  }
+
+  let too_many = |_| {
+    PcoError::corruption(format!(
+      "validity has too many numbers ({})",
+      n
+    ))
+  };
+  let mut nums = Vec::new();
+  nums.try_reserve_exact(n).map_err(too_many)?;
+  let mut validity = Vec::new();
+  validity.try_reserve_exact(n).map_err(too_many)?;
+  let mut valid_nums = valid_nums.into_iter();
+  for (run_idx, &run) in runs.iter().enumerate() {
+    let run = run as usize;
+    let is_valid = run_idx % 2 == 0;
+    if is_valid {
+      nums.extend(valid_nums.by_ref().take(run));
+    } else {
+      nums.resize(nums.len() + run, T::default());
+    }
+    validity.resize(validity.len() + run, is_valid);
+  }
+  Ok((nums, validity))
+}
+
+/// Compresses numbers with nulls, given as a validity flag for each number
+/// (`true` for non-null), without needing a sentinel value.
+///
+/// Null numbers are omitted from the compressed values, and the validity is
+/// run-length encoded and compressed separately, ahead of the values.
+/// The config applies to the non-null values, except that the validity is
+/// always compressed with the default mode and delta encoding.
+/// Use [`simple_decompress_nullable`] to undo this.
+///
+/// Will return an error if the compressor config is invalid or the validity
+/// length does not match the number of numbers.
+#[cfg(feature = "compress")]
+pub fn simple_compress_nullable<T: Number>(
+  nums: &[T],
+  validity: &[bool],
+  config: &ChunkConfig,
+) -> PcoResult<Vec<u8>> {
+  if nums.len() != validity.len() {
+    return Err(PcoError::invalid_argument(format!(
+      "validity length ({}) does not match count of numbers ({})",
+      validity.len(),
+      nums.len(),
+    )));
+  }
+
+  let mut writer = CompositeWriter::new(CompositeKind::Nullable);
+  write_nullable_parts(&mut writer, nums, validity, config)?;
+  Ok(writer.finish())
+}
+
+/// Takes in compressed bytes produced by [`simple_compress_nullable`] and
+/// returns the numbers and their validity.
+///
+/// Null numbers are filled in with the default value of `T`.
+/// Only accepts the output of [`simple_compress_nullable`] or
+/// [`simple_compress_options`]; given any other pco data, returns an error
+/// naming the function that can decompress it.
+/// Will return an error if there are any compatibility, corruption,
+/// or insufficient data issues, or if the count of non-null numbers does not
+/// match the validity.
+pub fn simple_decompress_nullable<T: Number>(src: &[u8]) -> PcoResult<(Vec<T>, Vec<bool>)> {
+  let mut reader = CompositeReader::new(src, CompositeKind::Nullable)?;
+  read_nullable_parts(&mut reader)
+}
+
+/// Compresses optional numbers, e.g. as parsed from data with missing
+/// values.
+///
+/// This is a convenience wrapper around [`simple_compress_nullable`] that
+/// splits the options into values and validity.
+/// Use [`simple_decompress_options`] to undo this.
+///
+/// Will return an error if the compressor config is invalid.
+#[cfg(feature = "compress")]
+pub fn simple_compress_options<T: Number>(
+  nums: &[Option<T>],
+  config: &ChunkConfig,
+) -> PcoResult<Vec<u8>> {
+  let validity = nums.iter().map(Option::is_some).collect::<Vec<_>>();
+  let values = nums
+    .iter()
+    .map(|num| num.unwrap_or_default())
+    .collect::<Vec<_>>();
+  simple_compress_nullable(&values, &validity, config)
+}
+
+/// Takes in compressed bytes produced by [`simple_compress_options`] (or
+/// [`simple_compress_nullable`]) and returns the optional numbers.
+///
+/// Given any other pco data, returns an error naming the function that can
+/// decompress it.
+/// Will return an error if there are any compatibility, corruption,
+/// or insufficient data issues.
+pub fn simple_decompress_options<T: Number>(src: &[u8]) -> PcoResult<Vec<Option<T>>> {
+  let (values, validity) = simple_decompress_nullable::<T>(src)?;
+  Ok(
+    values
+      .into_iter()
+      .zip(validity)
+      .map(|(value, is_valid)| is_valid.then_some(value))
+      .collect(),
+  )
+}
+
+#[cfg(feature = "compress")]
+fn most_common<T: Number>(nums: &[T]) -> T {
+  let mut counts = HashMap::new();
+  for &num in nums {
+    *counts.entry(num.to_latent_ordered()).or_insert(0_usize) += 1;
+  }
+  counts
+    .into_iter()
+    // break ties by value so the choice is deterministic
+    .max_by_key(|&(latent, count)| (count, latent))
+    .map(|(latent, _)| T::from_latent_ordered(latent))
+    .unwrap_or_default()
+}
+
+/// Compresses numbers dominated by a single value by run-length encoding
+/// that value.
+///
+/// The most common value is stored once, runs of it are run-length encoded,
+/// and only the other numbers are compressed individually (as in
+/// [`simple_compress_nullable`]).
+/// This can beat [`simple_compress`] when the most common value makes up
+/// nearly all of the numbers in long runs, since each number otherwise
+/// costs a fraction of a bit.
+/// Use [`simple_decompress_runs`] to undo this.
+///
+/// Will return an error if the compressor config is invalid.
+#[cfg(feature = "compress")]
+pub fn simple_compress_runs<T: Number>(nums: &[T], config: &ChunkConfig) -> PcoResult<Vec<u8>> {
+  let run_value = most_common(nums);
+  let run_latent = run_value.to_latent_ordered();
+  let validity = nums
+    .iter()
+    .map(|num| num.to_latent_ordered() != run_latent)
+    .collect::<Vec<_>>();
+  let mut writer = CompositeWriter::new(CompositeKind::Runs);
+  writer.write_part(&[run_value], &scalar_config(config))?;
+  write_nullable_parts(&mut writer, nums, &validity, config)?;
+  Ok(writer.finish())
+}
+
+/// Takes in compressed bytes produced by [`simple_compress_runs`] and
+/// returns the numbers.
+///
+/// Only accepts the output of [`simple_compress_runs`]; given any other pco
+/// data, returns an error naming the function that can decompress it.
+/// Will return an error if there are any compatibility, corruption,
+/// or insufficient data issues.
+pub fn simple_decompress_runs<T: Number>(src: &[u8]) -> PcoResult<Vec<T>> {
+  let mut reader = CompositeReader::new(src, CompositeKind::Runs)?;
+  let run_value = reader.read_single::<T>("run value")?;
+  let (mut nums, validity) = read_nullable_parts::<T>(&mut reader)?;
+  for (num, is_valid) in nums.iter_mut().zip(validity) {
+    if !is_valid {
+      *num = run_value;
+    }
+  }
+  Ok(nums)
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::seq::SliceRandom;
+  use rand_xoshiro::rand_core::SeedableRng;
+  use rand_xoshiro::Xoroshiro128PlusPlus;
+
+  use super::*;
+  use crate::errors::ErrorKind;
+  use crate::standalone::{peek_dtype, simple_decompress};
+
+  #[test]
+  fn test_simple_compress_transposed() -> PcoResult<()> {
+    // each column has its own scale, so columns compress better than rows
+    let width = 4;
+    let nums = (0..4000)
+      .map(|i| {
+        let (row, col) = (i / width, i % width);
+        (10_i64.pow(col as u32 * 3)) * (row as i64 % 17)
+      })
+      .collect::<Vec<_>>();
+    let config = ChunkConfig::default();
+    let compressed = simple_compress_transposed(&nums, width, &config)?;
+    assert!(compressed.len() < simple_compress(&nums, &config)?.len());
+    assert_eq!(
+      simple_decompress_transposed::<i64>(&compressed)?,
+      (nums.clone(), width)
+    );
+
+    assert!(simple_compress_transposed(&nums[1..], width, &config).is_err());
+    assert!(simple_compress_transposed(&nums, 0, &config).is_err());
+
+    let compressed = simple_compress_transposed::<i64>(&[], width, &config)?;
+    assert_eq!(
+      simple_decompress_transposed::<i64>(&compressed)?,
+      (vec![], width)
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_simple_compress_fixed() -> PcoResult<()> {
+    let records = (0..300)
+      .map(|i| [i as f32, i as f32 * 0.5, -1.0])
+      .collect::<Vec<_>>();
+    let compressed = simple_compress_fixed(&records, &ChunkConfig::default())?;
+    assert_eq!(
+      simple_decompress_fixed::<f32, 3>(&compressed)?,
+      records
+    );
+
+    // the stored record length is checked, even when it divides the count
+    for err in [
+      simple_decompress_fixed::<f32, 1>(&compressed).unwrap_err(),
+      simple_decompress_fixed::<f32, 7>(&compressed).unwrap_err(),
+    ] {
+      assert!(matches!(
+        err.kind,
+        ErrorKind::InvalidArgument
+      ));
+    }
+
+    let err = simple_compress_fixed::<f32, 0>(&[[]; 5], &ChunkConfig::default()).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+    Ok(())
+  }
+
+  #[test]
+  fn test_simple_compress_pair() -> PcoResult<()> {
+    let xs = (0..1000_i64)
+      .map(|i| (i * 7919) % 1000 * 1_000_000)
+      .collect::<Vec<_>>();
+    let ys = xs
+      .iter()
+      .enumerate()
+      .map(|(i, &x)| {
+        if i == 77 {
+          i64::MIN
+        } else {
+          x + i as i64 % 5 - 2
+        }
+      })
+      .collect::<Vec<_>>();
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(300));
+    let compressed = simple_compress_pair(&xs, &ys, &config)?;
+    let independent_size =
+      simple_compress(&xs, &config)?.len() + simple_compress(&ys, &config)?.len();
+    assert!(compressed.len() < independent_size);
+
+    let (decompressed_xs, decompressed_ys) = simple_decompress_pair::<i64>(&compressed)?;
+    assert_eq!(decompressed_xs, xs);
+    assert_eq!(decompressed_ys, ys);
+
+    assert!(simple_compress_pair(&xs, &ys[1..], &config).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_simple_compress_against() -> PcoResult<()> {
+    let reference = (0..1000)
+      .map(|i| (i as f32 * 0.37).sin() * 100.0)
+      .collect::<Vec<_>>();
+    let mut nums = reference.clone();
+    nums[3] = -0.0;
+    nums[500] += 1.0;
+    nums[999] = f32::NAN;
+    let config = ChunkConfig::default();
+    let compressed = simple_compress_against(&nums, &reference, &config)?;
+    assert!(compressed.len() < simple_compress(&nums, &config)?.len() / 10);
+
+    let decompressed = simple_decompress_against(&compressed, &reference)?;
+    let bits = |xs: &[f32]| xs.iter().map(|x| x.to_bits()).collect::<Vec<_>>();
+    assert_eq!(bits(&decompressed), bits(&nums));
+
+    assert!(simple_compress_against(&nums, &reference[1..], &config).is_err());
+    assert!(simple_decompress_against(&compressed, &reference[1..]).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_simple_compress_sorted() -> PcoResult<()> {
+    let nums = (0..1000)
+      .map(|i| ((i * 7919) % 1000) as f32 - 500.0)
+      .collect::<Vec<_>>();
+    let config = ChunkConfig::default();
+    let compressed = simple_compress_sorted(&nums, &config)?;
+    assert_eq!(
+      simple_decompress_sorted::<f32>(&compressed)?,
+      nums
+    );
+
+    // ties keep their original order
+    let nums = [3, 1, 3, 2, 1];
+    let compressed = simple_compress_sorted(&nums, &config)?;
+    assert_eq!(
+      simple_decompress_sorted::<i32>(&compressed)?,
+      nums
+    );
+
+    let crafted = |lehmer_code: Option<&[u32]>| -> PcoResult<Vec<u8>> {
+      let mut writer = CompositeWriter::new(CompositeKind::Sorted);
+      writer.write_part(&[1, 2, 3, 3], &config)?;
+      if let Some(lehmer_code) = lehmer_code {
+        writer.write_part(lehmer_code, &config)?;
+      }
+      Ok(writer.finish())
+    };
+    // missing the permutation
+    assert!(simple_decompress_sorted::<i32>(&crafted(None)?).is_err());
+    // a permutation of the wrong length
+    assert!(simple_decompress_sorted::<i32>(&crafted(Some(&[0, 0, 0]))?).is_err());
+    // a rank that's out of range
+    assert!(simple_decompress_sorted::<i32>(&crafted(Some(&[0, 0, 2, 0]))?).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_simple_compress_sorted_permutation_size() -> PcoResult<()> {
+    let n = 100_000;
+    let mut rng = Xoroshiro128PlusPlus::seed_from_u64(0);
+    let mut permutation = (0..n as u32).collect::<Vec<_>>();
+    permutation.shuffle(&mut rng);
+    let config = ChunkConfig::default();
+    let compressed = simple_compress_sorted(&permutation, &config)?;
+    assert_eq!(
+      simple_decompress_sorted::<u32>(&compressed)?,
+      permutation
+    );
+
+    let sorted_size = simple_compress(&(0..n as u32).collect::<Vec<_>>(), &config)?.len();
+    let bits_per_num = 8.0 * (compressed.len() - sorted_size) as f64 / n as f64;
+    let optimal_bits_per_num = (1..=n).map(|i| (i as f64).log2()).sum::<f64>() / n as f64;
+    assert!(
+      bits_per_num < optimal_bits_per_num + 0.25,
+      "{} vs optimal {}",
+      bits_per_num,
+      optimal_bits_per_num,
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_simple_compress_nullable() -> PcoResult<()> {
+    let validity = (0..1000).map(|i| i % 7 != 0 && i > 3).collect::<Vec<_>>();
+    let nums = (0..1000)
+      .map(|i| {
+        if validity[i] {
+          i as f32 * 0.5
+        } else {
+          f32::NAN
+        }
+      })
+      .collect::<Vec<_>>();
+    let compressed = simple_compress_nullable(&nums, &validity, &ChunkConfig::default())?;
+    let (rec_nums, rec_validity) = simple_decompress_nullable::<f32>(&compressed)?;
+    assert_eq!(rec_validity, validity);
+    for i in 0..nums.len() {
+      let expected = if validity[i] { nums[i] } else { 0.0 };
+      assert_eq!(rec_nums[i], expected);
+    }
+
+    for validity in [vec![], vec![false; 3], vec![true; 3]] {
+      let nums = vec![7_u32; validity.len()];
+      let compressed = simple_compress_nullable(&nums, &validity, &ChunkConfig::default())?;
+      let (_, rec_validity) = simple_decompress_nullable::<u32>(&compressed)?;
+      assert_eq!(rec_validity, validity);
+    }
+
+    assert!(simple_compress_nullable(&[1_u32], &[], &ChunkConfig::default()).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_simple_decompress_nullable_corrupt_runs() -> PcoResult<()> {
+    let config = ChunkConfig::default();
+    let crafted = |kind: CompositeKind, runs: &[u64], valid_nums: &[u32]| -> PcoResult<Vec<u8>> {
+      let mut writer = CompositeWriter::new(kind);
+      if kind == CompositeKind::Runs {
+        writer.write_part(&[7_u32], &config)?;
+      }
+      writer.write_part(runs, &config)?;
+      writer.write_part(valid_nums, &config)?;
+      Ok(writer.finish())
+    };
+    for (runs, valid_nums) in [
+      // valid runs don't match the values
+      (vec![0, u64::MAX / 2, 3], vec![]),
+      (vec![2, 1], vec![7]),
+      (vec![1], vec![7, 8]),
+      // run lengths overflow
+      (vec![1, u64::MAX], vec![7]),
+      // too many nulls to allocate
+      (vec![0, u64::MAX / 2], vec![]),
+    ] {
+      let src = crafted(CompositeKind::Nullable, &runs, &valid_nums)?;
+      let err = simple_decompress_nullable::<u32>(&src).unwrap_err();
+      assert!(matches!(err.kind, ErrorKind::Corruption));
+      assert!(simple_decompress_options::<u32>(&src).is_err());
+
+      let src = crafted(CompositeKind::Runs, &runs, &valid_nums)?;
+      let err = simple_decompress_runs::<u32>(&src).unwrap_err();
+      assert!(matches!(err.kind, ErrorKind::Corruption));
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_simple_compress_options() -> PcoResult<()> {
+    let nums = (0..1000)
+      .map(|i| (i % 5 != 0).then_some(i as f64 * 0.25))
+      .collect::<Vec<_>>();
+    let compressed = simple_compress_options(&nums, &ChunkConfig::default())?;
+    assert_eq!(
+      simple_decompress_options::<f64>(&compressed)?,
+      nums
+    );
+
+    for nums in [vec![], vec![None; 3], vec![Some(7_u32); 3]] {
+      let compressed = simple_compress_options(&nums, &ChunkConfig::default())?;
+      assert_eq!(
+        simple_decompress_options::<u32>(&compressed)?,
+        nums
+      );
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_simple_compress_runs() -> PcoResult<()> {
+    let mut nums = vec![0.5_f32; 1_000_000];
+    for i in (0..nums.len()).step_by(10007) {
+      nums[i] = i as f32;
+    }
+    let config = ChunkConfig::default();
+    let compressed = simple_compress_runs(&nums, &config)?;
+    assert!(compressed.len() < simple_compress(&nums, &config)?.len() / 2);
+    assert_eq!(
+      simple_decompress_runs::<f32>(&compressed)?,
+      nums
+    );
+
+    let compressed = simple_compress_runs::<f32>(&[], &config)?;
+    assert_eq!(
+      simple_decompress_runs::<f32>(&compressed)?,
+      Vec::<f32>::new()
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_empty_round_trips() -> PcoResult<()> {
+    let config = ChunkConfig::default();
+    let src = simple_compress_pair::<i64>(&[], &[], &config)?;
+    assert_eq!(
+      simple_decompress_pair::<i64>(&src)?,
+      (vec![], vec![])
+    );
+    let src = simple_compress_against::<i64>(&[], &[], &config)?;
+    assert!(simple_decompress_against::<i64>(&src, &[])?.is_empty());
+    let src = simple_compress_sorted::<i64>(&[], &config)?;
+    assert!(simple_decompress_sorted::<i64>(&src)?.is_empty());
+    let src = simple_compress_nullable::<i64>(&[], &[], &config)?;
+    assert_eq!(
+      simple_decompress_nullable::<i64>(&src)?,
+      (vec![], vec![])
+    );
+    let src = simple_compress_options::<i64>(&[], &config)?;
+    assert!(simple_decompress_options::<i64>(&src)?.is_empty());
+    let src = simple_compress_fixed::<i64, 3>(&[], &config)?;
+    assert!(simple_decompress_fixed::<i64, 3>(&src)?.is_empty());
+    Ok(())
+  }
+
+  #[test]
+  fn test_mismatched_decompress_errors() -> PcoResult<()> {
+    let config = ChunkConfig::default();
+    let nullable = simple_compress_nullable(&[1_u32, 2], &[true, false], &config)?;
+    let plain = simple_compress(&[1_u32, 2], &config)?;
+
+    // standalone decoders name the container's decoder
+    for err in [
+      simple_decompress::<u32>(&nullable).unwrap_err(),
+      peek_dtype(&nullable).unwrap_err(),
+    ] {
+      assert!(matches!(
+        err.kind,
+        ErrorKind::InvalidArgument
+      ));
+      assert!(
+        err.message.contains("simple_decompress_nullable"),
+        "{}",
+        err.message,
+      );
+    }
+
+    // other container decoders name it too
+    let err = simple_decompress_runs::<u32>(&nullable).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+    assert!(err.message.contains("simple_decompress_nullable"));
+
+    // container decoders point standalone files to simple_decompress
+    let err = simple_decompress_nullable::<u32>(&plain).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+    assert!(err.message.ends_with("simple_decompress"));
+
+    let err = simple_decompress_nullable::<u32>(&nullable[..3]).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InsufficientData
+    ));
+    let err = simple_decompress_nullable::<u32>(&nullable[..4]).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InsufficientData
+    ));
+    let err = simple_decompress_nullable::<u32>(&[1, 2, 3, 4, 5]).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::Corruption));
+    let mut unknown_kind = nullable.clone();
+    unknown_kind[4] = 77;
+    let err = simple_decompress_nullable::<u32>(&unknown_kind).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::Compatibility));
+    Ok(())
+  }
+}
@@ -0,0 +1,170 @@
+use crate::data_types::{Number, NumberType};
+use crate::errors::{PcoError, PcoResult};
+use crate::macros::match_number_enum;
+#[cfg(feature = "compress")]
+use crate::standalone::simple_compress;
+use crate::standalone::{
+  simple_decompress, FileDecompressor, MaybeChunkDecompressor, NumberTypeOrTermination,
+};
+#[cfg(feature = "compress")]
+use crate::ChunkConfig;
+use crate::FULL_BATCH_N;
+
+/// The bytes of a standalone .pco file, along with its data type and count
+/// of numbers.
+///
+/// This saves passing the data type and count around separately, and checks
+/// the requested type on decompression.
+///
+/// ```
+/// use pco::ChunkConfig;
+/// use pco::data_types::NumberType;
+/// use pco::standalone::CompressedColumn;
+/// # use pco::errors::PcoResult;
+///
+/// # fn main() -> PcoResult<()> {
+/// let nums = vec![1.5_f64, 2.5, 3.5];
+/// let column = CompressedColumn::compress(&nums, &ChunkConfig::default())?;
+/// let column = CompressedColumn::from_bytes(column.into_bytes())?;
+/// assert_eq!(column.dtype(), Some(NumberType::F64));
+/// assert_eq!(column.n(), 3);
+/// assert_eq!(column.decompress::<f64>()?, nums);
+/// assert!(column.decompress::<f32>().is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompressedColumn {
+  bytes: Vec<u8>,
+  dtype: Option<NumberType>,
+  n: usize,
+}
+
+// Standalone chunks don't record their compressed size, so we have to
+// decompress each one to find where the next begins.
+fn count_nums<T: Number>(fd: &FileDecompressor, mut src: &[u8]) -> PcoResult<usize> {
+  let mut n = 0;
+  let mut scratch = vec![T::default(); FULL_BATCH_N];
+  while let MaybeChunkDecompressor::Some(mut cd) = fd.chunk_decompressor::<T, _>(src)? {
+    n += cd.n();
+    while !cd.decompress(&mut scratch)?.finished {}
+    src = cd.into_src();
+  }
+  Ok(n)
+}
+
+impl CompressedColumn {
+  /// Compresses the numbers into a new column.
+  ///
+  /// Will return an error if the compressor config is invalid.
+  #[cfg(feature = "compress")]
+  pub fn compress<T: Number>(nums: &[T], config: &ChunkConfig) -> PcoResult<Self> {
+    let dtype = if nums.is_empty() {
+      None
+    } else {
+      NumberType::from_descriminant(T::NUMBER_TYPE_BYTE)
+    };
+    Ok(Self {
+      bytes: simple_compress(nums, config)?,
+      dtype,
+      n: nums.len(),
+    })
+  }
+
+  /// Takes in the bytes of a standalone .pco file, reading its data type and
+  /// count of numbers.
+  ///
+  /// Counting the numbers requires reading through every chunk, so this
+  /// costs about as much as decompressing, but also validates the whole file.
+  /// Will return an error if there are any compatibility, corruption,
+  /// or insufficient data issues, or if the file contains a data type unknown
+  /// to pco.
+  pub fn from_bytes(bytes: Vec<u8>) -> PcoResult<Self> {
+    let (fd, rest) = FileDecompressor::new(bytes.as_slice())?;
+    let (dtype, n) = match fd.peek_number_type_or_termination(rest)? {
+      NumberTypeOrTermination::Known(number_type) => match_number_enum!(
+        number_type,
+        NumberType<T> => { (Some(number_type), count_nums::<T>(&fd, rest)?) }
+      ),
+      NumberTypeOrTermination::Termination => (None, 0),
+      NumberTypeOrTermination::Unknown(byte) => {
+        return Err(PcoError::compatibility(format!(
+          "unknown number type byte: {}",
+          byte
+        )))
+      }
+    };
+    Ok(Self { bytes, dtype, n })
+  }
+
+  /// Returns the data type of the numbers, or `None` if there are no
+  /// numbers.
+  pub fn dtype(&self) -> Option<NumberType> {
+    self.dtype
+  }
+
+  /// Returns the count of numbers.
+  pub fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Returns the compressed bytes.
+  pub fn bytes(&self) -> &[u8] {
+    &self.bytes
+  }
+
+  /// Returns the compressed bytes, consuming the column.
+  pub fn into_bytes(self) -> Vec<u8> {
+    self.bytes
+  }
+
+  /// Decompresses the numbers.
+  ///
+  /// Will return an error if `T` does not match the column's data type, or
+  /// if there are any corruption issues.
+  pub fn decompress<T: Number>(&self) -> PcoResult<Vec<T>> {
+    match self.dtype {
+      Some(dtype) if dtype as u8 != T::NUMBER_TYPE_BYTE => {
+        Err(PcoError::invalid_argument(format!(
+          "requested data type byte {} does not match the column's {:?}",
+          T::NUMBER_TYPE_BYTE,
+          dtype,
+        )))
+      }
+      _ => simple_decompress(&self.bytes),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::errors::ErrorKind;
+  use crate::PagingSpec;
+
+  #[test]
+  fn test_compressed_column() -> PcoResult<()> {
+    let nums = (0..1000).map(|i| i * 3 - 77).collect::<Vec<i32>>();
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(300));
+    let column = CompressedColumn::compress(&nums, &config)?;
+    let read_column = CompressedColumn::from_bytes(column.bytes().to_vec())?;
+    assert_eq!(read_column, column);
+    assert_eq!(read_column.dtype(), Some(NumberType::I32));
+    assert_eq!(read_column.n(), 1000);
+    assert_eq!(read_column.decompress::<i32>()?, nums);
+    assert!(matches!(
+      read_column.decompress::<u32>().unwrap_err().kind,
+      ErrorKind::InvalidArgument
+    ));
+
+    let empty = CompressedColumn::compress::<i32>(&[], &config)?;
+    let read_empty = CompressedColumn::from_bytes(empty.bytes().to_vec())?;
+    assert_eq!(read_empty, empty);
+    assert_eq!(read_empty.dtype(), None);
+    assert_eq!(read_empty.decompress::<u32>()?, vec![]);
+
+    let truncated = column.bytes()[..column.bytes().len() / 2].to_vec();
+    assert!(CompressedColumn::from_bytes(truncated).is_err());
+    Ok(())
+  }
+}
@@ -3,9 +3,10 @@ use std::io::Write;
 use crate::bit_writer::BitWriter;
 use crate::chunk_config::PagingSpec;
 use crate::data_types::Number;
-use crate::errors::PcoResult;
+use crate::errors::{PcoError, PcoResult};
 use crate::metadata::ChunkMeta;
 use crate::standalone::constants::*;
+use crate::standalone::FileDecompressor;
 use crate::{bits, wrapped, ChunkConfig};
 
 unsafe fn write_varint<W: Write>(n: u64, writer: &mut BitWriter<W>) {
@@ -53,6 +54,15 @@ impl FileCompressor {
     self
   }
 
+  /// Sets the format version to write in the header (default: the current
+  /// format version).
+  ///
+  /// See [`wrapped::FileCompressor::with_format_version`].
+  pub fn with_format_version(mut self, format_version: u8) -> PcoResult<Self> {
+    self.inner = self.inner.with_format_version(format_version)?;
+    Ok(self)
+  }
+
   /// Writes a short header to the destination.
   ///
   /// Will return an error if the provided `Write` errors.
@@ -93,6 +103,39 @@ impl FileCompressor {
     })
   }
 
+  /// Returns bytes from which [`load_state`][FileCompressor::load_state] can
+  /// recreate this file compressor, e.g. to resume writing a file in another
+  /// process.
+  ///
+  /// A file compressor holds no state between chunks besides its settings;
+  /// the position in the file is the caller's destination, and each chunk is
+  /// independent.
+  /// So a file can be resumed at any chunk boundary by appending chunks and
+  /// the footer to the bytes written so far.
+  /// The state is encoded the same way as the header.
+  pub fn save_state(&self) -> PcoResult<Vec<u8>> {
+    self.write_header(Vec::new())
+  }
+
+  /// Recreates a file compressor from bytes returned by
+  /// [`save_state`][FileCompressor::save_state].
+  ///
+  /// Will return an error if the state is corrupt or was saved by a newer
+  /// version of pco.
+  pub fn load_state(state: &[u8]) -> PcoResult<Self> {
+    let (fd, rest) = FileDecompressor::new(state)?;
+    if !rest.is_empty() {
+      return Err(PcoError::corruption(format!(
+        "{} unexpected trailing bytes in file compressor state",
+        rest.len()
+      )));
+    }
+
+    Self::default()
+      .with_n_hint(fd.n_hint())
+      .with_format_version(fd.format_version())
+  }
+
   /// Writes a short footer to the destination.
   ///
   /// Will return an error if the provided `Write` errors.
@@ -117,6 +160,21 @@ impl ChunkCompressor {
     self.inner.meta()
   }
 
+  /// Returns whether the chunk fell back to the trivial baseline compression.
+  ///
+  /// See [`wrapped::ChunkCompressor::used_fallback`] for more details.
+  pub fn used_fallback(&self) -> bool {
+    self.inner.used_fallback()
+  }
+
+  /// Returns whether the chunk will reconstruct the numbers only
+  /// approximately.
+  ///
+  /// See [`wrapped::ChunkCompressor::is_lossy`] for more details.
+  pub fn is_lossy(&self) -> bool {
+    self.inner.is_lossy()
+  }
+
   /// Returns an estimate of the overall size of the chunk.
   ///
   /// This can be useful when building the file as a `Vec<u8>` in memory;
@@ -127,6 +185,19 @@ impl ChunkCompressor {
       + self.inner.page_size_hint(0)
   }
 
+  /// Returns the exact number of bytes that
+  /// [`write_chunk`][Self::write_chunk] will write.
+  ///
+  /// See [`wrapped::ChunkCompressor::exact_compressed_size`] for more details.
+  pub fn exact_compressed_size(&self) -> PcoResult<usize> {
+    Ok(1 + BITS_TO_ENCODE_N_ENTRIES.div_ceil(8) as usize + self.inner.exact_compressed_size()?)
+  }
+
+  // The number of bytes in the chunk before its only page begins.
+  pub(crate) fn page_offset_in_chunk(&self) -> usize {
+    1 + BITS_TO_ENCODE_N_ENTRIES.div_ceil(8) as usize + self.inner.chunk_meta_size_hint()
+  }
+
   /// Writes an entire chunk to the destination.
   ///
   /// Will return an error if the provided `Write` errors.
@@ -144,3 +215,56 @@ impl ChunkCompressor {
     self.inner.write_page(0, dst)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::standalone::simple_decompress;
+
+  #[test]
+  fn test_save_and_load_state() -> PcoResult<()> {
+    let config = ChunkConfig::default();
+    let fc = FileCompressor::default()
+      .with_n_hint(5)
+      .with_format_version(2)?;
+    let mut compressed = fc.write_header(Vec::new())?;
+    fc.chunk_compressor(&[1_i64, 2, 3], &config)?
+      .write_chunk(&mut compressed)?;
+    let state = fc.save_state()?;
+
+    // resume as if in a new process
+    let fc = FileCompressor::load_state(&state)?;
+    assert_eq!(fc.save_state()?, state);
+    fc.chunk_compressor(&[4_i64, 5], &config)?
+      .write_chunk(&mut compressed)?;
+    fc.write_footer(&mut compressed)?;
+    assert_eq!(
+      simple_decompress::<i64>(&compressed)?,
+      vec![1, 2, 3, 4, 5]
+    );
+    let (fd, _) = FileDecompressor::new(compressed.as_slice())?;
+    assert_eq!(fd.n_hint(), 5);
+    assert_eq!(fd.format_version(), 2);
+
+    assert!(FileCompressor::load_state(&state[..state.len() - 1]).is_err());
+    assert!(FileCompressor::load_state(&[state.as_slice(), &[0]].concat()).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_exact_compressed_size() -> PcoResult<()> {
+    let fc = FileCompressor::default();
+    let nums = (0..5000).map(|i| (i * i) % 1009).collect::<Vec<u32>>();
+    for config in [
+      ChunkConfig::default(),
+      ChunkConfig::default().with_compression_level(0),
+    ] {
+      let cc = fc.chunk_compressor(&nums, &config)?;
+      assert_eq!(
+        cc.exact_compressed_size()?,
+        cc.write_chunk(Vec::new())?.len()
+      );
+    }
+    Ok(())
+  }
+}
@@ -2,6 +2,8 @@ use crate::constants::{Bitlen, OVERSHOOT_PADDING};
 
 // ascii for pco!
 pub const MAGIC_HEADER: [u8; 4] = [112, 99, 111, 33];
+// ascii for pco+
+pub const MAGIC_COMPOSITE_HEADER: [u8; 4] = [112, 99, 111, 43];
 pub const MAGIC_TERMINATION_BYTE: u8 = 0;
 pub const BITS_TO_ENCODE_N_ENTRIES: Bitlen = 24;
 pub const BITS_TO_ENCODE_STANDALONE_VERSION: Bitlen = 8;
@@ -2,13 +2,15 @@ use better_io::BetterBufRead;
 
 use crate::bit_reader::{BitReader, BitReaderBuilder};
 use crate::constants::Bitlen;
-use crate::data_types::Number;
+use crate::data_types::{Number, NumberType};
 use crate::errors::{PcoError, PcoResult};
+use crate::macros::match_number_enum;
 use crate::metadata::ChunkMeta;
 use crate::progress::Progress;
+use crate::standalone::composite;
 use crate::standalone::constants::*;
 use crate::standalone::NumberTypeOrTermination;
-use crate::{bit_reader, wrapped};
+use crate::{bit_reader, wrapped, DecompressorConfig, FULL_BATCH_N};
 
 unsafe fn read_varint(reader: &mut BitReader) -> PcoResult<u64> {
   let power = 1 + reader.read_uint::<Bitlen>(BITS_TO_ENCODE_VARINT_POWER);
@@ -47,6 +49,17 @@ pub struct FileDecompressor {
   inner: wrapped::FileDecompressor,
 }
 
+/// A rough estimate of how expensive a standalone file is to decompress, as
+/// returned by [`FileDecompressor::estimate_decode_cost`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DecodeCostEstimate {
+  /// The count of numbers in the file, according to its header.
+  pub n: usize,
+  /// The estimated cost of decoding the whole file, in the arbitrary units of
+  /// [`ChunkMeta::decode_cost_per_num`].
+  pub cost: f64,
+}
+
 /// The outcome of starting a new chunk of a standalone file.
 #[allow(clippy::large_enum_variant)]
 pub enum MaybeChunkDecompressor<T: Number, R: BetterBufRead> {
@@ -64,16 +77,36 @@ impl FileDecompressor {
   ///
   /// Will return an error if any corruptions, version incompatibilities, or
   /// insufficient data are found.
-  pub fn new<R: BetterBufRead>(mut src: R) -> PcoResult<(Self, R)> {
+  pub fn new<R: BetterBufRead>(src: R) -> PcoResult<(Self, R)> {
+    Self::new_with_config(src, &DecompressorConfig::default())
+  }
+
+  /// Reads a short header and returns a `FileDecompressor` that enforces the
+  /// given configuration, as well as the remaining input.
+  ///
+  /// Will return an error if any corruptions, version incompatibilities, or
+  /// insufficient data are found, or if the file's size hint exceeds the
+  /// config's `max_file_n`.
+  pub fn new_with_config<R: BetterBufRead>(
+    mut src: R,
+    config: &DecompressorConfig,
+  ) -> PcoResult<(Self, R)> {
     bit_reader::ensure_buf_read_capacity(&mut src, STANDALONE_HEADER_PADDING);
     let mut reader_builder = BitReaderBuilder::new(src, STANDALONE_HEADER_PADDING, 0);
     // Do this part first so we check for insufficient data before returning a
     // confusing corruption error.
     let header = reader_builder
       .with_reader(|reader| Ok(reader.read_aligned_bytes(MAGIC_HEADER.len())?.to_vec()))?;
+    if header == MAGIC_COMPOSITE_HEADER {
+      let kind_byte = reader_builder
+        .with_reader(|reader| Ok(reader.read_aligned_bytes(1)?[0]))
+        .ok();
+      return Err(composite::container_error(kind_byte));
+    }
     if header != MAGIC_HEADER {
       return Err(PcoError::corruption(format!(
-        "magic header does not match {:?}; instead found {:?}",
+        "magic header does not match {:?} (\"pco!\"); instead found {:?}, so this is \
+         not a standalone pco file",
         MAGIC_HEADER, header,
       )));
     }
@@ -99,7 +132,10 @@ impl FileDecompressor {
       )));
     }
 
-    let (inner, rest) = wrapped::FileDecompressor::new(reader_builder.into_inner())?;
+    config.check_file_n(n_hint)?;
+
+    let (inner, rest) =
+      wrapped::FileDecompressor::new_with_config(reader_builder.into_inner(), config)?;
     Ok((Self { inner, n_hint }, rest))
   }
 
@@ -111,6 +147,53 @@ impl FileDecompressor {
     self.n_hint
   }
 
+  /// Reads the metadata of the first chunk in `src` (typically the bytes
+  /// right after the header), or returns `None` if at the end of the file.
+  ///
+  /// This is useful for cheaply guessing characteristics of the data (e.g.
+  /// from the mode or bins) without decompressing any numbers.
+  /// `src` is not advanced; to move on to the next chunk, use
+  /// [`chunk_decompressor`][FileDecompressor::chunk_decompressor] instead.
+  /// Will return an error if there are any compatibility, corruption, or
+  /// insufficient data issues in the chunk's metadata.
+  pub fn first_chunk_meta(&self, src: &[u8]) -> PcoResult<Option<ChunkMeta>> {
+    match self.peek_number_type_or_termination(src)? {
+      NumberTypeOrTermination::Known(number_type) => match_number_enum!(
+        number_type,
+        NumberType<T> => {
+          match self.chunk_decompressor::<T, _>(src)? {
+            MaybeChunkDecompressor::Some(cd) => Ok(Some(cd.meta().clone())),
+            MaybeChunkDecompressor::EndOfData(_) => Ok(None),
+          }
+        }
+      ),
+      NumberTypeOrTermination::Termination => Ok(None),
+      NumberTypeOrTermination::Unknown(byte) => Err(PcoError::compatibility(format!(
+        "unknown number type byte: {}",
+        byte
+      ))),
+    }
+  }
+
+  /// Estimates the cost of decompressing the rest of the file without
+  /// decompressing it.
+  ///
+  /// Standalone chunks don't record their compressed size, so only the first
+  /// chunk's metadata is read, and its cost per number is extrapolated to the
+  /// count of numbers in the header's size hint.
+  /// Files written without a size hint therefore get an estimate of 0.
+  /// Will return an error if there are any compatibility, corruption, or
+  /// insufficient data issues in the first chunk's metadata.
+  pub fn estimate_decode_cost(&self, src: &[u8]) -> PcoResult<DecodeCostEstimate> {
+    let cost_per_num = self
+      .first_chunk_meta(src)?
+      .map_or(0.0, |meta| meta.decode_cost_per_num());
+    Ok(DecodeCostEstimate {
+      n: self.n_hint,
+      cost: self.n_hint as f64 * cost_per_num,
+    })
+  }
+
   /// Peeks at what's next in the file, returning whether it's a termination
   /// or chunk with some data type.
   ///
@@ -144,15 +227,25 @@ impl FileDecompressor {
     }
 
     if type_or_termination_byte != T::NUMBER_TYPE_BYTE {
-      return Err(PcoError::corruption(format!(
-        "data type byte does not match {:?}; instead found {:?}",
-        T::NUMBER_TYPE_BYTE,
-        type_or_termination_byte,
-      )));
+      let byte = type_or_termination_byte;
+      return Err(match NumberType::from_descriminant(byte) {
+        Some(number_type) => PcoError::corruption(format!(
+          "data type byte does not match {:?}; instead found {:?} ({:?})",
+          T::NUMBER_TYPE_BYTE,
+          byte,
+          number_type,
+        )),
+        None => PcoError::compatibility(format!(
+          "unknown data type byte {}; the file may have been written by a newer \
+           version of pco",
+          byte,
+        )),
+      });
     }
 
     let n = reader_builder
       .with_reader(|reader| unsafe { Ok(reader.read_usize(BITS_TO_ENCODE_N_ENTRIES) + 1) })?;
+    let preamble_n_bytes = reader_builder.n_bytes_consumed();
     let src = reader_builder.into_inner();
     let (inner_cd, src) = self.inner.chunk_decompressor::<T, R>(src)?;
     let inner_pd = inner_cd.page_decompressor(src, n)?;
@@ -160,6 +253,7 @@ impl FileDecompressor {
     let res = ChunkDecompressor {
       inner_cd,
       inner_pd,
+      preamble_n_bytes,
       n,
       n_processed: 0,
     };
@@ -171,6 +265,7 @@ impl FileDecompressor {
 pub struct ChunkDecompressor<T: Number, R: BetterBufRead> {
   inner_cd: wrapped::ChunkDecompressor<T>,
   inner_pd: wrapped::PageDecompressor<T, R>,
+  preamble_n_bytes: usize,
   n: usize,
   n_processed: usize,
 }
@@ -186,6 +281,24 @@ impl<T: Number, R: BetterBufRead> ChunkDecompressor<T, R> {
     self.n
   }
 
+  /// Returns the smallest and largest numbers this chunk could contain,
+  /// without decompressing it.
+  ///
+  /// See [`wrapped::PageDecompressor::value_range`]; since each standalone
+  /// chunk has a single page, this also works for consecutive delta
+  /// encoding.
+  pub fn value_range(&self) -> Option<(T, T)> {
+    self.inner_pd.value_range()
+  }
+
+  /// Returns the number of bytes of the chunk consumed so far.
+  ///
+  /// Once the chunk is finished, this is the chunk's exact size, which can be
+  /// compared against an externally stored length.
+  pub fn n_bytes_consumed(&self) -> usize {
+    self.preamble_n_bytes + self.inner_cd.meta_n_bytes() + self.inner_pd.n_bytes_consumed()
+  }
+
   /// Reads the next decompressed numbers into the destination, returning
   /// progress into the chunk and advancing along the compressed data.
   ///
@@ -218,4 +331,109 @@ impl<T: Number, R: BetterBufRead> ChunkDecompressor<T, R> {
     assert!(progress.finished);
     Ok(())
   }
+
+  // Decodes the rest of the chunk one batch at a time, discarding the
+  // numbers. Standalone chunks don't record their size, so this is the only
+  // way to find where the next chunk begins.
+  pub(crate) fn skip_remaining(&mut self) -> PcoResult<()> {
+    let mut batch = [T::default(); FULL_BATCH_N];
+    while self.n_processed < self.n {
+      self.decompress(&mut batch)?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::errors::ErrorKind;
+  use crate::standalone::simple_compress;
+  use crate::{ChunkConfig, DeltaSpec, PagingSpec};
+
+  fn chunk_decompressor_err<T: Number>(src: &[u8]) -> PcoError {
+    let (fd, src) = FileDecompressor::new(src).unwrap();
+    match fd.chunk_decompressor::<T, _>(src) {
+      Ok(_) => panic!("expected an error"),
+      Err(e) => e,
+    }
+  }
+
+  #[test]
+  fn test_header_errors() -> PcoResult<()> {
+    let Err(e) = FileDecompressor::new(b"PAR1abcdefgh".as_slice()) else {
+      panic!("expected an error");
+    };
+    assert_eq!(e.kind, ErrorKind::Corruption);
+    assert!(e.message.contains("[80, 65, 82, 49]"));
+
+    let compressed = simple_compress(&[1_u32, 2, 3], &ChunkConfig::default())?;
+    let (_, rest) = FileDecompressor::new(compressed.as_slice())?;
+    let header_len = compressed.len() - rest.len();
+
+    let e = chunk_decompressor_err::<i64>(&compressed);
+    assert_eq!(e.kind, ErrorKind::Corruption);
+    assert!(e.message.contains("U32"));
+
+    let mut unknown_dtype = compressed.clone();
+    unknown_dtype[header_len] = 200;
+    let e = chunk_decompressor_err::<u32>(&unknown_dtype);
+    assert_eq!(e.kind, ErrorKind::Compatibility);
+    assert!(e.message.contains("200"));
+
+    let mut future_version = compressed;
+    future_version[header_len - 1] = 77;
+    let Err(e) = FileDecompressor::new(future_version.as_slice()) else {
+      panic!("expected an error");
+    };
+    assert_eq!(e.kind, ErrorKind::Compatibility);
+    assert!(e.message.contains("77"));
+    Ok(())
+  }
+
+  #[test]
+  fn test_estimate_decode_cost() -> PcoResult<()> {
+    let estimate = |nums: &[u32], config: &ChunkConfig| -> PcoResult<DecodeCostEstimate> {
+      let compressed = simple_compress(nums, config)?;
+      let (fd, rest) = FileDecompressor::new(compressed.as_slice())?;
+      fd.estimate_decode_cost(rest)
+    };
+
+    let config = ChunkConfig::default();
+    let empty = estimate(&[], &config)?;
+    assert_eq!(empty, DecodeCostEstimate { n: 0, cost: 0.0 });
+
+    let constant = estimate(&[7; 1000], &config)?;
+    let noisy_nums = (0..1000_u32)
+      .map(|i| i.wrapping_mul(2654435761) >> 8)
+      .collect::<Vec<_>>();
+    let noisy = estimate(&noisy_nums, &config)?;
+    let lookback = estimate(
+      &noisy_nums,
+      &config.with_delta_spec(DeltaSpec::TryLookback),
+    )?;
+    assert_eq!(constant.n, 1000);
+    assert_eq!(noisy.n, 1000);
+    assert!(constant.cost < noisy.cost);
+    assert!(noisy.cost < lookback.cost);
+    Ok(())
+  }
+
+  #[test]
+  fn test_first_chunk_meta() -> PcoResult<()> {
+    let nums = (0..1000_u64).map(|i| i * 1000).collect::<Vec<_>>();
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(300));
+    let compressed = simple_compress(&nums, &config)?;
+    let (fd, rest) = FileDecompressor::new(compressed.as_slice())?;
+    let meta = fd.first_chunk_meta(rest)?.unwrap();
+    let MaybeChunkDecompressor::Some(cd) = fd.chunk_decompressor::<u64, _>(rest)? else {
+      panic!("expected a chunk");
+    };
+    assert_eq!(&meta, cd.meta());
+
+    let empty = simple_compress::<u64>(&[], &config)?;
+    let (fd, rest) = FileDecompressor::new(empty.as_slice())?;
+    assert_eq!(fd.first_chunk_meta(rest)?, None);
+    Ok(())
+  }
 }
@@ -0,0 +1,167 @@
+use std::mem;
+
+use better_io::BetterBufRead;
+
+use crate::data_types::Number;
+use crate::errors::PcoResult;
+use crate::standalone::{ChunkDecompressor, FileDecompressor, MaybeChunkDecompressor};
+use crate::FULL_BATCH_N;
+
+#[allow(clippy::large_enum_variant)]
+enum State<T: Number, R: BetterBufRead> {
+  BetweenChunks(R),
+  InChunk(ChunkDecompressor<T, R>),
+  Finished,
+}
+
+/// Lazily yields the numbers of a standalone .pco file along with their
+/// indices, as returned by [`decompress_enumerate`].
+///
+/// Numbers are decompressed a batch at a time, so only one batch is held in
+/// memory, no matter how many numbers the file has.
+/// If a corruption or insufficient data is found, the error is yielded and
+/// iteration ends.
+pub struct DecompressEnumerate<T: Number, R: BetterBufRead> {
+  fd: FileDecompressor,
+  state: State<T, R>,
+  nums: Vec<T>,
+  batch_n: usize,
+  batch_idx: usize,
+  idx: usize,
+}
+
+/// Takes in compressed bytes (or any `BetterBufRead`) of a standalone .pco
+/// file and returns an iterator over `(index, number)` pairs.
+///
+/// This is useful for filtering numbers as they're decompressed, without
+/// ever materializing the full array.
+/// Will return an error if any corruptions, version incompatibilities, or
+/// insufficient data are found in the header; errors found later are
+/// yielded by the iterator.
+///
+/// Example:
+/// ```
+/// use pco::standalone::{decompress_enumerate, simple_compress};
+/// use pco::ChunkConfig;
+/// # use pco::errors::PcoResult;
+///
+/// # fn main() -> PcoResult<()> {
+/// let nums = (0..1000).map(|i| i % 100).collect::<Vec<u32>>();
+/// let compressed = simple_compress(&nums, &ChunkConfig::default())?;
+/// let mut zero_idxs = Vec::new();
+/// for res in decompress_enumerate::<u32, _>(compressed.as_slice())? {
+///   let (i, x) = res?;
+///   if x == 0 {
+///     zero_idxs.push(i);
+///   }
+/// }
+/// assert_eq!(zero_idxs, (0..10).map(|i| i * 100).collect::<Vec<_>>());
+/// # Ok(())
+/// # }
+/// ```
+pub fn decompress_enumerate<T: Number, R: BetterBufRead>(
+  src: R,
+) -> PcoResult<DecompressEnumerate<T, R>> {
+  let (fd, src) = FileDecompressor::new(src)?;
+  Ok(DecompressEnumerate {
+    fd,
+    state: State::BetweenChunks(src),
+    nums: vec![T::default(); FULL_BATCH_N],
+    batch_n: 0,
+    batch_idx: 0,
+    idx: 0,
+  })
+}
+
+impl<T: Number, R: BetterBufRead> DecompressEnumerate<T, R> {
+  // Returns whether any numbers were buffered; false means we reached the end
+  // of the data.
+  fn refill(&mut self) -> PcoResult<bool> {
+    loop {
+      match mem::replace(&mut self.state, State::Finished) {
+        State::BetweenChunks(src) => match self.fd.chunk_decompressor::<T, R>(src)? {
+          MaybeChunkDecompressor::Some(cd) => self.state = State::InChunk(cd),
+          MaybeChunkDecompressor::EndOfData(_) => return Ok(false),
+        },
+        State::InChunk(mut cd) => {
+          let progress = cd.decompress(&mut self.nums)?;
+          self.state = if progress.finished {
+            State::BetweenChunks(cd.into_src())
+          } else {
+            State::InChunk(cd)
+          };
+
+          self.batch_n = progress.n_processed;
+          self.batch_idx = 0;
+          if self.batch_n > 0 {
+            return Ok(true);
+          }
+        }
+        State::Finished => return Ok(false),
+      }
+    }
+  }
+}
+
+impl<T: Number, R: BetterBufRead> Iterator for DecompressEnumerate<T, R> {
+  type Item = PcoResult<(usize, T)>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.batch_idx == self.batch_n {
+      // on error, the state is left as Finished, so iteration ends after
+      // yielding it
+      match self.refill() {
+        Ok(true) => (),
+        Ok(false) => return None,
+        Err(e) => return Some(Err(e)),
+      }
+    }
+
+    let res = (self.idx, self.nums[self.batch_idx]);
+    self.batch_idx += 1;
+    self.idx += 1;
+    Some(Ok(res))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::standalone::simple_compress;
+  use crate::{ChunkConfig, PagingSpec};
+
+  #[test]
+  fn test_decompress_enumerate() -> PcoResult<()> {
+    let nums = (0..1000).map(|i| i * 3 - 77).collect::<Vec<i64>>();
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(300));
+    let compressed = simple_compress(&nums, &config)?;
+    let enumerated =
+      decompress_enumerate::<i64, _>(compressed.as_slice())?.collect::<PcoResult<Vec<_>>>()?;
+    assert_eq!(
+      enumerated,
+      nums.iter().copied().enumerate().collect::<Vec<_>>()
+    );
+
+    let empty = simple_compress::<i64>(&[], &config)?;
+    assert_eq!(
+      decompress_enumerate::<i64, _>(empty.as_slice())?.count(),
+      0
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_decompress_enumerate_error() -> PcoResult<()> {
+    let nums = (0..1000).map(|i| i * 3 - 77).collect::<Vec<i64>>();
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(300));
+    let compressed = simple_compress(&nums, &config)?;
+    let truncated = &compressed[..compressed.len() / 2];
+    let results = decompress_enumerate::<i64, _>(truncated)?.collect::<Vec<_>>();
+    // some numbers decompress, then we get exactly one error at the end
+    let n_ok = results.iter().take_while(|res| res.is_ok()).count();
+    assert!(n_ok > 0);
+    assert_eq!(results.len(), n_ok + 1);
+    assert!(results.last().unwrap().is_err());
+    Ok(())
+  }
+}
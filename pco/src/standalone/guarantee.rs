@@ -1,4 +1,4 @@
-use crate::data_types::Latent;
+use crate::data_types::{Latent, Number};
 use crate::errors::PcoResult;
 use crate::standalone::constants::{
   BITS_TO_ENCODE_N_ENTRIES, BITS_TO_ENCODE_STANDALONE_VERSION, BITS_TO_ENCODE_VARINT_POWER,
@@ -36,6 +36,19 @@ pub fn file_size<L: Latent>(n: usize, paging_spec: &PagingSpec) -> PcoResult<usi
   Ok(res)
 }
 
+/// Returns the maximum possible byte size of a standalone file of `n`
+/// numbers of type `T`, compressed with the default `PagingSpec`, as
+/// [`simple_compress`][crate::standalone::simple_compress] does with
+/// any `ChunkConfig` that keeps its default paging spec.
+///
+/// This holds regardless of the rest of the config, so it can be used to
+/// size a buffer before compressing.
+/// For other paging specs, see [`file_size`].
+pub fn max_compressed_size<T: Number>(n: usize) -> usize {
+  // the default paging spec is valid for any n
+  file_size::<T::L>(n, &PagingSpec::default()).unwrap()
+}
+
 #[cfg(test)]
 mod tests {
   use rand::Rng;
@@ -44,7 +57,6 @@ mod tests {
 
   use super::*;
   use crate::chunk_config::DeltaSpec;
-  use crate::data_types::Number;
   use crate::errors::PcoResult;
   use crate::standalone::{simple_compress, FileCompressor};
   use crate::{ChunkConfig, ModeSpec, PagingSpec};
@@ -85,4 +97,25 @@ mod tests {
     };
     check_file_guarantee(&nums, &config)
   }
+
+  #[test]
+  fn test_max_compressed_size() -> PcoResult<()> {
+    let mut rng = Xoroshiro128PlusPlus::seed_from_u64(0);
+    for n in [0, 1, 300, 1 << 18] {
+      let nums = (0..n).map(|_| rng.gen::<f64>()).collect::<Vec<_>>();
+      for config in [
+        ChunkConfig::default(),
+        ChunkConfig::default().with_mode_spec(ModeSpec::TryFloatMult(0.1)),
+        ChunkConfig::default().with_delta_spec(DeltaSpec::TryConsecutive(7)),
+      ] {
+        let compressed = simple_compress(&nums, &config)?;
+        assert!(compressed.len() <= max_compressed_size::<f64>(n));
+      }
+    }
+    assert_eq!(
+      max_compressed_size::<u16>(1000),
+      file_size::<u16>(1000, &PagingSpec::default())?
+    );
+    Ok(())
+  }
 }
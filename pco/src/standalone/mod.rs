@@ -1,11 +1,36 @@
+pub use byte_reader::PcoByteReader;
+pub use composite::*;
+pub use compressed_column::CompressedColumn;
+#[cfg(feature = "compress")]
 pub use compressor::{ChunkCompressor, FileCompressor};
-pub use decompressor::{ChunkDecompressor, FileDecompressor, MaybeChunkDecompressor};
+pub use decompressor::{
+  ChunkDecompressor, DecodeCostEstimate, FileDecompressor, MaybeChunkDecompressor,
+};
+pub use enumerate::{decompress_enumerate, DecompressEnumerate};
 pub use number_type_or_termination::NumberTypeOrTermination;
+#[cfg(feature = "compress")]
+pub use rechunk::rechunk;
 pub use simple::*;
+#[cfg(feature = "compress")]
+pub use stream_compressor::StreamCompressor;
+#[cfg(feature = "ndarray")]
+pub use strided::compress_ndarray;
 
+mod byte_reader;
+mod composite;
+mod compressed_column;
+#[cfg(feature = "compress")]
 mod compressor;
 mod constants;
 mod decompressor;
+mod enumerate;
+#[cfg(feature = "compress")]
 pub mod guarantee;
 mod number_type_or_termination;
+#[cfg(feature = "compress")]
+mod rechunk;
 mod simple;
+#[cfg(feature = "compress")]
+mod stream_compressor;
+#[cfg(feature = "ndarray")]
+mod strided;
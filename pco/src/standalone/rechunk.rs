@@ -0,0 +1,142 @@
+use crate::data_types::{Latent, Number, NumberType};
+use crate::errors::{PcoError, PcoResult};
+use crate::macros::match_number_enum;
+use crate::metadata::{ChunkMeta, DeltaEncoding, Mode};
+use crate::standalone::{
+  simple_compress, FileCompressor, FileDecompressor, MaybeChunkDecompressor,
+  NumberTypeOrTermination,
+};
+use crate::{ChunkConfig, DeltaSpec, ModeSpec, PagingSpec};
+
+// Builds a config that tries the same mode and delta encoding as the given
+// chunk. FloatMult bases can't be recovered as a float from the metadata
+// alone, so we fall back to automatic detection for those.
+fn config_like<T: Number>(meta: &ChunkMeta) -> ChunkConfig {
+  let mode_spec = match &meta.mode {
+    Mode::Classic => ModeSpec::Classic,
+    Mode::IntMult(base) => match base.downcast_ref::<T::L>() {
+      Some(base) => ModeSpec::TryIntMult(base.to_u64()),
+      None => ModeSpec::Auto,
+    },
+    Mode::FloatMult(_) | Mode::FloatMultLossy(_) => ModeSpec::Auto,
+    Mode::FloatQuant(k) => ModeSpec::TryFloatQuant(*k),
+  };
+  let (delta_spec, secondary_uses_delta) = match meta.delta_encoding {
+    DeltaEncoding::None => (DeltaSpec::None, false),
+    DeltaEncoding::Consecutive(config) => (
+      DeltaSpec::TryConsecutive(config.order),
+      config.secondary_uses_delta,
+    ),
+    DeltaEncoding::Lookback(config) => (
+      DeltaSpec::TryLookback,
+      config.secondary_uses_delta,
+    ),
+  };
+  ChunkConfig::default()
+    .with_mode_spec(mode_spec)
+    .with_delta_spec(delta_spec)
+    .with_secondary_uses_delta(secondary_uses_delta)
+}
+
+fn rechunk_typed<T: Number>(
+  fd: &FileDecompressor,
+  mut src: &[u8],
+  new_paging: PagingSpec,
+) -> PcoResult<Vec<u8>> {
+  let mut config = None;
+  let mut nums = Vec::with_capacity(fd.n_hint());
+  while let MaybeChunkDecompressor::Some(mut cd) = fd.chunk_decompressor::<T, _>(src)? {
+    if config.is_none() {
+      config = Some(config_like::<T>(cd.meta()));
+    }
+    cd.decompress_remaining_extend(&mut nums)?;
+    src = cd.into_src();
+  }
+
+  let config = config
+    .unwrap_or_default()
+    .with_paging_spec(new_paging)
+    .with_format_version(Some(fd.format_version()));
+  simple_compress(&nums, &config)
+}
+
+/// Takes in the bytes of a standalone .pco file and recompresses its numbers
+/// into chunks split according to `new_paging`.
+///
+/// The data type and format version are preserved, and the mode and delta
+/// encoding of the first chunk are tried again where possible.
+/// This is equivalent to decompressing and compressing again, but handles the
+/// data type dispatch internally.
+///
+/// Will return an error if there are any compatibility, corruption,
+/// or insufficient data issues, if the file contains a data type unknown to
+/// pco, or if the paging spec is invalid for the file's numbers.
+pub fn rechunk(src: &[u8], new_paging: PagingSpec) -> PcoResult<Vec<u8>> {
+  let (fd, rest) = FileDecompressor::new(src)?;
+  match fd.peek_number_type_or_termination(rest)? {
+    NumberTypeOrTermination::Known(number_type) => match_number_enum!(
+      number_type,
+      NumberType<T> => { rechunk_typed::<T>(&fd, rest, new_paging) }
+    ),
+    NumberTypeOrTermination::Termination => {
+      // there are no numbers, so the paging spec can't change anything
+      let fc = FileCompressor::default().with_format_version(fd.format_version())?;
+      let mut dst = Vec::new();
+      fc.write_header(&mut dst)?;
+      fc.write_footer(&mut dst)?;
+      Ok(dst)
+    }
+    NumberTypeOrTermination::Unknown(byte) => Err(PcoError::compatibility(format!(
+      "unable to rechunk unknown number type byte: {}",
+      byte
+    ))),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::standalone::simple_decompress;
+
+  fn chunk_ns<T: Number>(src: &[u8]) -> PcoResult<Vec<usize>> {
+    let (fd, mut src) = FileDecompressor::new(src)?;
+    let mut res = Vec::new();
+    while let MaybeChunkDecompressor::Some(mut cd) = fd.chunk_decompressor::<T, _>(src)? {
+      res.push(cd.n());
+      let mut nums = Vec::new();
+      cd.decompress_remaining_extend(&mut nums)?;
+      src = cd.into_src();
+    }
+    Ok(res)
+  }
+
+  #[test]
+  fn test_rechunk() -> PcoResult<()> {
+    let nums = (0..1000).map(|i| i * 1000 - 7).collect::<Vec<i64>>();
+    let compressed = simple_compress(&nums, &ChunkConfig::default())?;
+    assert_eq!(chunk_ns::<i64>(&compressed)?, vec![1000]);
+
+    let rechunked = rechunk(&compressed, PagingSpec::EqualPagesUpTo(300))?;
+    assert_eq!(
+      chunk_ns::<i64>(&rechunked)?,
+      vec![250, 250, 250, 250]
+    );
+    assert_eq!(simple_decompress::<i64>(&rechunked)?, nums);
+
+    // the mode and delta encoding should carry over
+    let (fd, src) = FileDecompressor::new(rechunked.as_slice())?;
+    let MaybeChunkDecompressor::Some(cd) = fd.chunk_decompressor::<i64, _>(src)? else {
+      panic!("expected a chunk");
+    };
+    assert!(matches!(cd.meta().mode, Mode::IntMult(_)));
+    Ok(())
+  }
+
+  #[test]
+  fn test_rechunk_empty() -> PcoResult<()> {
+    let compressed = simple_compress::<f32>(&[], &ChunkConfig::default())?;
+    let rechunked = rechunk(&compressed, PagingSpec::EqualPagesUpTo(10))?;
+    assert_eq!(rechunked, compressed);
+    Ok(())
+  }
+}
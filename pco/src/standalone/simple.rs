@@ -1,12 +1,34 @@
 use std::cmp::min;
+#[cfg(feature = "xxhash")]
+use std::hash::Hasher;
+#[cfg(feature = "xxhash")]
+use std::mem;
 
+#[cfg(feature = "compress")]
 use crate::chunk_config::ChunkConfig;
-use crate::data_types::Number;
-use crate::errors::PcoResult;
+use crate::data_types::{Number, NumberType};
+use crate::errors::{PcoError, PcoResult};
+#[cfg(feature = "compress")]
+use crate::metadata::{DeltaEncoding, Mode};
 use crate::progress::Progress;
+#[cfg(feature = "compress")]
 use crate::standalone::compressor::FileCompressor;
 use crate::standalone::decompressor::{FileDecompressor, MaybeChunkDecompressor};
-use crate::{PagingSpec, FULL_BATCH_N};
+use crate::standalone::NumberTypeOrTermination;
+#[cfg(feature = "compress")]
+use crate::PagingSpec;
+use crate::{DecompressorConfig, FULL_BATCH_N};
+#[cfg(feature = "xxhash")]
+use twox_hash::XxHash64;
+
+#[cfg(feature = "compress")]
+pub(crate) fn new_file_compressor(n: usize, config: &ChunkConfig) -> PcoResult<FileCompressor> {
+  let file_compressor = FileCompressor::default().with_n_hint(n);
+  match config.format_version {
+    Some(format_version) => file_compressor.with_format_version(format_version),
+    None => Ok(file_compressor),
+  }
+}
 
 // TODO in 0.4 make this generic to Write and make all compress methods
 // accepting a Write return the number of bytes written?
@@ -19,13 +41,14 @@ use crate::{PagingSpec, FULL_BATCH_N};
 /// chunks.
 /// For standalone, the concepts of chunk and page are conflated since each
 /// chunk has exactly one page.
+#[cfg(feature = "compress")]
 pub fn simple_compress_into<T: Number>(
   nums: &[T],
   config: &ChunkConfig,
   mut dst: &mut [u8],
 ) -> PcoResult<usize> {
   let original_length = dst.len();
-  let file_compressor = FileCompressor::default().with_n_hint(nums.len());
+  let file_compressor = new_file_compressor(nums.len(), config)?;
   dst = file_compressor.write_header(dst)?;
 
   // here we use the paging spec to determine chunks; each chunk has 1 page
@@ -46,29 +69,72 @@ pub fn simple_compress_into<T: Number>(
   Ok(original_length - dst.len())
 }
 
-/// Takes in a slice of numbers and an exact configuration and returns
-/// compressed bytes.
+/// The choices made while compressing a chunk, as reported by
+/// [`simple_compress_with_callback`].
+#[cfg(feature = "compress")]
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct ChunkDecision {
+  /// The index of the chunk within the file.
+  pub chunk_idx: usize,
+  /// The count of numbers in the chunk.
+  pub n: usize,
+  /// The mode the chunk was compressed with.
+  pub mode: Mode,
+  /// The delta encoding the chunk was compressed with.
+  pub delta_encoding: DeltaEncoding,
+  /// The compression level the chunk was compressed at.
+  pub compression_level: usize,
+  /// Whether the chunk fell back to classic mode and no delta encoding; see
+  /// [`ChunkCompressor::used_fallback`][crate::wrapped::ChunkCompressor::used_fallback].
+  pub used_fallback: bool,
+  /// Whether the chunk reconstructs its numbers only approximately; see
+  /// [`ChunkCompressor::is_lossy`][crate::wrapped::ChunkCompressor::is_lossy].
+  pub lossy: bool,
+}
+
+/// Where each chunk and page begins within a standalone file, as returned by
+/// [`simple_compress_with_layout`].
 ///
-/// Will return an error if the compressor config is invalid.
-/// This will use the `PagingSpec` in `ChunkConfig` to decide where to split
-/// chunks.
-/// For standalone, the concepts of chunk and page are conflated since each
-/// chunk has exactly one page.
-pub fn simple_compress<T: Number>(nums: &[T], config: &ChunkConfig) -> PcoResult<Vec<u8>> {
+/// Offsets are in bytes from the start of the file.
+/// Chunks and pages always begin on a byte boundary, so these are exact; the
+/// bit offset of each is 8 times its byte offset.
+/// Since each standalone chunk has exactly one page, there is one page offset
+/// per chunk.
+#[cfg(feature = "compress")]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Layout {
+  /// The offset of each chunk, starting with its number type byte.
+  pub chunk_offsets: Vec<usize>,
+  /// The offset of each page, just after its chunk's metadata.
+  pub page_offsets: Vec<usize>,
+}
+
+// Writes a whole file, splitting chunks according to the config's paging
+// spec. The chunk config is scratch space, passed in so that callers
+// compressing many files can avoid cloning the config for each one.
+#[cfg(feature = "compress")]
+fn compress_with_file_compressor<T: Number>(
+  nums: &[T],
+  file_compressor: &FileCompressor,
+  config: &ChunkConfig,
+  this_chunk_config: &mut ChunkConfig,
+  on_chunk: &mut dyn FnMut(ChunkDecision),
+  mut layout: Option<&mut Layout>,
+) -> PcoResult<Vec<u8>> {
   let mut dst = Vec::new();
-  let file_compressor = FileCompressor::default().with_n_hint(nums.len());
   file_compressor.write_header(&mut dst)?;
 
   // here we use the paging spec to determine chunks; each chunk has 1 page
   let n_per_page = config.paging_spec.n_per_page(nums.len())?;
   let mut start = 0;
-  let mut this_chunk_config = config.clone();
   let mut hinted_size = false;
-  for &page_n in &n_per_page {
+  for (chunk_idx, &page_n) in n_per_page.iter().enumerate() {
     let end = start + page_n;
     this_chunk_config.paging_spec = PagingSpec::Exact(vec![page_n]);
     let chunk_compressor =
-      file_compressor.chunk_compressor(&nums[start..end], &this_chunk_config)?;
+      file_compressor.chunk_compressor(&nums[start..end], this_chunk_config)?;
 
     if !hinted_size {
       let file_size_hint =
@@ -77,6 +143,181 @@ pub fn simple_compress<T: Number>(nums: &[T], config: &ChunkConfig) -> PcoResult
       hinted_size = true;
     }
 
+    if let Some(layout) = layout.as_deref_mut() {
+      layout.chunk_offsets.push(dst.len());
+      layout
+        .page_offsets
+        .push(dst.len() + chunk_compressor.page_offset_in_chunk());
+    }
+    chunk_compressor.write_chunk(&mut dst)?;
+    let meta = chunk_compressor.meta();
+    on_chunk(ChunkDecision {
+      chunk_idx,
+      n: page_n,
+      mode: meta.mode,
+      delta_encoding: meta.delta_encoding,
+      compression_level: this_chunk_config.compression_level,
+      used_fallback: chunk_compressor.used_fallback(),
+      lossy: chunk_compressor.is_lossy(),
+    });
+    start = end;
+  }
+
+  file_compressor.write_footer(&mut dst)?;
+  Ok(dst)
+}
+
+/// Takes in a slice of numbers and an exact configuration and returns
+/// compressed bytes.
+///
+/// Will return an error if the compressor config is invalid.
+/// This will use the `PagingSpec` in `ChunkConfig` to decide where to split
+/// chunks.
+/// For standalone, the concepts of chunk and page are conflated since each
+/// chunk has exactly one page.
+/// Empty input produces a valid file with no chunks, which decompresses to
+/// an empty `Vec`.
+#[cfg(feature = "compress")]
+pub fn simple_compress<T: Number>(nums: &[T], config: &ChunkConfig) -> PcoResult<Vec<u8>> {
+  let file_compressor = new_file_compressor(nums.len(), config)?;
+  compress_with_file_compressor(
+    nums,
+    &file_compressor,
+    config,
+    &mut config.clone(),
+    &mut |_| {},
+    None,
+  )
+}
+
+/// Like [`simple_compress`], but calls `on_chunk` after compressing each
+/// chunk with the mode, delta encoding, and other choices made for it.
+///
+/// This is useful for recording statistics about what
+/// [`ModeSpec::Auto`][crate::ModeSpec::Auto] and
+/// [`DeltaSpec::Auto`][crate::DeltaSpec::Auto] choose without decompressing
+/// the result.
+///
+/// Will return an error if the compressor config is invalid.
+#[cfg(feature = "compress")]
+pub fn simple_compress_with_callback<T: Number>(
+  nums: &[T],
+  config: &ChunkConfig,
+  on_chunk: &mut dyn FnMut(ChunkDecision),
+) -> PcoResult<Vec<u8>> {
+  let file_compressor = new_file_compressor(nums.len(), config)?;
+  compress_with_file_compressor(
+    nums,
+    &file_compressor,
+    config,
+    &mut config.clone(),
+    on_chunk,
+    None,
+  )
+}
+
+/// Like [`simple_compress`], but also returns the [`Layout`] of the
+/// compressed file, i.e. where each chunk and page begins.
+///
+/// This is useful for building an external index to seek directly to a
+/// chunk without reading the chunks before it.
+///
+/// Will return an error if the compressor config is invalid.
+#[cfg(feature = "compress")]
+pub fn simple_compress_with_layout<T: Number>(
+  nums: &[T],
+  config: &ChunkConfig,
+) -> PcoResult<(Vec<u8>, Layout)> {
+  let file_compressor = new_file_compressor(nums.len(), config)?;
+  let mut layout = Layout::default();
+  let compressed = compress_with_file_compressor(
+    nums,
+    &file_compressor,
+    config,
+    &mut config.clone(),
+    &mut |_| {},
+    Some(&mut layout),
+  )?;
+  Ok((compressed, layout))
+}
+
+/// Takes in many independent slices of numbers and returns the compressed
+/// bytes of a separate standalone file for each.
+///
+/// This is a convenience, equivalent to calling [`simple_compress`] on each
+/// slice in a loop.
+/// Compression is single-threaded; to use multiple threads, divide the
+/// slices among them and call this on each part.
+///
+/// Will return an error if the compressor config is invalid.
+#[cfg(feature = "compress")]
+pub fn simple_compress_many<T: Number>(
+  arrays: &[&[T]],
+  config: &ChunkConfig,
+) -> PcoResult<Vec<Vec<u8>>> {
+  let file_compressor = new_file_compressor(0, config)?;
+  let mut this_chunk_config = config.clone();
+  arrays
+    .iter()
+    .map(|nums| {
+      compress_with_file_compressor(
+        nums,
+        &file_compressor.clone().with_n_hint(nums.len()),
+        config,
+        &mut this_chunk_config,
+        &mut |_| {},
+        None,
+      )
+    })
+    .collect()
+}
+
+/// Takes in a slice of numbers, split into chunks of the given counts, and
+/// returns compressed bytes, compressing each chunk with its own config.
+///
+/// This allows choosing e.g. a different mode or delta encoding for
+/// each chunk.
+/// The paging spec of each config is ignored, since each entry describes
+/// exactly one chunk.
+/// The file's format version is taken from the first config, and no chunk
+/// will use a newer one.
+/// A chunk whose config has an older format version only uses features that
+/// version supports, but is still written in the file's format version.
+///
+/// Will return an error if any config is invalid or if the chunk counts do
+/// not sum to the count of numbers.
+#[cfg(feature = "compress")]
+pub fn simple_compress_with_configs<T: Number>(
+  nums: &[T],
+  chunk_configs: &[(usize, ChunkConfig)],
+) -> PcoResult<Vec<u8>> {
+  let total_n = chunk_configs.iter().map(|(n, _)| n).sum::<usize>();
+  if total_n != nums.len() {
+    return Err(PcoError::invalid_argument(format!(
+      "chunk counts sum to {} but there are {} numbers",
+      total_n,
+      nums.len(),
+    )));
+  }
+
+  let file_config = chunk_configs
+    .first()
+    .map(|(_, config)| config.clone())
+    .unwrap_or_default();
+  let file_compressor = new_file_compressor(nums.len(), &file_config)?;
+  let mut dst = Vec::new();
+  file_compressor.write_header(&mut dst)?;
+
+  let mut start = 0;
+  for (chunk_n, config) in chunk_configs {
+    let end = start + chunk_n;
+    let this_chunk_config = config
+      .clone()
+      .with_paging_spec(PagingSpec::Exact(vec![*chunk_n]));
+    let chunk_compressor =
+      file_compressor.chunk_compressor(&nums[start..end], &this_chunk_config)?;
+    // chunks can differ a lot in size, so we reserve one at a time
+    dst.reserve(chunk_compressor.chunk_size_hint());
     chunk_compressor.write_chunk(&mut dst)?;
     start = end;
   }
@@ -144,6 +385,7 @@ pub fn simple_decompress_into<T: Number>(src: &[u8], mut dst: &mut [T]) -> PcoRe
 /// [`ChunkConfig`][crate::ChunkConfig] for an explanation of compression
 /// levels).
 /// This wraps [`simple_compress`].
+#[cfg(feature = "compress")]
 pub fn simpler_compress<T: Number>(nums: &[T], compression_level: usize) -> PcoResult<Vec<u8>> {
   let config = ChunkConfig {
     compression_level,
@@ -157,22 +399,206 @@ pub fn simpler_compress<T: Number>(nums: &[T], compression_level: usize) -> PcoR
 /// Will return an error if there are any compatibility, corruption,
 /// or insufficient data issues.
 pub fn simple_decompress<T: Number>(src: &[u8]) -> PcoResult<Vec<T>> {
+  simple_decompress_with_config(src, &DecompressorConfig::default())
+}
+
+/// Takes in compressed bytes and returns a vector of numbers, enforcing the
+/// limits in the decompressor config.
+///
+/// Will return an error if there are any compatibility, corruption,
+/// or insufficient data issues, or if the file contains more numbers than
+/// the config allows.
+pub fn simple_decompress_with_config<T: Number>(
+  src: &[u8],
+  config: &DecompressorConfig,
+) -> PcoResult<Vec<T>> {
+  let (file_decompressor, mut src) = FileDecompressor::new_with_config(src, config)?;
+
+  let mut res = Vec::with_capacity(file_decompressor.n_hint());
+  let mut chunk_idx = 0;
+  let in_chunk =
+    |chunk_idx: usize| move |err: PcoError| err.with_context(format!("in chunk {}", chunk_idx));
+  while let MaybeChunkDecompressor::Some(mut chunk_decompressor) = file_decompressor
+    .chunk_decompressor(src)
+    .map_err(in_chunk(chunk_idx))?
+  {
+    config.check_file_n(res.len() + chunk_decompressor.n())?;
+    chunk_decompressor
+      .decompress_remaining_extend(&mut res)
+      .map_err(in_chunk(chunk_idx))?;
+    src = chunk_decompressor.into_src();
+    chunk_idx += 1;
+  }
+  Ok(res)
+}
+
+/// Takes in compressed bytes and returns a vector of numbers, along with the
+/// 64-bit xxHash (seed 0) of their bytes.
+///
+/// The hash covers each number's little-endian bytes in order, so it matches
+/// what a producer would get by hashing the raw numbers before compression,
+/// e.g. `XxHash64::with_seed(0)` over a little-endian `&[T]` as bytes.
+/// Unlike a checksum of the compressed bytes, this verifies the decompressed
+/// content itself.
+/// It is accumulated batch by batch during decoding.
+///
+/// Will return an error if there are any compatibility, corruption,
+/// or insufficient data issues.
+#[cfg(feature = "xxhash")]
+pub fn decompress_with_hash<T: Number>(src: &[u8]) -> PcoResult<(Vec<T>, u64)> {
+  use crate::data_types::Latent;
+
   let (file_decompressor, mut src) = FileDecompressor::new(src)?;
 
   let mut res = Vec::with_capacity(file_decompressor.n_hint());
+  let mut hasher = XxHash64::with_seed(0);
+  let num_size = mem::size_of::<T>();
+  let mut batch_bytes = Vec::with_capacity(FULL_BATCH_N * num_size);
   while let MaybeChunkDecompressor::Some(mut chunk_decompressor) =
     file_decompressor.chunk_decompressor(src)?
   {
-    chunk_decompressor.decompress_remaining_extend(&mut res)?;
+    loop {
+      let start = res.len();
+      res.resize(start + FULL_BATCH_N, T::default());
+      let progress = chunk_decompressor.decompress(&mut res[start..])?;
+      res.truncate(start + progress.n_processed);
+
+      batch_bytes.clear();
+      for &num in &res[start..] {
+        let bytes = num.transmute_to_latent().to_u64().to_le_bytes();
+        batch_bytes.extend_from_slice(&bytes[..num_size]);
+      }
+      hasher.write(&batch_bytes);
+
+      if progress.finished {
+        break;
+      }
+    }
+    src = chunk_decompressor.into_src();
+  }
+  Ok((res, hasher.finish()))
+}
+
+// Like simple_decompress, but also returns the bytes after the file's
+// termination byte.
+pub(crate) fn decompress_with_rest<T: Number>(src: &[u8]) -> PcoResult<(Vec<T>, &[u8])> {
+  let (file_decompressor, mut src) = FileDecompressor::new(src)?;
+
+  let mut res = Vec::with_capacity(file_decompressor.n_hint());
+  loop {
+    match file_decompressor.chunk_decompressor(src)? {
+      MaybeChunkDecompressor::Some(mut chunk_decompressor) => {
+        chunk_decompressor.decompress_remaining_extend(&mut res)?;
+        src = chunk_decompressor.into_src();
+      }
+      MaybeChunkDecompressor::EndOfData(rest) => return Ok((res, rest)),
+    }
+  }
+}
+
+/// Takes in compressed bytes and returns only the numbers within
+/// `[lower, upper]`, in their original order.
+///
+/// Floats are compared by their total ordering.
+/// Chunks whose bounds (see
+/// [`ChunkDecompressor::value_range`][crate::standalone::ChunkDecompressor::value_range])
+/// lie entirely within the range are returned without checking each number,
+/// and chunks entirely outside it are discarded.
+/// The bounds account for consecutive delta encoding, so this works well
+/// for sorted data like timestamps, where most chunks fall on one side of
+/// the range or the other.
+/// Bounds are tightest for first-order delta encoding, which can be chosen
+/// explicitly with [`DeltaSpec::TryConsecutive(1)`][crate::DeltaSpec::TryConsecutive].
+///
+/// Standalone chunks don't record their compressed size, so discarded chunks
+/// still get decoded to find where the next chunk begins, one batch at a
+/// time without being kept.
+/// Skipping chunks without decoding them requires an index of chunk offsets,
+/// e.g. from [`simple_compress_with_layout`], or a wrapping format.
+///
+/// Will return an error if there are any compatibility, corruption,
+/// or insufficient data issues.
+pub fn simple_decompress_range<T: Number>(src: &[u8], lower: T, upper: T) -> PcoResult<Vec<T>> {
+  let lower = lower.to_latent_ordered();
+  let upper = upper.to_latent_ordered();
+  let (file_decompressor, mut src) = FileDecompressor::new(src)?;
+
+  let mut res = Vec::new();
+  let mut scratch = Vec::new();
+  while let MaybeChunkDecompressor::Some(mut chunk_decompressor) =
+    file_decompressor.chunk_decompressor::<T, _>(src)?
+  {
+    let chunk_range = chunk_decompressor.value_range().map(|(min, max)| {
+      (
+        min.to_latent_ordered(),
+        max.to_latent_ordered(),
+      )
+    });
+    match chunk_range {
+      Some((min, max)) if min >= lower && max <= upper => {
+        chunk_decompressor.decompress_remaining_extend(&mut res)?;
+      }
+      Some((min, max)) if max < lower || min > upper => {
+        chunk_decompressor.skip_remaining()?;
+      }
+      _ => {
+        scratch.clear();
+        chunk_decompressor.decompress_remaining_extend(&mut scratch)?;
+        res.extend(scratch.iter().filter(|num| {
+          let latent = num.to_latent_ordered();
+          latent >= lower && latent <= upper
+        }));
+      }
+    }
     src = chunk_decompressor.into_src();
   }
   Ok(res)
 }
 
+/// Takes in compressed bytes and returns a vector of numbers in reverse
+/// order, last number first.
+///
+/// Decoding can only proceed forward: ANS states and delta encoding both
+/// depend on the numbers before them, and standalone chunks don't record
+/// their compressed size, so later chunks can't be found without decoding
+/// the earlier ones.
+/// So this decompresses the whole file and then reverses it in place,
+/// using a single allocation when the file has an accurate n hint.
+///
+/// Will return an error if there are any compatibility, corruption,
+/// or insufficient data issues.
+pub fn simple_decompress_reversed<T: Number>(src: &[u8]) -> PcoResult<Vec<T>> {
+  let mut res = simple_decompress(src)?;
+  res.reverse();
+  Ok(res)
+}
+
+/// Reads the header of a standalone .pco file and returns the data type of
+/// its first chunk, without decompressing any chunks.
+///
+/// Returns `None` if the file has no chunks.
+/// Will return an error if there are any compatibility, corruption,
+/// or insufficient data issues in the header, or if the data type is not
+/// part of pco's core implementation.
+pub fn peek_dtype(src: &[u8]) -> PcoResult<Option<NumberType>> {
+  let (file_decompressor, src) = FileDecompressor::new(src)?;
+  match file_decompressor.peek_number_type_or_termination(src)? {
+    NumberTypeOrTermination::Termination => Ok(None),
+    NumberTypeOrTermination::Known(number_type) => Ok(Some(number_type)),
+    NumberTypeOrTermination::Unknown(byte) => Err(PcoError::compatibility(format!(
+      "unknown number type byte: {}",
+      byte
+    ))),
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
   use crate::chunk_config::DeltaSpec;
+  use crate::errors::ErrorKind;
+  use crate::metadata::{DeltaConsecutiveConfig, DeltaEncoding};
+  use crate::wrapped;
 
   #[test]
   fn test_simple_compress_into() -> PcoResult<()> {
@@ -227,4 +653,386 @@ mod tests {
 
     Ok(())
   }
+
+  #[test]
+  fn test_simple_decompress_with_config() -> PcoResult<()> {
+    let nums = (0..600).collect::<Vec<i32>>();
+    let src = simple_compress(
+      &nums,
+      &ChunkConfig::default().with_paging_spec(PagingSpec::Exact(vec![300, 300])),
+    )?;
+
+    let decompressed = simple_decompress_with_config::<i32>(
+      &src,
+      &DecompressorConfig::default()
+        .with_max_n(Some(300))
+        .with_max_file_n(Some(600)),
+    )?;
+    assert_eq!(decompressed, nums);
+
+    let too_small_configs = [
+      DecompressorConfig::default().with_max_n(Some(299)),
+      DecompressorConfig::default().with_max_file_n(Some(599)),
+    ];
+    for config in &too_small_configs {
+      let err = simple_decompress_with_config::<i32>(&src, config).unwrap_err();
+      assert!(matches!(
+        err.kind,
+        ErrorKind::InvalidArgument
+      ));
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_simple_decompress_range() -> PcoResult<()> {
+    let nums = (0..1000).map(|i| i * 10).collect::<Vec<u32>>();
+    let config = ChunkConfig::default()
+      .with_delta_spec(DeltaSpec::None)
+      .with_paging_spec(PagingSpec::EqualPagesUpTo(100));
+    let compressed = simple_compress(&nums, &config)?;
+    let decompressed = simple_decompress_range::<u32>(&compressed, 2345, 5000)?;
+    assert_eq!(
+      decompressed,
+      (235..=500).map(|i| i * 10).collect::<Vec<_>>()
+    );
+    assert!(simple_decompress_range(&compressed, 20000, u32::MAX)?.is_empty());
+
+    // sorted timestamps get delta encoded, but their chunks are still bounded
+    let timestamps = (0..10000_i64)
+      .map(|i| 1_700_000_000_000 + i * 1000 + (i * i) % 7)
+      .collect::<Vec<_>>();
+    let config = ChunkConfig::default()
+      .with_delta_spec(DeltaSpec::TryConsecutive(1))
+      .with_paging_spec(PagingSpec::EqualPagesUpTo(1000));
+    let compressed = simple_compress(&timestamps, &config)?;
+    let (lower, upper) = (timestamps[2345], timestamps[5000]);
+    let (fd, mut src) = FileDecompressor::new(compressed.as_slice())?;
+    let mut n_disjoint = 0;
+    while let MaybeChunkDecompressor::Some(mut cd) = fd.chunk_decompressor::<i64, _>(src)? {
+      assert!(matches!(
+        cd.meta().delta_encoding,
+        DeltaEncoding::Consecutive(_)
+      ));
+      let (min, max) = cd.value_range().unwrap();
+      let mut chunk_nums = Vec::new();
+      cd.decompress_remaining_extend(&mut chunk_nums)?;
+      assert_eq!(min, chunk_nums[0]);
+      assert!(chunk_nums.iter().all(|&x| x >= min && x <= max));
+      if max < lower || min > upper {
+        n_disjoint += 1;
+      }
+      src = cd.into_src();
+    }
+    // only the chunks overlapping the range need their numbers checked
+    assert_eq!(n_disjoint, 6);
+    assert_eq!(
+      simple_decompress_range(&compressed, lower, upper)?,
+      timestamps[2345..=5000]
+    );
+
+    // lookback chunks have no bounds, so every number gets checked
+    let floats = [1.0, -0.0, f32::NAN, 0.0, 3.0, -5.0].repeat(100);
+    let config = ChunkConfig::default().with_delta_spec(DeltaSpec::TryLookback);
+    let compressed = simple_compress(&floats, &config)?;
+    let decompressed = simple_decompress_range::<f32>(&compressed, -0.0, 1.0)?;
+    assert_eq!(decompressed, [1.0, -0.0, 0.0].repeat(100));
+    Ok(())
+  }
+
+  #[test]
+  fn test_simple_decompress_reversed() -> PcoResult<()> {
+    let nums = (0..1000).map(|i| i * i - 77).collect::<Vec<i64>>();
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(300));
+    let compressed = simple_compress(&nums, &config)?;
+    let reversed = simple_decompress_reversed::<i64>(&compressed)?;
+    assert_eq!(reversed.capacity(), nums.len());
+    assert!(reversed.iter().eq(nums.iter().rev()));
+    Ok(())
+  }
+
+  #[cfg(feature = "xxhash")]
+  #[test]
+  fn test_decompress_with_hash() -> PcoResult<()> {
+    let xxhash = |bytes: &[u8]| {
+      let mut hasher = XxHash64::with_seed(0);
+      hasher.write(bytes);
+      hasher.finish()
+    };
+
+    let mut nums = (0..1000)
+      .map(|i| (i as f32).sqrt() - 7.0)
+      .collect::<Vec<_>>();
+    nums[3] = f32::NAN;
+    nums[4] = -0.0;
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(300));
+    let compressed = simple_compress(&nums, &config)?;
+    let (decompressed, hash) = decompress_with_hash::<f32>(&compressed)?;
+    let bytes = nums
+      .iter()
+      .flat_map(|num| num.to_le_bytes())
+      .collect::<Vec<_>>();
+    assert_eq!(hash, xxhash(&bytes));
+    assert_eq!(decompressed.len(), nums.len());
+    assert!(decompressed
+      .iter()
+      .zip(&nums)
+      .all(|(x, y)| x.to_bits() == y.to_bits()));
+
+    let compressed = simple_compress::<u16>(&[], &ChunkConfig::default())?;
+    let (decompressed, hash) = decompress_with_hash::<u16>(&compressed)?;
+    assert!(decompressed.is_empty());
+    assert_eq!(hash, xxhash(&[]));
+    Ok(())
+  }
+
+  #[test]
+  fn test_decompress_error_context() -> PcoResult<()> {
+    let nums = (0..1000).map(|i| i * i - 77).collect::<Vec<i64>>();
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(300));
+    let compressed = simple_compress(&nums, &config)?;
+    let truncated = &compressed[..compressed.len() - 10];
+    let err = simple_decompress::<i64>(truncated).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::InsufficientData);
+    assert!(err.message.contains("; at byte "));
+    assert!(err.message.ends_with("; in chunk 3"));
+    Ok(())
+  }
+
+  #[test]
+  fn test_decompress_corrupt_ans_bytes() -> PcoResult<()> {
+    // skewed numbers so that there are many bins with different weights
+    let nums = (0..1000_u32)
+      .map(|i| (i.wrapping_mul(2654435761) >> 22).pow(2))
+      .collect::<Vec<_>>();
+    let compressed = simple_compress(&nums, &ChunkConfig::classic())?;
+    // Corrupting each byte may give wrong numbers or an error, but must never
+    // panic or read out of bounds.
+    for byte_idx in 0..compressed.len() {
+      for flip in [0x01, 0x5a, 0xff] {
+        let mut corrupted = compressed.clone();
+        corrupted[byte_idx] ^= flip;
+        let _ = simple_decompress::<u32>(&corrupted);
+      }
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_peek_dtype() -> PcoResult<()> {
+    let src = simple_compress(&[1.0_f32, 2.0], &ChunkConfig::default())?;
+    assert_eq!(peek_dtype(&src)?, Some(NumberType::F32));
+    let src = simple_compress::<u16>(&[], &ChunkConfig::default())?;
+    assert_eq!(peek_dtype(&src)?, None);
+    assert!(peek_dtype(&[1, 2, 3]).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_chunk_n_bytes_consumed() -> PcoResult<()> {
+    let nums = (0..600).collect::<Vec<i32>>();
+    let src = simple_compress(
+      &nums,
+      &ChunkConfig::default().with_paging_spec(PagingSpec::Exact(vec![100, 500])),
+    )?;
+
+    let (fd, mut rest) = FileDecompressor::new(src.as_slice())?;
+    let mut dst = vec![0; 600];
+    while let MaybeChunkDecompressor::Some(mut cd) = fd.chunk_decompressor::<i32, _>(rest)? {
+      cd.decompress(&mut dst)?;
+      let n_bytes = cd.n_bytes_consumed();
+      let prev_len = rest.len();
+      rest = cd.into_src();
+      assert_eq!(n_bytes, prev_len - rest.len());
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_simple_compress_with_configs() -> PcoResult<()> {
+    let bursty = (0..1000_i64)
+      .map(|i| if i % 100 < 3 { i * 1000 } else { 0 })
+      .collect::<Vec<_>>();
+    let smooth = (0..2000_i64).map(|i| i * i).collect::<Vec<_>>();
+    let nums = [bursty.clone(), smooth.clone()].concat();
+    let chunk_configs = [
+      (
+        bursty.len(),
+        ChunkConfig::default().with_delta_spec(DeltaSpec::None),
+      ),
+      (
+        smooth.len(),
+        ChunkConfig::default().with_delta_spec(DeltaSpec::TryConsecutive(2)),
+      ),
+    ];
+    let compressed = simple_compress_with_configs(&nums, &chunk_configs)?;
+    assert_eq!(simple_decompress::<i64>(&compressed)?, nums);
+
+    // each chunk's delta encoding comes from its own config
+    let (fd, mut src) = FileDecompressor::new(compressed.as_slice())?;
+    let mut delta_encodings = Vec::new();
+    while let MaybeChunkDecompressor::Some(mut cd) = fd.chunk_decompressor::<i64, _>(src)? {
+      delta_encodings.push(cd.meta().delta_encoding);
+      let mut chunk_nums = Vec::new();
+      cd.decompress_remaining_extend(&mut chunk_nums)?;
+      src = cd.into_src();
+    }
+    assert!(matches!(
+      delta_encodings[..],
+      [
+        DeltaEncoding::None,
+        DeltaEncoding::Consecutive(DeltaConsecutiveConfig { order: 2, .. }),
+      ]
+    ));
+
+    assert!(simple_compress_with_configs(&nums[1..], &chunk_configs).is_err());
+    assert_eq!(
+      simple_compress_with_configs::<i64>(&[], &[])?,
+      simple_compress::<i64>(&[], &ChunkConfig::default())?,
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_simple_compress_with_configs_mixed_versions() -> PcoResult<()> {
+    let nums = (0..3000_i64).map(|i| i * i % 1009).collect::<Vec<_>>();
+    let old_config = ChunkConfig::default()
+      .with_format_version(Some(1))
+      .with_delta_spec(DeltaSpec::TryConsecutive(2));
+    for chunk_configs in [
+      [(1500, ChunkConfig::default()), (1500, old_config.clone())],
+      [(1500, old_config.clone()), (1500, ChunkConfig::default())],
+    ] {
+      let compressed = simple_compress_with_configs(&nums, &chunk_configs)?;
+      assert_eq!(simple_decompress::<i64>(&compressed)?, nums);
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_simple_compress_with_callback() -> PcoResult<()> {
+    let nums = (0..3000_i64).map(|i| i * i).collect::<Vec<_>>();
+    let config = ChunkConfig::default()
+      .with_compression_level(4)
+      .with_paging_spec(PagingSpec::EqualPagesUpTo(1000));
+    let mut decisions = Vec::new();
+    let compressed = simple_compress_with_callback(&nums, &config, &mut |decision| {
+      decisions.push(decision)
+    })?;
+    assert_eq!(compressed, simple_compress(&nums, &config)?);
+
+    assert_eq!(decisions.len(), 3);
+    for (chunk_idx, decision) in decisions.iter().enumerate() {
+      assert_eq!(decision.chunk_idx, chunk_idx);
+      assert_eq!(decision.n, 1000);
+      assert_eq!(decision.mode, Mode::Classic);
+      assert!(matches!(
+        decision.delta_encoding,
+        DeltaEncoding::Consecutive(_)
+      ));
+      assert_eq!(decision.compression_level, 4);
+      assert!(!decision.used_fallback);
+      assert!(!decision.lossy);
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_simple_compress_with_layout() -> PcoResult<()> {
+    let nums = (0..1000_i64).map(|i| i * i % 777).collect::<Vec<_>>();
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(300));
+    let (compressed, layout) = simple_compress_with_layout(&nums, &config)?;
+    assert_eq!(compressed, simple_compress(&nums, &config)?);
+    assert_eq!(layout.chunk_offsets.len(), 4);
+    assert_eq!(layout.page_offsets.len(), 4);
+
+    let (fd, _) = FileDecompressor::new(compressed.as_slice())?;
+    let wrapped_header = wrapped::FileCompressor::default().write_header(Vec::new())?;
+    let (wrapped_fd, _) = wrapped::FileDecompressor::new(wrapped_header.as_slice())?;
+    let mut start = 0;
+    for (&chunk_offset, &page_offset) in layout.chunk_offsets.iter().zip(&layout.page_offsets) {
+      // we can seek directly to each chunk
+      let MaybeChunkDecompressor::Some(mut cd) =
+        fd.chunk_decompressor::<i64, _>(&compressed[chunk_offset..])?
+      else {
+        panic!("expected a chunk at offset {}", chunk_offset);
+      };
+      let n = cd.n();
+      let mut chunk_nums = Vec::new();
+      cd.decompress_remaining_extend(&mut chunk_nums)?;
+      assert_eq!(chunk_nums, nums[start..start + n]);
+
+      // and each page begins right after its chunk's 4-byte preamble and
+      // metadata
+      let (wrapped_cd, _) =
+        wrapped_fd.chunk_decompressor::<i64, _>(&compressed[chunk_offset + 4..])?;
+      assert_eq!(
+        chunk_offset + 4 + wrapped_cd.meta_n_bytes(),
+        page_offset
+      );
+      let mut pd = wrapped_cd.page_decompressor(&compressed[page_offset..], n)?;
+      let mut page_nums = vec![0; n];
+      pd.decompress(&mut page_nums)?;
+      assert_eq!(page_nums, chunk_nums);
+      start += n;
+    }
+    assert_eq!(start, nums.len());
+    Ok(())
+  }
+
+  #[test]
+  fn test_simple_compress_many() -> PcoResult<()> {
+    let arrays = (0..50_i32)
+      .map(|i| (0..i).map(|j| j * i - 7).collect::<Vec<_>>())
+      .collect::<Vec<_>>();
+    let array_refs = arrays.iter().map(Vec::as_slice).collect::<Vec<_>>();
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(20));
+    let compressed = simple_compress_many(&array_refs, &config)?;
+    assert_eq!(compressed.len(), arrays.len());
+    for (nums, bytes) in arrays.iter().zip(&compressed) {
+      assert_eq!(bytes, &simple_compress(nums, &config)?);
+    }
+
+    assert!(simple_compress_many::<i32>(&[], &config)?.is_empty());
+    assert!(simple_compress_many(
+      &array_refs,
+      &config.with_compression_level(99)
+    )
+    .is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_empty_round_trips() -> PcoResult<()> {
+    let config = ChunkConfig::default();
+    let src = simple_compress::<i64>(&[], &config)?;
+    assert_eq!(
+      simple_decompress::<i64>(&src)?,
+      Vec::<i64>::new()
+    );
+    assert_eq!(simpler_compress::<i64>(&[], 8)?, src);
+
+    let mut dst = vec![0; src.len()];
+    assert_eq!(
+      simple_compress_into::<i64>(&[], &config, &mut dst)?,
+      src.len()
+    );
+    assert_eq!(dst, src);
+    let progress = simple_decompress_into::<i64>(&src, &mut [])?;
+    assert_eq!(progress.n_processed, 0);
+    assert!(progress.finished);
+
+    let (with_layout, layout) = simple_compress_with_layout::<i64>(&[], &config)?;
+    assert_eq!(with_layout, src);
+    assert!(layout.chunk_offsets.is_empty());
+    assert!(layout.page_offsets.is_empty());
+    let mut n_decisions = 0;
+    simple_compress_with_callback::<i64>(&[], &config, &mut |_| n_decisions += 1)?;
+    assert_eq!(n_decisions, 0);
+
+    assert!(simple_decompress_range::<i64>(&src, 0, 10)?.is_empty());
+    assert!(simple_decompress_reversed::<i64>(&src)?.is_empty());
+    Ok(())
+  }
 }
@@ -0,0 +1,172 @@
+use std::io::Write;
+
+use crate::data_types::Number;
+use crate::errors::{PcoError, PcoResult};
+use crate::standalone::FileCompressor;
+use crate::ChunkConfig;
+
+/// Compresses numbers of unknown count into a standalone .pco file,
+/// writing each chunk as soon as it fills.
+///
+/// Numbers are buffered until there are `chunk_n` of them, then compressed
+/// as a chunk, so at most one chunk's worth of numbers is held in memory.
+/// Call [`finish`][StreamCompressor::finish] to write the last, possibly
+/// partial chunk and the footer.
+///
+/// ```
+/// use pco::ChunkConfig;
+/// use pco::standalone::{simple_decompress, StreamCompressor};
+/// # use pco::errors::PcoResult;
+///
+/// # fn main() -> PcoResult<()> {
+/// let mut compressor = StreamCompressor::new(Vec::new(), &ChunkConfig::default(), 1000)?;
+/// compressor.extend((0..2500).map(|i| i as f32))?;
+/// let compressed = compressor.finish()?;
+/// assert_eq!(simple_decompress::<f32>(&compressed)?.len(), 2500);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct StreamCompressor<T: Number, W: Write> {
+  file_compressor: FileCompressor,
+  config: ChunkConfig,
+  chunk_n: usize,
+  buffer: Vec<T>,
+  dst: W,
+}
+
+impl<T: Number, W: Write> StreamCompressor<T, W> {
+  /// Creates a compressor that writes chunks of `chunk_n` numbers (except
+  /// possibly the last) to the destination, and writes the header.
+  ///
+  /// The config's paging spec is ignored, since chunks are split by count.
+  /// Will return an error if `chunk_n` is 0, the format version is invalid,
+  /// or the provided `Write` errors.
+  pub fn new(mut dst: W, config: &ChunkConfig, chunk_n: usize) -> PcoResult<Self> {
+    if chunk_n == 0 {
+      return Err(PcoError::invalid_argument(
+        "chunk_n must be positive",
+      ));
+    }
+
+    let mut file_compressor = FileCompressor::default();
+    if let Some(format_version) = config.format_version {
+      file_compressor = file_compressor.with_format_version(format_version)?;
+    }
+    file_compressor.write_header(&mut dst)?;
+    Ok(Self {
+      file_compressor,
+      config: config.clone(),
+      chunk_n,
+      buffer: Vec::with_capacity(chunk_n),
+      dst,
+    })
+  }
+
+  fn write_buffered_chunk(&mut self) -> PcoResult<()> {
+    let chunk_compressor = self
+      .file_compressor
+      .chunk_compressor(&self.buffer, &self.config)?;
+    chunk_compressor.write_chunk(&mut self.dst)?;
+    self.buffer.clear();
+    Ok(())
+  }
+
+  /// Adds a number, writing a chunk if one just filled.
+  ///
+  /// Will return an error if the config is invalid or the provided `Write`
+  /// errors.
+  pub fn push(&mut self, num: T) -> PcoResult<()> {
+    self.buffer.push(num);
+    if self.buffer.len() == self.chunk_n {
+      self.write_buffered_chunk()?;
+    }
+    Ok(())
+  }
+
+  /// Adds all numbers from the iterator, writing chunks as they fill.
+  ///
+  /// The iterator may be of any length, including unbounded.
+  /// Will return an error if the config is invalid or the provided `Write`
+  /// errors.
+  pub fn extend<I: IntoIterator<Item = T>>(&mut self, nums: I) -> PcoResult<()> {
+    for num in nums {
+      self.push(num)?;
+    }
+    Ok(())
+  }
+
+  /// Writes any remaining buffered numbers as a final chunk, followed by the
+  /// footer, and returns the destination.
+  ///
+  /// Will return an error if the config is invalid or the provided `Write`
+  /// errors.
+  pub fn finish(mut self) -> PcoResult<W> {
+    if !self.buffer.is_empty() {
+      self.write_buffered_chunk()?;
+    }
+    self.file_compressor.write_footer(&mut self.dst)?;
+    Ok(self.dst)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::standalone::{
+    simple_compress, simple_decompress, FileDecompressor, MaybeChunkDecompressor,
+  };
+  use crate::PagingSpec;
+
+  fn chunk_ns(src: &[u8]) -> PcoResult<Vec<usize>> {
+    let (fd, mut src) = FileDecompressor::new(src)?;
+    let mut res = Vec::new();
+    while let MaybeChunkDecompressor::Some(mut cd) = fd.chunk_decompressor::<i64, _>(src)? {
+      res.push(cd.n());
+      let mut nums = Vec::new();
+      cd.decompress_remaining_extend(&mut nums)?;
+      src = cd.into_src();
+    }
+    Ok(res)
+  }
+
+  #[test]
+  fn test_stream_compressor() -> PcoResult<()> {
+    let config = ChunkConfig::default();
+    let nums = (0..2500_i64).map(|i| i * i).collect::<Vec<_>>();
+    let mut compressor = StreamCompressor::new(Vec::new(), &config, 1000)?;
+    compressor.extend(nums.iter().copied())?;
+    let compressed = compressor.finish()?;
+    assert_eq!(simple_decompress::<i64>(&compressed)?, nums);
+    assert_eq!(chunk_ns(&compressed)?, vec![1000, 1000, 500]);
+
+    // the result should match compressing the same chunks all at once
+    let exact_config = config.with_paging_spec(PagingSpec::Exact(vec![1000, 1000, 500]));
+    let expected = simple_compress(&nums, &exact_config)?;
+    // except for the n hint in the header, which we can't know when streaming
+    let (_, expected_body) = FileDecompressor::new(expected.as_slice())?;
+    assert!(compressed.ends_with(expected_body));
+    Ok(())
+  }
+
+  #[test]
+  fn test_stream_compressor_edge_cases() -> PcoResult<()> {
+    let config = ChunkConfig::default();
+    let compressed = StreamCompressor::<i64, _>::new(Vec::new(), &config, 10)?.finish()?;
+    assert_eq!(
+      compressed,
+      simple_compress::<i64>(&[], &config)?
+    );
+
+    // an exactly full last chunk shouldn't leave an empty chunk behind
+    let mut compressor = StreamCompressor::new(Vec::new(), &config, 10)?;
+    compressor.extend(0..20_i64)?;
+    assert_eq!(
+      chunk_ns(&compressor.finish()?)?,
+      vec![10, 10]
+    );
+
+    assert!(StreamCompressor::<i64, _>::new(Vec::new(), &config, 0).is_err());
+    Ok(())
+  }
+}
@@ -0,0 +1,90 @@
+use ndarray::ArrayView1;
+
+use crate::data_types::Number;
+use crate::errors::PcoResult;
+use crate::standalone::simple::{new_file_compressor, simple_compress};
+use crate::{ChunkConfig, PagingSpec};
+
+/// Takes in a 1D ndarray view and an exact configuration and returns
+/// compressed bytes.
+///
+/// Contiguous views are compressed in place.
+/// Strided views, such as a column of a row-major matrix, are gathered one
+/// chunk at a time, so the full array is never copied; the extra memory is
+/// no more than the latents the chunk compressor materializes anyway.
+/// The result is identical to calling [`simple_compress`] on the view's
+/// elements.
+///
+/// Will return an error if the compressor config is invalid.
+///
+/// ```
+/// use ndarray::Array2;
+/// use pco::ChunkConfig;
+/// use pco::standalone::{compress_ndarray, simple_decompress};
+/// # use pco::errors::PcoResult;
+///
+/// # fn main() -> PcoResult<()> {
+/// let matrix = Array2::from_shape_fn((1000, 3), |(i, j)| (i * 3 + j) as f64);
+/// let compressed = compress_ndarray(matrix.column(1), &ChunkConfig::default())?;
+/// assert_eq!(simple_decompress::<f64>(&compressed)?, matrix.column(1).to_vec());
+/// # Ok(())
+/// # }
+/// ```
+pub fn compress_ndarray<T: Number>(
+  view: ArrayView1<T>,
+  config: &ChunkConfig,
+) -> PcoResult<Vec<u8>> {
+  if let Some(nums) = view.as_slice() {
+    return simple_compress(nums, config);
+  }
+
+  let n = view.len();
+  let file_compressor = new_file_compressor(n, config)?;
+  let mut dst = Vec::new();
+  file_compressor.write_header(&mut dst)?;
+
+  // here we use the paging spec to determine chunks; each chunk has 1 page
+  let n_per_page = config.paging_spec.n_per_page(n)?;
+  let mut this_chunk_config = config.clone();
+  let mut chunk_nums = Vec::with_capacity(n_per_page.iter().copied().max().unwrap_or(0));
+  let mut strided_nums = view.iter();
+  for &page_n in &n_per_page {
+    chunk_nums.clear();
+    chunk_nums.extend(strided_nums.by_ref().take(page_n).copied());
+    this_chunk_config.paging_spec = PagingSpec::Exact(vec![page_n]);
+    let chunk_compressor = file_compressor.chunk_compressor(&chunk_nums, &this_chunk_config)?;
+    chunk_compressor.write_chunk(&mut dst)?;
+  }
+
+  file_compressor.write_footer(&mut dst)?;
+  Ok(dst)
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::{s, Array1, Array2};
+
+  use super::*;
+
+  #[test]
+  fn test_compress_ndarray() -> PcoResult<()> {
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(300));
+    let matrix = Array2::from_shape_fn((1000, 4), |(i, j)| (i * i + j) as i64);
+    let views = [
+      matrix.column(2),
+      matrix.row(7),
+      matrix.column(0).slice_move(s![..;-3]),
+    ];
+    for view in views {
+      let expected = simple_compress(&view.to_vec(), &config)?;
+      assert_eq!(compress_ndarray(view, &config)?, expected);
+    }
+
+    let empty = Array1::<i64>::zeros(0);
+    assert_eq!(
+      compress_ndarray(empty.view(), &config)?,
+      simple_compress::<i64>(&[], &config)?
+    );
+    Ok(())
+  }
+}
@@ -1,3 +1,4 @@
+use crate::constants::CURRENT_FORMAT_VERSION;
 use crate::data_types::Number;
 use crate::errors::PcoResult;
 use crate::{standalone, ChunkConfig, DeltaSpec, ModeSpec};
@@ -6,6 +7,12 @@ use std::fs;
 use std::path::PathBuf;
 use std::str::FromStr;
 
+// Each test here checks that a golden .pco file, written by the pco version in
+// its name, still decompresses to the expected numbers. Goldens are only
+// written when the test runs on the matching crate version and the file
+// doesn't exist yet, so they're never regenerated. When a release adds to the
+// format, add new goldens for it; never modify or remove old ones.
+
 fn get_asset_dir() -> PathBuf {
   PathBuf::from_str(env!("CARGO_MANIFEST_DIR"))
     .unwrap()
@@ -194,3 +201,151 @@ fn v0_4_0_lookback_delta() -> PcoResult<()> {
   assert_compatible(version, name, &nums)?;
   Ok(())
 }
+
+#[test]
+fn v0_4_1_secondary_delta() -> PcoResult<()> {
+  // v0.4.1 introduced format version 3, allowing delta encoding of secondary
+  // latents
+  let version = "0.4.1";
+  let name = "secondary_delta";
+  let nums = (0..2000_i64)
+    .map(|i| i * 1000 + (i * i) % 7)
+    .collect::<Vec<_>>();
+  let config = ChunkConfig::default()
+    .with_mode_spec(ModeSpec::TryIntMult(1000))
+    .with_delta_spec(DeltaSpec::TryConsecutive(1))
+    .with_secondary_uses_delta(true);
+  simple_write_if_version_matches(version, name, &nums, &config)?;
+  assert_compatible(version, name, &nums)?;
+  Ok(())
+}
+
+fn check_classic_dtype<T: Number>(name: &str, nums: &[T]) -> PcoResult<()> {
+  let version = "0.4.1";
+  let config = ChunkConfig::classic();
+  simple_write_if_version_matches(version, name, nums, &config)?;
+  assert_compatible(version, name, nums)
+}
+
+#[test]
+fn v0_4_1_dtypes() -> PcoResult<()> {
+  // one golden for each dtype not covered by the goldens above
+  // (v0.4.1 also introduced format version 4, adding u8)
+  check_classic_dtype(
+    "classic_u8",
+    &(0..2000).map(|i| (i * i % 251) as u8).collect::<Vec<_>>(),
+  )?;
+  check_classic_dtype(
+    "classic_u16",
+    &(0..2000).map(|i| (i * 37) as u16).collect::<Vec<_>>(),
+  )?;
+  check_classic_dtype(
+    "classic_i16",
+    &(0..2000)
+      .map(|i| (i * 37 - 30000) as i16)
+      .collect::<Vec<_>>(),
+  )?;
+  check_classic_dtype(
+    "classic_u64",
+    &(0..2000).map(|i| u64::MAX - i * i * i).collect::<Vec<_>>(),
+  )?;
+  check_classic_dtype(
+    "classic_i64",
+    &(0..2000).map(|i| i64::MIN + i * 7).collect::<Vec<_>>(),
+  )?;
+  check_classic_dtype(
+    "classic_f64",
+    &(0..2000)
+      .map(|i| (i as f64 * 0.01).sin() * 1e100)
+      .chain([f64::NAN, -0.0, f64::INFINITY, f64::NEG_INFINITY])
+      .collect::<Vec<_>>(),
+  )?;
+  Ok(())
+}
+
+#[test]
+fn older_format_versions() -> PcoResult<()> {
+  let nums = (0..2000).map(|i| i * 1000).collect::<Vec<i64>>();
+  for format_version in 0..=CURRENT_FORMAT_VERSION {
+    let config = ChunkConfig::default()
+      .with_delta_spec(DeltaSpec::TryConsecutive(1))
+      .with_format_version(Some(format_version));
+    let compressed = standalone::simple_compress(&nums, &config)?;
+    let (fd, _) = standalone::FileDecompressor::new(compressed.as_slice())?;
+    assert_eq!(fd.format_version(), format_version);
+    let decompressed = standalone::simple_decompress::<i64>(&compressed)?;
+    assert_nums_eq(&decompressed, &nums);
+  }
+
+  // explicitly requesting features the format version lacks should fail
+  let config = ChunkConfig::default().with_format_version(Some(2));
+  assert!(standalone::simple_compress(
+    &nums,
+    &config.clone().with_delta_spec(DeltaSpec::TryLookback)
+  )
+  .is_err());
+  let config = ChunkConfig::default().with_format_version(Some(3));
+  assert!(standalone::simple_compress(&[1_u8, 2], &config).is_err());
+  assert!(standalone::simple_compress(
+    &[1.0_f32, 2.0],
+    &config.with_mode_spec(ModeSpec::TryFloatMultLossy(0.5))
+  )
+  .is_err());
+  let config = ChunkConfig::default().with_format_version(Some(1));
+  assert!(standalone::simple_compress(&generate_pseudorandom_f16s(), &config).is_err());
+  assert!(standalone::simple_compress(
+    &[1.0_f32, 2.0],
+    &config.with_mode_spec(ModeSpec::TryFloatQuant(3))
+  )
+  .is_err());
+  let config = ChunkConfig::default().with_format_version(Some(CURRENT_FORMAT_VERSION + 1));
+  assert!(standalone::simple_compress(&nums, &config).is_err());
+  Ok(())
+}
+
+#[test]
+fn mixed_format_versions_in_one_file() -> PcoResult<()> {
+  let nums = (0..3000)
+    .map(|i| (i as i64 * i as i64) % 1009)
+    .collect::<Vec<_>>();
+  let configs = [
+    ChunkConfig::default(),
+    ChunkConfig::default()
+      .with_format_version(Some(1))
+      .with_delta_spec(DeltaSpec::TryConsecutive(2)),
+    ChunkConfig::default()
+      .with_format_version(Some(0))
+      .with_delta_spec(DeltaSpec::TryConsecutive(1)),
+    ChunkConfig::default().with_delta_spec(DeltaSpec::TryLookback),
+  ];
+
+  for file_version in [1, CURRENT_FORMAT_VERSION] {
+    let fc = standalone::FileCompressor::default().with_format_version(file_version)?;
+    let mut compressed = fc.write_header(Vec::new())?;
+    let mut expected = Vec::new();
+    for (chunk, config) in nums.chunks(750).zip(&configs) {
+      let is_lookback = config.delta_spec == DeltaSpec::TryLookback;
+      let cc = fc.chunk_compressor(chunk, config);
+      if is_lookback && file_version < 3 {
+        // the older file can't hold this chunk's explicitly requested feature
+        assert!(cc.is_err());
+        continue;
+      }
+      compressed = cc?.write_chunk(compressed)?;
+      expected.extend_from_slice(chunk);
+    }
+    compressed = fc.write_footer(compressed)?;
+
+    let decompressed = standalone::simple_decompress::<i64>(&compressed)?;
+    assert_nums_eq(&decompressed, &expected);
+  }
+
+  // chunks may not use features their own config's format version lacks, even
+  // if the file's format version supports them
+  let fc = standalone::FileCompressor::default();
+  let config = ChunkConfig::default()
+    .with_format_version(Some(2))
+    .with_delta_spec(DeltaSpec::TryLookback);
+  assert!(fc.chunk_compressor(&nums, &config).is_err());
+  Ok(())
+}
@@ -0,0 +1,122 @@
+use std::thread;
+
+use crate::chunk_config::{ChunkConfig, PagingSpec};
+use crate::data_types::Number;
+use crate::errors::PcoResult;
+use crate::standalone::{simple_compress, FileCompressor};
+
+// Compresses each chunk on one of `n_threads` threads, mimicking how a
+// parallel compressor would work. Chunk boundaries come only from the paging
+// spec, so the output must not depend on the thread count or scheduling.
+fn compress_with_threads<T: Number>(
+  nums: &[T],
+  config: &ChunkConfig,
+  n_threads: usize,
+) -> PcoResult<Vec<u8>> {
+  let fc = FileCompressor::default().with_n_hint(nums.len());
+  let n_per_chunk = config.paging_spec.n_per_page(nums.len())?;
+  let mut chunks = Vec::new();
+  let mut start = 0;
+  for (chunk_idx, &chunk_n) in n_per_chunk.iter().enumerate() {
+    let chunk_config = config
+      .clone()
+      .with_paging_spec(PagingSpec::Exact(vec![chunk_n]));
+    chunks.push((
+      chunk_idx,
+      &nums[start..start + chunk_n],
+      chunk_config,
+    ));
+    start += chunk_n;
+  }
+
+  let mut compressed_chunks = thread::scope(|s| {
+    let handles = (0..n_threads)
+      .map(|thread_idx| {
+        let fc = &fc;
+        let chunks = &chunks;
+        s.spawn(move || -> PcoResult<Vec<(usize, Vec<u8>)>> {
+          // work through this thread's chunks backward to vary the order
+          // chunks finish in
+          let mut res = Vec::new();
+          for (chunk_idx, chunk_nums, chunk_config) in chunks.iter().rev() {
+            if chunk_idx % n_threads == thread_idx {
+              let cc = fc.chunk_compressor(chunk_nums, chunk_config)?;
+              res.push((*chunk_idx, cc.write_chunk(Vec::new())?));
+            }
+          }
+          Ok(res)
+        })
+      })
+      .collect::<Vec<_>>();
+
+    let mut compressed_chunks = Vec::new();
+    for handle in handles {
+      compressed_chunks.extend(handle.join().unwrap()?);
+    }
+    PcoResult::Ok(compressed_chunks)
+  })?;
+  compressed_chunks.sort_by_key(|(chunk_idx, _)| *chunk_idx);
+
+  let mut dst = fc.write_header(Vec::new())?;
+  for (_, bytes) in compressed_chunks {
+    dst.extend(bytes);
+  }
+  fc.write_footer(dst)
+}
+
+fn assert_deterministic<T: Number>(nums: &[T], config: &ChunkConfig) -> PcoResult<()> {
+  let expected = simple_compress(nums, config)?;
+  for n_threads in [1, 2, 8] {
+    let compressed = compress_with_threads(nums, config, n_threads)?;
+    assert_eq!(
+      compressed, expected,
+      "n_threads={}",
+      n_threads
+    );
+  }
+  Ok(())
+}
+
+#[test]
+fn test_deterministic_across_threads() -> PcoResult<()> {
+  let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(3000));
+
+  let floats = (0..20000)
+    .map(|i| ((i % 1000) as f64 * 0.1).sin() + (i / 1000) as f64)
+    .collect::<Vec<_>>();
+  assert_deterministic(&floats, &config)?;
+
+  let ints = (0..20000_i64)
+    .map(|i| (i * 7919) % 10007 * 1000 + i)
+    .collect::<Vec<_>>();
+  assert_deterministic(&ints, &config)?;
+  assert_deterministic(
+    &ints,
+    &config.with_paging_spec(PagingSpec::Exact(vec![1, 9999, 5000, 5000])),
+  )?;
+  Ok(())
+}
+
+#[test]
+fn test_sampling_seed_reproducible() -> PcoResult<()> {
+  let floats = (0..20000)
+    .map(|i| (i % 777) as f64 * 0.1 + (i / 777) as f64 * 0.01)
+    .collect::<Vec<_>>();
+  let ints = (0..20000_i64)
+    .map(|i| (i * 7919) % 10007 * 1000 + i % 3)
+    .collect::<Vec<_>>();
+  let default_config = ChunkConfig::default();
+  assert_eq!(
+    simple_compress(
+      &floats,
+      &default_config.clone().with_sampling_seed(0)
+    )?,
+    simple_compress(&floats, &default_config)?,
+  );
+  for seed in [1, 12345] {
+    let config = default_config.clone().with_sampling_seed(seed);
+    assert_deterministic(&floats, &config)?;
+    assert_deterministic(&ints, &config)?;
+  }
+  Ok(())
+}
@@ -4,9 +4,10 @@ use std::io::Write;
 
 use better_io::{BetterBufRead, BetterBufReader};
 
-use crate::chunk_config::{ChunkConfig, DeltaSpec};
+use crate::chunk_config::{ChunkConfig, DeltaSpec, ModeSpec};
 use crate::errors::PcoResult;
-use crate::wrapped::{FileCompressor, FileDecompressor, PageDecompressor};
+use crate::metadata::Mode;
+use crate::wrapped::{guarantee, FileCompressor, FileDecompressor, PageDecompressor};
 use crate::{PagingSpec, FULL_BATCH_N};
 
 struct Chunk {
@@ -51,14 +52,17 @@ fn test_wrapped_compress<W: Write>(chunks: &[Chunk], dst: W) -> PcoResult<W> {
   Ok(dst)
 }
 
-fn test_wrapped_decompress<R: BetterBufRead>(chunks: &[Chunk], src: R) -> PcoResult<()> {
+// returns the total byte size of all chunk metas and pages
+fn test_wrapped_decompress<R: BetterBufRead>(chunks: &[Chunk], src: R) -> PcoResult<usize> {
   let (fd, mut src) = FileDecompressor::new(src)?;
+  let mut n_bytes = 0;
 
   // antagonistically keep setting the buf read capacity to 0
   for chunk in chunks {
     src.resize_capacity(0);
     let (cd, new_src) = fd.chunk_decompressor(src)?;
     src = new_src;
+    n_bytes += cd.meta_n_bytes();
 
     let mut page_start = 0;
     let n_per_page = chunk.config.paging_spec.n_per_page(chunk.nums.len())?;
@@ -68,6 +72,7 @@ fn test_wrapped_decompress<R: BetterBufRead>(chunks: &[Chunk], src: R) -> PcoRes
       src.resize_capacity(0);
       let mut pd = cd.page_decompressor(src, page_n)?;
       let page_nums = decompress_by_batch(&mut pd, page_n)?;
+      n_bytes += pd.n_bytes_consumed();
       src = pd.into_src();
 
       assert_eq!(&page_nums, &chunk.nums[page_start..page_end]);
@@ -75,14 +80,18 @@ fn test_wrapped_decompress<R: BetterBufRead>(chunks: &[Chunk], src: R) -> PcoRes
     }
   }
 
-  Ok(())
+  Ok(n_bytes)
 }
 
 fn test_wrapped(chunks: &[Chunk]) -> PcoResult<()> {
   // IN MEMORY
   let mut compressed = Vec::new();
   test_wrapped_compress(chunks, &mut compressed)?;
-  test_wrapped_decompress(chunks, compressed.as_slice())?;
+  let n_bytes = test_wrapped_decompress(chunks, compressed.as_slice())?;
+  assert_eq!(
+    n_bytes,
+    compressed.len() - guarantee::header_size()
+  );
 
   // ON DISK
   let file_path = std::env::temp_dir().join("pco_test_file");
@@ -90,7 +99,8 @@ fn test_wrapped(chunks: &[Chunk]) -> PcoResult<()> {
   test_wrapped_compress(chunks, f)?;
   let f = File::open(file_path)?;
   let buf_read = BetterBufReader::new(&[], f, 0);
-  test_wrapped_decompress(chunks, buf_read)?;
+  let n_bytes_on_disk = test_wrapped_decompress(chunks, buf_read)?;
+  assert_eq!(n_bytes_on_disk, n_bytes);
 
   Ok(())
 }
@@ -127,3 +137,66 @@ fn test_low_level_wrapped() -> PcoResult<()> {
     },
   ])
 }
+
+#[test]
+fn test_wrapped_per_chunk_modes() -> PcoResult<()> {
+  let nums = (0..1000).map(|i| i * 7 + 3).collect::<Vec<u32>>();
+  let configs = [
+    ChunkConfig::default().with_mode_spec(ModeSpec::Classic),
+    ChunkConfig::default().with_mode_spec(ModeSpec::TryIntMult(7)),
+  ];
+  let fc = FileCompressor::default();
+  let mut dst = fc.write_header(Vec::new())?;
+  for config in &configs {
+    let cc = fc.chunk_compressor(&nums, config)?;
+    dst = cc.write_chunk_meta(dst)?;
+    dst = cc.write_page(0, dst)?;
+  }
+
+  let (fd, mut src) = FileDecompressor::new(dst.as_slice())?;
+  let mut modes = Vec::new();
+  for _ in &configs {
+    let (cd, new_src) = fd.chunk_decompressor::<u32, _>(src)?;
+    modes.push(cd.meta().mode);
+    let mut pd = cd.page_decompressor(new_src, nums.len())?;
+    assert_eq!(
+      decompress_by_batch(&mut pd, nums.len())?,
+      nums
+    );
+    src = pd.into_src();
+  }
+  assert!(matches!(
+    modes[..],
+    [Mode::Classic, Mode::IntMult(_)]
+  ));
+  Ok(())
+}
+
+#[test]
+fn test_wrapped_tiny_page_into_array() -> PcoResult<()> {
+  // tiny pages with secondary and delta latents decompress straight into a
+  // fixed-size array
+  let nums = (0..100).map(|i| (i % 9) * 7 + 3).collect::<Vec<u32>>();
+  let config = ChunkConfig::default()
+    .with_mode_spec(ModeSpec::TryIntMult(7))
+    .with_delta_spec(DeltaSpec::TryLookback);
+  let fc = FileCompressor::default();
+  let cc = fc.chunk_compressor(&nums, &config)?;
+  let meta = fc.write_header(Vec::new())?;
+  let meta = cc.write_chunk_meta(meta)?;
+  let page = cc.write_page(0, Vec::new())?;
+
+  let (fd, src) = FileDecompressor::new(meta.as_slice())?;
+  let (cd, _) = fd.chunk_decompressor::<u32, _>(src)?;
+  assert!(matches!(cd.meta().mode, Mode::IntMult(_)));
+  let mut dst = [0_u32; 100];
+  let mut pd = cd.page_decompressor(page.as_slice(), nums.len())?;
+  let progress = pd.decompress(&mut dst)?;
+  assert!(progress.finished);
+  assert_eq!(dst.as_slice(), nums.as_slice());
+
+  // a destination too small for the page is rejected
+  let mut pd = cd.page_decompressor(page.as_slice(), nums.len())?;
+  assert!(pd.decompress(&mut [0_u32; 50]).is_err());
+  Ok(())
+}
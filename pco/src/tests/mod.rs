@@ -1,4 +1,5 @@
 mod compatibility;
+mod determinism;
 mod low_level;
 mod recovery;
 mod stability;
@@ -3,12 +3,12 @@ use rand::Rng;
 use rand_xoshiro::rand_core::SeedableRng;
 
 use crate::chunk_config::{ChunkConfig, DeltaSpec};
-use crate::constants::Bitlen;
+use crate::constants::{Bitlen, MAX_DELTA_ENCODING_ORDER};
 use crate::data_types::Number;
 use crate::errors::PcoResult;
 use crate::metadata::{ChunkMeta, DeltaEncoding, DynLatent, Mode};
-use crate::standalone::{simple_compress, simple_decompress, FileCompressor};
-use crate::ModeSpec;
+use crate::standalone::{simple_compress, simple_decompress, FileCompressor, FileDecompressor};
+use crate::{ModeSpec, PagingSpec};
 
 fn compress_w_meta<T: Number>(nums: &[T], config: &ChunkConfig) -> PcoResult<(Vec<u8>, ChunkMeta)> {
   let mut compressed = Vec::new();
@@ -118,6 +118,46 @@ fn test_sparse() -> PcoResult<()> {
   assert_recovers(&v, 1, "sparse")
 }
 
+#[test]
+fn test_u8_codec() -> PcoResult<()> {
+  assert_recovers(&[0_u8, u8::MAX, 2, 3, 4, 5], 1, "u8s")
+}
+
+#[test]
+fn test_u8_low_cardinality_near_entropy() -> PcoResult<()> {
+  // categorical codes should cost little more than their entropy, since
+  // each gets its own bin with no offset bits
+  let codes = [3_u8, 17, 42, 200, 255];
+  let probs = [0.5, 0.2, 0.15, 0.1, 0.05];
+  let mut rng = rand_xoshiro::Xoroshiro128PlusPlus::seed_from_u64(0);
+  let n = 1_000_000;
+  let nums = (0..n)
+    .map(|_| {
+      let mut u = rng.gen::<f64>();
+      let mut i = 0;
+      while i < codes.len() - 1 && u >= probs[i] {
+        u -= probs[i];
+        i += 1;
+      }
+      codes[i]
+    })
+    .collect::<Vec<_>>();
+
+  let (compressed, meta) = compress_w_meta(&nums, &ChunkConfig::default())?;
+  assert_eq!(meta.mode, Mode::Classic);
+  assert_eq!(meta.delta_encoding, DeltaEncoding::None);
+  let entropy_bits = probs.iter().map(|p: &f64| -p * p.log2()).sum::<f64>();
+  let entropy_bytes = n as f64 * entropy_bits / 8.0;
+  assert!(
+    (compressed.len() as f64) < 1.01 * entropy_bytes,
+    "{} vs {}",
+    compressed.len(),
+    entropy_bytes,
+  );
+  assert_eq!(simple_decompress::<u8>(&compressed)?, nums);
+  Ok(())
+}
+
 #[test]
 fn test_u16_codec() -> PcoResult<()> {
   assert_recovers(&[0_u16, u16::MAX, 2, 3, 4, 5], 1, "u16s")
@@ -289,6 +329,41 @@ fn test_with_int_mult() -> PcoResult<()> {
   Ok(())
 }
 
+#[test]
+fn test_secondary_uses_delta() -> PcoResult<()> {
+  let mut rng = rand_xoshiro::Xoroshiro128PlusPlus::seed_from_u64(0);
+  let mut nums = Vec::new();
+  let mut adj = 0;
+  for i in 0..300 {
+    adj = (adj + rng.gen_range(0..2)) % 8;
+    nums.push(i * 8 + adj);
+  }
+  for delta_spec in [DeltaSpec::TryConsecutive(1), DeltaSpec::TryLookback] {
+    let config = ChunkConfig::default()
+      .with_mode_spec(ModeSpec::TryIntMult(8))
+      .with_delta_spec(delta_spec)
+      .with_secondary_uses_delta(true);
+    let (compressed, meta) = compress_w_meta(&nums, &config)?;
+    assert_eq!(
+      meta.mode,
+      Mode::IntMult(DynLatent::U32(8_u32))
+    );
+    match meta.delta_encoding {
+      DeltaEncoding::Consecutive(config) => assert!(config.secondary_uses_delta),
+      DeltaEncoding::Lookback(config) => assert!(config.secondary_uses_delta),
+      other => panic!("unexpected delta encoding {:?}", other),
+    }
+    let decompressed = simple_decompress(&compressed)?;
+    assert_nums_eq(&decompressed, &nums, "secondary uses delta")?;
+  }
+
+  let config = ChunkConfig::default()
+    .with_secondary_uses_delta(true)
+    .with_format_version(Some(2));
+  assert!(simple_compress(&nums, &config).is_err());
+  Ok(())
+}
+
 #[test]
 fn test_sparse_islands() -> PcoResult<()> {
   let mut rng = rand_xoshiro::Xoroshiro128PlusPlus::seed_from_u64(0);
@@ -344,6 +419,77 @@ fn test_f16_mult() -> PcoResult<()> {
   assert_recovers(&nums, 1, "f16 mult mode")
 }
 
+#[test]
+fn test_lossy_float_mult() -> PcoResult<()> {
+  let nums = (0..1000)
+    .map(|i| (i % 97) as f64 * 0.1 + (i % 7) as f64 * 1e-9)
+    .collect::<Vec<_>>();
+  let lossy_config = ChunkConfig::default().with_mode_spec(ModeSpec::TryFloatMultLossy(0.1));
+  let (lossy, meta) = compress_w_meta(&nums, &lossy_config)?;
+  assert_eq!(meta.mode, Mode::float_mult_lossy(0.1_f64));
+  assert!(meta.per_latent_var.secondary.is_none());
+  assert!(FileCompressor::default()
+    .chunk_compressor(&nums, &lossy_config)?
+    .is_lossy());
+  // the lossiness is visible to decompressors too
+  let (fd, rest) = FileDecompressor::new(lossy.as_slice())?;
+  assert_eq!(
+    fd.first_chunk_meta(rest)?.unwrap().mode,
+    Mode::float_mult_lossy(0.1_f64)
+  );
+
+  let expected = nums
+    .iter()
+    .map(|&x| (x * 10.0).round() * 0.1)
+    .collect::<Vec<_>>();
+  assert_nums_eq(
+    &simple_decompress::<f64>(&lossy)?,
+    &expected,
+    "lossy float mult",
+  )?;
+
+  let lossless_config = ChunkConfig::default().with_mode_spec(ModeSpec::TryFloatMult(0.1));
+  let (lossless, _) = compress_w_meta(&nums, &lossless_config)?;
+  assert!(lossy.len() < lossless.len());
+
+  // ints don't support float modes
+  assert!(simple_compress(&[1_u32, 2, 3], &lossy_config).is_err());
+  Ok(())
+}
+
+#[test]
+fn test_lossy_float_mult_fallback() -> PcoResult<()> {
+  // large integers spread uniformly over the float bit representation make
+  // the multiples incompressible, so for a short chunk FloatMultLossy's extra
+  // metadata makes it worse than the baseline and Pco falls back to lossless
+  // classic mode
+  let mut rng = rand_xoshiro::Xoroshiro128PlusPlus::seed_from_u64(0);
+  let min_bits = 2.0_f64.powi(53).to_bits();
+  let nums = (0..20)
+    .map(|i| {
+      let abs = f64::from_bits(rng.gen_range(min_bits..f64::INFINITY.to_bits()));
+      if i % 2 == 0 {
+        abs
+      } else {
+        -abs
+      }
+    })
+    .collect::<Vec<_>>();
+  let config = ChunkConfig::default().with_mode_spec(ModeSpec::TryFloatMultLossy(1.0));
+  let cc = FileCompressor::default().chunk_compressor(&nums, &config)?;
+  assert!(cc.used_fallback());
+  assert!(!cc.is_lossy());
+  assert_eq!(cc.meta().mode, Mode::Classic);
+
+  let compressed = simple_compress(&nums, &config)?;
+  assert_nums_eq(
+    &simple_decompress::<f64>(&compressed)?,
+    &nums,
+    "lossy float mult fallback",
+  )?;
+  Ok(())
+}
+
 #[test]
 fn test_trivial_first_latent_var() -> PcoResult<()> {
   let mut nums = Vec::new();
@@ -376,3 +522,29 @@ fn test_lookback_delta_encoding() -> PcoResult<()> {
   assert_nums_eq(&decompressed, &nums, "trivial_first_latent")?;
   Ok(())
 }
+
+#[test]
+fn test_tiny_chunks_at_max_delta_order() -> PcoResult<()> {
+  for n in 1..=10 {
+    let nums = (0..n)
+      .map(|i| (i * i * 1000) as i64 - 77)
+      .collect::<Vec<_>>();
+    // pages shorter than the delta order, including a short last page
+    for max_page_n in [n, 3] {
+      let config = ChunkConfig::default()
+        .with_delta_spec(DeltaSpec::TryConsecutive(
+          MAX_DELTA_ENCODING_ORDER,
+        ))
+        .with_paging_spec(PagingSpec::EqualPagesUpTo(max_page_n));
+      let (compressed, meta) = compress_w_meta(&nums, &config)?;
+      assert!(meta.delta_encoding.n_latents_per_state() < n);
+      let decompressed = simple_decompress::<i64>(&compressed)?;
+      assert_nums_eq(
+        &decompressed,
+        &nums,
+        &format!("n={} max_page_n={}", n, max_page_n),
+      )?;
+    }
+  }
+  Ok(())
+}
@@ -3,14 +3,16 @@ use crate::chunk_config::DeltaSpec;
 use crate::compression_intermediates::{BinCompressionInfo, PageInfoVar};
 use crate::compression_intermediates::{DissectedPage, PageInfo};
 use crate::constants::{
-  Bitlen, Weight, LIMITED_UNOPTIMIZED_BINS_LOG, MAX_COMPRESSION_LEVEL, MAX_DELTA_ENCODING_ORDER,
-  MAX_ENTRIES, OVERSHOOT_PADDING, PAGE_PADDING,
+  Bitlen, Weight, LIMITED_UNOPTIMIZED_BINS_LOG, MAX_BINS, MAX_COMPRESSION_LEVEL,
+  MAX_COMPRESSOR_ANS_BITS, MAX_DELTA_ENCODING_ORDER, MAX_ENTRIES, OVERSHOOT_PADDING, PAGE_PADDING,
 };
 use crate::data_types::SplitLatents;
 use crate::data_types::{Latent, LatentType, Number};
 use crate::delta::DeltaState;
 use crate::errors::{PcoError, PcoResult};
-use crate::histograms::histogram;
+use crate::histograms::{histogram, histogram_from_lowers, HistogramBin};
+#[cfg(feature = "debug_invariants")]
+use crate::invariants;
 use crate::latent_chunk_compressor::{
   DynLatentChunkCompressor, LatentChunkCompressor, TrainedBins,
 };
@@ -19,13 +21,14 @@ use crate::metadata::chunk_latent_var::ChunkLatentVarMeta;
 use crate::metadata::delta_encoding::{DeltaConsecutiveConfig, DeltaLookbackConfig};
 use crate::metadata::dyn_bins::DynBins;
 use crate::metadata::dyn_latents::DynLatents;
+use crate::metadata::format_version::FormatVersion;
 use crate::metadata::page::PageMeta;
 use crate::metadata::page_latent_var::PageLatentVarMeta;
 use crate::metadata::per_latent_var::{LatentVarKey, PerLatentVar, PerLatentVarBuilder};
 use crate::metadata::{Bin, ChunkMeta, DeltaEncoding, Mode};
 use crate::wrapped::guarantee;
 use crate::{
-  ans, bin_optimization, bits, data_types, delta, ChunkConfig, PagingSpec, FULL_BATCH_N,
+  ans, bin_optimization, bits, data_types, delta, ChunkConfig, ModeSpec, PagingSpec, FULL_BATCH_N,
 };
 use std::cmp::min;
 use std::io::Write;
@@ -37,7 +40,6 @@ const N_PER_EXTRA_DELTA_GROUP: usize = 10000;
 const DELTA_GROUP_SIZE: usize = 200;
 const LOOKBACK_MAX_WINDOW_N_LOG: Bitlen = 15;
 const LOOKBACK_MIN_WINDOW_N_LOG: Bitlen = 4;
-const LOOKBACK_REQUIRED_BYTE_SAVINGS_PER_N: f32 = 0.25;
 
 // TODO taking deltas of secondary latents has been proven to help slightly
 // in some cases, so we should consider it in the future
@@ -68,16 +70,81 @@ fn quantize_weights<L: Latent>(
   ans_size_log
 }
 
+// Options for training the bins of each latent variable.
+#[derive(Clone, Copy, Debug, Default)]
+struct BinTrainingConfig<'a> {
+  // only applied to the primary latent variable
+  min_bins: usize,
+  reoptimize: bool,
+  // only applied to the primary latent variable
+  bin_boundaries: Option<&'a DynLatents>,
+}
+
+fn optimize_and_quantize<L: Latent>(
+  unoptimized_bins: &[HistogramBin<L>],
+  n_latents: usize,
+  estimated_ans_size_log: Bitlen,
+  min_bins: usize,
+) -> TrainedBins<L> {
+  // every bin needs a nonzero weight in the ANS table
+  let min_bins = min(min_bins, 1 << estimated_ans_size_log);
+  let mut optimized_infos = bin_optimization::optimize_bins(
+    unoptimized_bins,
+    estimated_ans_size_log,
+    min_bins,
+  );
+
+  let counts = optimized_infos
+    .iter()
+    .map(|info| info.weight)
+    .collect::<Vec<_>>();
+  let ans_size_log = quantize_weights(
+    &mut optimized_infos,
+    n_latents,
+    estimated_ans_size_log,
+  );
+
+  TrainedBins {
+    infos: optimized_infos,
+    ans_size_log,
+    counts,
+  }
+}
+
+// The size in bits these bins should take to encode their latents, including
+// their metadata, now that their ANS weights are quantized.
+fn trained_bit_size<L: Latent>(trained: &TrainedBins<L>) -> f64 {
+  let bin_meta_bits = Bin::<L>::exact_bit_size(trained.ans_size_log) as f64;
+  trained
+    .infos
+    .iter()
+    .zip(&trained.counts)
+    .map(|(info, &count)| {
+      let ans_bits = trained.ans_size_log as f64 - (info.weight as f64).log2();
+      bin_meta_bits + count as f64 * (ans_bits + info.offset_bits as f64)
+    })
+    .sum()
+}
+
 fn train_infos<L: Latent>(
   mut latents: Vec<L>,
   unoptimized_bins_log: Bitlen,
+  training_config: BinTrainingConfig,
 ) -> PcoResult<TrainedBins<L>> {
   if latents.is_empty() {
     return Ok(TrainedBins::default());
   }
 
   let n_latents = latents.len();
-  let unoptimized_bins = histogram(&mut latents, unoptimized_bins_log as Bitlen);
+  let unoptimized_bins = match training_config.bin_boundaries {
+    Some(boundaries) => {
+      let lowers = boundaries.downcast_ref::<L>().ok_or_else(|| {
+        PcoError::invalid_argument("bin boundaries must have the same latent type as the numbers")
+      })?;
+      histogram_from_lowers(&latents, lowers)?
+    }
+    None => histogram(&mut latents, unoptimized_bins_log as Bitlen),
+  };
 
   let n_log_ceil = if n_latents <= 1 {
     0
@@ -92,44 +159,64 @@ fn train_infos<L: Latent>(
   let estimated_ans_size_log = min(
     min(
       (unoptimized_bins_log + 2) as Bitlen,
-      MAX_COMPRESSION_LEVEL as Bitlen,
+      MAX_COMPRESSOR_ANS_BITS,
     ),
     n_log_ceil,
   );
 
-  let mut optimized_infos =
-    bin_optimization::optimize_bins(&unoptimized_bins, estimated_ans_size_log);
-
-  let counts = optimized_infos
-    .iter()
-    .map(|info| info.weight)
-    .collect::<Vec<_>>();
-  let ans_size_log = quantize_weights(
-    &mut optimized_infos,
+  let trained = optimize_and_quantize(
+    &unoptimized_bins,
     n_latents,
     estimated_ans_size_log,
+    training_config.min_bins,
   );
+  // The first pass optimizes bins for the estimated ANS size log, but
+  // quantization may settle on a different one. If so, optimizing again for
+  // the actual size log can find cheaper bins.
+  if !training_config.reoptimize || trained.ans_size_log == estimated_ans_size_log {
+    return Ok(trained);
+  }
 
-  Ok(TrainedBins {
-    infos: optimized_infos,
-    ans_size_log,
-    counts,
-  })
+  let retrained = optimize_and_quantize(
+    &unoptimized_bins,
+    n_latents,
+    trained.ans_size_log,
+    training_config.min_bins,
+  );
+  if trained_bit_size(&retrained) < trained_bit_size(&trained) {
+    Ok(retrained)
+  } else {
+    Ok(trained)
+  }
+}
+
+/// The exact byte sizes of each component of a chunk, as returned by
+/// [`ChunkCompressor::component_sizes`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ComponentSizes {
+  /// The size of the chunk metadata.
+  pub meta: usize,
+  /// The total size of all the page metadata.
+  pub page_meta: usize,
+  /// The total size of all the page bodies.
+  pub body: usize,
 }
 
 /// Holds metadata about a chunk and supports compression.
 #[derive(Clone, Debug)]
 pub struct ChunkCompressor {
   meta: ChunkMeta,
+  format_version: FormatVersion,
   latent_chunk_compressors: PerLatentVar<DynLatentChunkCompressor>,
   page_infos: Vec<PageInfo>,
+  used_fallback: bool,
 }
 
 fn bins_from_compression_infos<L: Latent>(infos: &[BinCompressionInfo<L>]) -> Vec<Bin<L>> {
   infos.iter().cloned().map(Bin::from).collect()
 }
 
-fn validate_config(config: &ChunkConfig) -> PcoResult<()> {
+fn validate_config(config: &ChunkConfig, format_version: &FormatVersion) -> PcoResult<()> {
   let compression_level = config.compression_level;
   if compression_level > MAX_COMPRESSION_LEVEL {
     return Err(PcoError::invalid_argument(format!(
@@ -138,6 +225,31 @@ fn validate_config(config: &ChunkConfig) -> PcoResult<()> {
     )));
   }
 
+  if let Some(min_bins) = config.min_bins {
+    if min_bins > MAX_BINS {
+      return Err(PcoError::invalid_argument(format!(
+        "min bins may not exceed {} (was {})",
+        MAX_BINS, min_bins,
+      )));
+    }
+  }
+
+  let lookback_savings = config.lookback_required_byte_savings_per_n;
+  if lookback_savings.is_nan() {
+    return Err(PcoError::invalid_argument(
+      "lookback required byte savings per number may not be NaN",
+    ));
+  }
+  if config.float_quant_required_bits_saved_per_num.is_nan() {
+    return Err(PcoError::invalid_argument(
+      "float quant required bits saved per number may not be NaN",
+    ));
+  }
+
+  if let Some(boundaries) = &config.bin_boundaries {
+    validate_bin_boundaries(boundaries, config)?;
+  }
+
   if let DeltaSpec::TryConsecutive(order) = config.delta_spec {
     if order > MAX_DELTA_ENCODING_ORDER {
       return Err(PcoError::invalid_argument(format!(
@@ -147,6 +259,62 @@ fn validate_config(config: &ChunkConfig) -> PcoResult<()> {
     }
   }
 
+  let unsupported_feature = match (config.mode_spec, config.delta_spec) {
+    (ModeSpec::TryIntMult(_), _) if format_version.used_old_gcds() => Some("int mult mode"),
+    (ModeSpec::TryFloatQuant(_), _) if !format_version.supports_float_quant() => {
+      Some("float quant mode")
+    }
+    (ModeSpec::TryFloatMultLossy(_), _) if !format_version.supports_lossy_float_mult() => {
+      Some("lossy float mult mode")
+    }
+    (_, DeltaSpec::TryLookback) if !format_version.supports_delta_variants() => {
+      Some("lookback delta encoding")
+    }
+    _ if config.secondary_uses_delta && !format_version.supports_delta_variants() => {
+      Some("delta encoding secondary latents")
+    }
+    _ => None,
+  };
+  if let Some(feature) = unsupported_feature {
+    return Err(PcoError::invalid_argument(format!(
+      "{} is not supported by format version {}",
+      feature, format_version.0,
+    )));
+  }
+
+  Ok(())
+}
+
+fn validate_bin_boundaries(boundaries: &DynLatents, config: &ChunkConfig) -> PcoResult<()> {
+  if config.mode_spec != ModeSpec::Classic
+    || !matches!(
+      config.delta_spec,
+      DeltaSpec::None | DeltaSpec::TryConsecutive(0)
+    )
+  {
+    return Err(PcoError::invalid_argument(
+      "bin boundaries require classic mode and no delta encoding",
+    ));
+  }
+
+  let n_bins = boundaries.len();
+  if n_bins == 0 || n_bins > MAX_BINS {
+    return Err(PcoError::invalid_argument(format!(
+      "bin boundaries must number from 1 to {} (was {})",
+      MAX_BINS, n_bins,
+    )));
+  }
+
+  let is_increasing = match_latent_enum!(
+    boundaries,
+    DynLatents<L>(lowers) => { lowers.windows(2).all(|pair| pair[0] < pair[1]) }
+  );
+  if !is_increasing {
+    return Err(PcoError::invalid_argument(
+      "bin boundaries must be strictly increasing",
+    ));
+  }
+
   Ok(())
 }
 
@@ -218,7 +386,9 @@ fn delta_encode_and_build_page_infos(
         start_idx..end_idx,
         var_latents,
       );
-      // delta encoding in place leaves junk in the first n_latents_per_state
+      // delta encoding in place leaves junk in the first n_latents_per_state.
+      // Pages may be shorter than that (e.g. a short last page), in which
+      // case all their latents live in the delta state and none are stored.
       let stored_start_idx = min(
         start_idx + encoding_for_var.n_latents_per_state(),
         end_idx,
@@ -258,9 +428,13 @@ fn new_candidate_w_split_and_delta_encoding(
   mode: Mode,
   delta_encoding: DeltaEncoding,
   unoptimized_bins_log: Bitlen,
+  training_config: BinTrainingConfig,
+  format_version: FormatVersion,
 ) -> PcoResult<(ChunkCompressor, PerLatentVar<Vec<Weight>>)> {
   let chunk_n = latents.primary.len();
   let n_per_page = paging_spec.n_per_page(chunk_n)?;
+  #[cfg(feature = "debug_invariants")]
+  invariants::check_page_ns(&n_per_page, chunk_n);
 
   // delta encoding
   let (latents, page_infos) =
@@ -287,9 +461,24 @@ fn new_candidate_w_split_and_delta_encoding(
       latents,
       DynLatents<L>(latents) => {
         let contiguous_deltas = collect_contiguous_latents(&latents, &page_infos, key);
-        let trained = train_infos(contiguous_deltas, unoptimized_bins_log)?;
+        let training_config = match key {
+          LatentVarKey::Primary => training_config,
+          LatentVarKey::Delta | LatentVarKey::Secondary => BinTrainingConfig {
+            min_bins: 0,
+            bin_boundaries: None,
+            ..training_config
+          },
+        };
+        let trained = train_infos(contiguous_deltas, unoptimized_bins_log, training_config)?;
 
         let bins = bins_from_compression_infos(&trained.infos);
+        #[cfg(feature = "debug_invariants")]
+        invariants::check_trained_bins(
+          &bins,
+          trained.ans_size_log,
+          &trained.counts,
+          &collect_contiguous_latents(&latents, &page_infos, key),
+        );
 
         let ans_size_log = trained.ans_size_log;
         let bin_counts = trained.counts.to_vec();
@@ -318,8 +507,10 @@ fn new_candidate_w_split_and_delta_encoding(
   };
   let chunk_compressor = ChunkCompressor {
     meta,
+    format_version,
     latent_chunk_compressors,
     page_infos,
+    used_fallback: false,
   };
 
   Ok((chunk_compressor, bin_countss))
@@ -359,6 +550,7 @@ fn calculate_compressed_sample_size(
   sample: &DynLatents,
   unoptimized_bins_log: Bitlen,
   delta_encoding: DeltaEncoding,
+  format_version: FormatVersion,
 ) -> PcoResult<f32> {
   let sample_n = sample.len();
   let (sample_cc, _) = new_candidate_w_split_and_delta_encoding(
@@ -370,6 +562,8 @@ fn calculate_compressed_sample_size(
     Mode::Classic,
     delta_encoding,
     unoptimized_bins_log,
+    BinTrainingConfig::default(),
+    format_version,
   )?;
   let size = sample_cc.chunk_meta_size_hint() + sample_cc.page_size_hint_inner(0, 1.0);
   Ok(size as f32)
@@ -379,6 +573,8 @@ fn calculate_compressed_sample_size(
 fn choose_delta_encoding(
   primary_latents: &DynLatents,
   unoptimized_bins_log: Bitlen,
+  lookback_required_byte_savings_per_n: f32,
+  format_version: FormatVersion,
 ) -> PcoResult<DeltaEncoding> {
   let n = primary_latents.len();
   let sample = choose_delta_sample(
@@ -393,15 +589,17 @@ fn choose_delta_encoding(
     &sample,
     unoptimized_bins_log,
     DeltaEncoding::None,
+    format_version,
   )?;
 
-  let lookback_penalty = LOOKBACK_REQUIRED_BYTE_SAVINGS_PER_N * sample_n as f32;
-  if best_cost > lookback_penalty {
+  let lookback_penalty = lookback_required_byte_savings_per_n * sample_n as f32;
+  if format_version.supports_delta_variants() && best_cost > lookback_penalty {
     let lookback_encoding = new_lookback_delta_encoding(sample_n);
     let lookback_cost = calculate_compressed_sample_size(
       &sample,
       unoptimized_bins_log,
       lookback_encoding,
+      format_version,
     )? + lookback_penalty;
     if lookback_cost < best_cost {
       best_encoding = new_lookback_delta_encoding(primary_latents.len());
@@ -414,7 +612,12 @@ fn choose_delta_encoding(
       order: delta_encoding_order,
       secondary_uses_delta: false,
     });
-    let cost = calculate_compressed_sample_size(&sample, unoptimized_bins_log, encoding)?;
+    let cost = calculate_compressed_sample_size(
+      &sample,
+      unoptimized_bins_log,
+      encoding,
+      format_version,
+    )?;
     if cost < best_cost {
       best_encoding = encoding;
       best_cost = cost;
@@ -446,18 +649,34 @@ fn new_candidate_w_split(
   mode: Mode,
   latents: SplitLatents,
   config: &ChunkConfig,
+  file_format_version: FormatVersion,
 ) -> PcoResult<(ChunkCompressor, PerLatentVar<Vec<Weight>>)> {
   let n = latents.primary.len();
   let unoptimized_bins_log = choose_unoptimized_bins_log(config.compression_level, n);
   let delta_encoding = match config.delta_spec {
-    DeltaSpec::Auto => choose_delta_encoding(&latents.primary, unoptimized_bins_log)?,
+    DeltaSpec::Auto => choose_delta_encoding(
+      &latents.primary,
+      unoptimized_bins_log,
+      config.lookback_required_byte_savings_per_n,
+      config.format_version()?,
+    )?,
     DeltaSpec::None | DeltaSpec::TryConsecutive(0) => DeltaEncoding::None,
+    // Orders of n or higher would only store zero moments in each page, so we
+    // cap the order at n - 1 for tiny chunks.
+    DeltaSpec::TryConsecutive(order) if order >= n => match n {
+      0 | 1 => DeltaEncoding::None,
+      _ => DeltaEncoding::Consecutive(DeltaConsecutiveConfig {
+        order: n - 1,
+        secondary_uses_delta: false,
+      }),
+    },
     DeltaSpec::TryConsecutive(order) => DeltaEncoding::Consecutive(DeltaConsecutiveConfig {
       order,
       secondary_uses_delta: false,
     }),
     DeltaSpec::TryLookback => new_lookback_delta_encoding(n),
   };
+  let delta_encoding = delta_encoding.with_secondary_uses_delta(config.secondary_uses_delta);
 
   new_candidate_w_split_and_delta_encoding(
     latents,
@@ -465,12 +684,19 @@ fn new_candidate_w_split(
     mode,
     delta_encoding,
     unoptimized_bins_log,
+    BinTrainingConfig {
+      min_bins: config.min_bins.unwrap_or(0),
+      reoptimize: config.reoptimize_bins,
+      bin_boundaries: config.bin_boundaries.as_ref(),
+    },
+    file_format_version,
   )
 }
 
 fn fallback_chunk_compressor(
   latents: SplitLatents,
   config: &ChunkConfig,
+  format_version: FormatVersion,
 ) -> PcoResult<ChunkCompressor> {
   let n = latents.primary.len();
   let n_per_page = config.paging_spec.n_per_page(n)?;
@@ -503,31 +729,59 @@ fn fallback_chunk_compressor(
 
   Ok(ChunkCompressor {
     meta,
+    format_version,
     latent_chunk_compressors: PerLatentVar {
       delta: None,
       primary: lcc,
       secondary: None,
     },
     page_infos,
+    used_fallback: true,
   })
 }
 
 // Should this take nums as a slice of slices instead of having a config.paging_spec?
-pub(crate) fn new<T: Number>(nums: &[T], config: &ChunkConfig) -> PcoResult<ChunkCompressor> {
-  validate_config(config)?;
+// The config's format version limits which features the chunk may use, and
+// must not exceed the file's format version, which determines how the chunk
+// metadata gets written.
+pub(crate) fn new<T: Number>(
+  nums: &[T],
+  config: &ChunkConfig,
+  file_format_version: FormatVersion,
+) -> PcoResult<ChunkCompressor> {
+  let format_version = config.format_version()?;
+  if format_version > file_format_version {
+    return Err(PcoError::invalid_argument(format!(
+      "chunk format version ({}) exceeds file format version ({})",
+      format_version.0, file_format_version.0,
+    )));
+  }
+  validate_config(config, &format_version)?;
+  let supports_latent_bits = match T::L::BITS {
+    8 => format_version.supports_8_bit_types(),
+    16 => format_version.supports_16_bit_types(),
+    _ => true,
+  };
+  if !supports_latent_bits {
+    return Err(PcoError::invalid_argument(format!(
+      "{}-bit data types are not supported by format version {}",
+      T::L::BITS,
+      format_version.0,
+    )));
+  }
   let n = nums.len();
   validate_chunk_size(n)?;
 
   let (mode, latents) = T::choose_mode_and_split_latents(nums, config)?;
 
-  let (candidate, bin_counts) = new_candidate_w_split(mode, latents, config)?;
+  let (candidate, bin_counts) = new_candidate_w_split(mode, latents, config, file_format_version)?;
   if candidate.should_fallback(
     LatentType::new::<T::L>().unwrap(),
     n,
     bin_counts,
   ) {
     let split_latents = data_types::split_latents_classic(nums);
-    return fallback_chunk_compressor(split_latents, config);
+    return fallback_chunk_compressor(split_latents, config, file_format_version);
   }
 
   Ok(candidate)
@@ -564,7 +818,7 @@ impl ChunkCompressor {
       });
     }
 
-    let worst_case_size = meta.exact_size()
+    let worst_case_size = meta.exact_size(&self.format_version)
       + n_pages * meta.exact_page_meta_size()
       + worst_case_body_bit_size.div_ceil(8);
 
@@ -585,12 +839,69 @@ impl ChunkCompressor {
     &self.meta
   }
 
-  /// Returns an estimate of the overall size of the chunk.
+  /// Returns whether the chosen compression was expected to exceed the
+  /// worst-case size of the trivial baseline, causing the chunk to fall back
+  /// to classic mode with no delta encoding and a single bin.
+  pub fn used_fallback(&self) -> bool {
+    self.used_fallback
+  }
+
+  /// Returns whether the chunk will reconstruct the numbers only
+  /// approximately.
+  ///
+  /// This is true when the config's mode spec was
+  /// [`TryFloatMultLossy`][crate::ModeSpec::TryFloatMultLossy] and the chunk
+  /// didn't fall back to classic mode.
+  /// The chunk meta records this as [`Mode::FloatMultLossy`], so
+  /// decompressors can tell too.
+  pub fn is_lossy(&self) -> bool {
+    matches!(self.meta.mode, Mode::FloatMultLossy(_))
+  }
+
+  /// Returns the Shannon entropy, in bits per latent, of the primary latent
+  /// variable's bin distribution, as given by the bin weights.
+  ///
+  /// This is the theoretical minimum average cost of encoding which bin each
+  /// latent falls in, excluding offset bits.
+  /// Comparing it to the actual body size can show how close tANS got to
+  /// optimal.
+  pub fn primary_entropy_bits(&self) -> f64 {
+    let primary = &self.meta.per_latent_var.primary;
+    let weights = match_latent_enum!(
+      &primary.bins,
+      DynBins<L>(bins) => {
+        bins.iter().map(|bin| bin.weight).collect::<Vec<_>>()
+      }
+    );
+    let total_weight = (1 << primary.ans_size_log) as f64;
+    weights
+      .iter()
+      .map(|&weight| {
+        let p = weight as f64 / total_weight;
+        -p * p.log2()
+      })
+      .sum()
+  }
+
+  /// Returns the size of the chunk metadata.
   ///
   /// This can be useful when building the file as a `Vec<u8>` in memory;
   /// you can `.reserve()` ahead of time.
   pub fn chunk_meta_size_hint(&self) -> usize {
-    self.meta.exact_size()
+    self.meta.exact_size(&self.format_version)
+  }
+
+  /// Returns an estimate of the overall size of the chunk, including its
+  /// metadata and all its pages.
+  ///
+  /// This can be useful when building the chunk as a `Vec<u8>` in memory;
+  /// you can `.reserve(chunk_compressor.chunk_size_hint())` once ahead of
+  /// time instead of reallocating as each page gets written.
+  pub fn chunk_size_hint(&self) -> usize {
+    self.chunk_meta_size_hint()
+      + (0..self.page_infos.len())
+        .map(|page_idx| self.page_size_hint(page_idx))
+        .sum::<usize>()
   }
 
   /// Writes the chunk metadata to the destination.
@@ -599,9 +910,9 @@ impl ChunkCompressor {
   pub fn write_chunk_meta<W: Write>(&self, dst: W) -> PcoResult<W> {
     let mut writer = BitWriter::new(
       dst,
-      self.meta.exact_size() + OVERSHOOT_PADDING,
+      self.meta.exact_size(&self.format_version) + OVERSHOOT_PADDING,
     );
-    unsafe { self.meta.write_to(&mut writer)? };
+    unsafe { self.meta.write_to(&self.format_version, &mut writer)? };
     Ok(writer.into_inner())
   }
 
@@ -633,7 +944,8 @@ impl ChunkCompressor {
   /// Returns an estimate of the overall size of a specific page.
   ///
   /// This can be useful when building the file as a `Vec<u8>` in memory;
-  /// you can `.reserve(chunk_compressor.chunk_size_hint())` ahead of time.
+  /// you can `.reserve(chunk_compressor.page_size_hint(page_idx))` ahead of
+  /// time.
   pub fn page_size_hint(&self, page_idx: usize) -> usize {
     self.page_size_hint_inner(page_idx, PAGE_SIZE_OVERESTIMATION)
   }
@@ -658,6 +970,55 @@ impl ChunkCompressor {
     self.meta.exact_page_meta_size() + body_bit_size.div_ceil(8)
   }
 
+  /// Returns the exact number of bytes that the chunk metadata, all page
+  /// metadata, and all page bodies will take up when written.
+  ///
+  /// This can be useful for seeing whether metadata overhead is significant,
+  /// e.g. when choosing chunk and page sizes.
+  /// It does most of the work of writing each page, so it is not much
+  /// cheaper than actually writing the chunk.
+  pub fn component_sizes(&self) -> PcoResult<ComponentSizes> {
+    let n_pages = self.page_infos.len();
+    let mut body = 0;
+    for page_idx in 0..n_pages {
+      let dissected_page = self.dissect_page(page_idx)?;
+      let mut body_bit_size = 0;
+      for (_, (dissected_page_var, lcc)) in dissected_page
+        .per_latent_var
+        .as_ref()
+        .zip_exact(self.latent_chunk_compressors.as_ref())
+        .enumerated()
+      {
+        body_bit_size += match_latent_enum!(
+          lcc,
+          DynLatentChunkCompressor<L>(inner) => {
+            inner.dissected_page_bit_size(dissected_page_var)
+          }
+        );
+      }
+      body += body_bit_size.div_ceil(8);
+    }
+
+    Ok(ComponentSizes {
+      meta: self.meta.exact_size(&self.format_version),
+      page_meta: n_pages * self.meta.exact_page_meta_size(),
+      body,
+    })
+  }
+
+  /// Returns the exact number of bytes that the chunk metadata and all pages
+  /// will take up when written.
+  ///
+  /// Unlike [`chunk_size_hint`][Self::chunk_size_hint], this is not an
+  /// estimate, so it can be used to allocate or transmit the output
+  /// precisely before writing it.
+  /// Like [`component_sizes`][Self::component_sizes], it dissects every page,
+  /// so it is not much cheaper than writing the chunk.
+  pub fn exact_compressed_size(&self) -> PcoResult<usize> {
+    let sizes = self.component_sizes()?;
+    Ok(sizes.meta + sizes.page_meta + sizes.body)
+  }
+
   #[inline(never)]
   fn write_dissected_page<W: Write>(
     &self,
@@ -692,6 +1053,21 @@ impl ChunkCompressor {
   ///
   /// Will return an error if the provided `Write` errors.
   pub fn write_page<W: Write>(&self, page_idx: usize, dst: W) -> PcoResult<W> {
+    let (dst, _) = self.write_page_with_n_bytes(page_idx, dst)?;
+    Ok(dst)
+  }
+
+  /// Writes a page to the destination, also returning the exact number of
+  /// bytes written.
+  ///
+  /// This is useful for building an index of page offsets without tracking
+  /// the destination's position.
+  /// Will return an error if the provided `Write` errors.
+  pub fn write_page_with_n_bytes<W: Write>(
+    &self,
+    page_idx: usize,
+    dst: W,
+  ) -> PcoResult<(W, usize)> {
     let n_pages = self.page_infos.len();
     if page_idx >= n_pages {
       return Err(PcoError::invalid_argument(format!(
@@ -736,13 +1112,19 @@ impl ChunkCompressor {
 
     writer.finish_byte();
     writer.flush()?;
-    Ok(writer.into_inner())
+    let n_bytes = writer.n_bytes_flushed();
+    Ok((writer.into_inner(), n_bytes))
   }
 }
 
 #[cfg(test)]
 mod tests {
+  use rand::Rng;
+  use rand_xoshiro::rand_core::SeedableRng;
+  use rand_xoshiro::Xoroshiro128PlusPlus;
+
   use super::*;
+  use crate::sampling;
 
   #[test]
   fn test_choose_delta_sample() {
@@ -776,4 +1158,327 @@ mod tests {
       vec![0, 1, 3, 4, 6, 7]
     );
   }
+
+  #[test]
+  fn test_reoptimize_bins() -> PcoResult<()> {
+    // quantization settles on an ANS size log of 4 for these skewed latents,
+    // below the estimated 6, so a second pass optimizes for the actual one
+    let mut rng = Xoroshiro128PlusPlus::seed_from_u64(40);
+    let latents = (0..1000)
+      .map(|_| (rng.gen::<f64>().powi(3) * 100000.0) as u32)
+      .collect::<Vec<_>>();
+    let trained = train_infos(
+      latents.clone(),
+      4,
+      BinTrainingConfig::default(),
+    )?;
+    let retrained = train_infos(
+      latents.clone(),
+      4,
+      BinTrainingConfig {
+        reoptimize: true,
+        ..Default::default()
+      },
+    )?;
+    assert_eq!(trained.ans_size_log, 4);
+    assert_ne!(retrained.infos, trained.infos);
+    assert!(trained_bit_size(&retrained) < trained_bit_size(&trained));
+
+    let config = ChunkConfig::default()
+      .with_compression_level(4)
+      .with_mode_spec(ModeSpec::Classic)
+      .with_delta_spec(DeltaSpec::None);
+    let compressed = crate::standalone::simple_compress(&latents, &config)?;
+    let reoptimized =
+      crate::standalone::simple_compress(&latents, &config.with_reoptimize_bins(true))?;
+    assert!(reoptimized.len() <= compressed.len());
+    assert_eq!(
+      crate::standalone::simple_decompress::<u32>(&reoptimized)?,
+      latents
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_min_bins() -> PcoResult<()> {
+    let nums = (0..1000_u32).collect::<Vec<_>>();
+    let n_bins = |config: &ChunkConfig| -> PcoResult<usize> {
+      let cc = new(&nums, config, FormatVersion::default())?;
+      Ok(
+        cc.meta
+          .per_latent_var
+          .primary
+          .bins
+          .downcast_ref::<u32>()
+          .unwrap()
+          .len(),
+      )
+    };
+    let config = ChunkConfig::default()
+      .with_mode_spec(ModeSpec::Classic)
+      .with_delta_spec(DeltaSpec::None);
+    assert_eq!(n_bins(&config)?, 1);
+    assert_eq!(
+      n_bins(&config.clone().with_min_bins(Some(5)))?,
+      5
+    );
+    assert!(n_bins(&config.with_min_bins(Some(5000))).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_bin_boundaries() -> PcoResult<()> {
+    let nums = (0..1000_i32)
+      .map(|i| (i * 7) % 1000 - 500)
+      .collect::<Vec<_>>();
+    let boundaries = [-500, -100, 0, 100, 400];
+    // min bins keeps the optimizer from merging the seeded bins
+    let config = ChunkConfig::classic()
+      .with_bin_boundaries(Some(&boundaries))
+      .with_min_bins(Some(boundaries.len()));
+    let bins = new(&nums, &config, FormatVersion::default())?
+      .meta
+      .per_latent_var
+      .primary
+      .bins;
+    let lowers = bins
+      .downcast_ref::<u32>()
+      .unwrap()
+      .iter()
+      .map(|bin| i32::from_latent_ordered(bin.lower))
+      .collect::<Vec<_>>();
+    assert_eq!(lowers, boundaries);
+    let compressed = crate::standalone::simple_compress(&nums, &config)?;
+    assert_eq!(
+      crate::standalone::simple_decompress::<i32>(&compressed)?,
+      nums
+    );
+
+    let is_err = |config: ChunkConfig| new(&nums, &config, FormatVersion::default()).is_err();
+    // don't cover the smallest number
+    assert!(is_err(
+      ChunkConfig::classic().with_bin_boundaries(Some(&[-499, 0]))
+    ));
+    // not sorted
+    assert!(is_err(
+      ChunkConfig::classic().with_bin_boundaries(Some(&[-500, 100, 0]))
+    ));
+    // empty
+    assert!(is_err(
+      ChunkConfig::classic().with_bin_boundaries(Some(&[] as &[i32]))
+    ));
+    // wrong latent type
+    assert!(is_err(
+      ChunkConfig::classic().with_bin_boundaries(Some(&[-500_i64]))
+    ));
+    // not classic
+    assert!(is_err(
+      ChunkConfig::default().with_bin_boundaries(Some(&boundaries))
+    ));
+    Ok(())
+  }
+
+  #[test]
+  fn test_lookback_required_savings() -> PcoResult<()> {
+    // a short pattern of unrelated numbers, repeated
+    let nums = (0..10000_u32)
+      .map(|i| (i % 37).wrapping_mul(2654435761) >> 8)
+      .collect::<Vec<_>>();
+    let delta_encoding = |savings: f32| -> PcoResult<DeltaEncoding> {
+      let config = ChunkConfig::default().with_lookback_required_byte_savings_per_n(savings);
+      Ok(
+        new(&nums, &config, FormatVersion::default())?
+          .meta
+          .delta_encoding,
+      )
+    };
+    let default_savings = ChunkConfig::default().lookback_required_byte_savings_per_n;
+    assert!(!matches!(
+      delta_encoding(default_savings)?,
+      DeltaEncoding::Lookback(_)
+    ));
+    assert!(matches!(
+      delta_encoding(-0.5)?,
+      DeltaEncoding::Lookback(_)
+    ));
+    assert!(!matches!(
+      delta_encoding(f32::INFINITY)?,
+      DeltaEncoding::Lookback(_)
+    ));
+    assert!(delta_encoding(f32::NAN).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_auto_delta_for_timestamps() -> PcoResult<()> {
+    // epoch nanosecond timestamps with regular, jittered, and bursty gaps
+    let mut rng = Xoroshiro128PlusPlus::seed_from_u64(0);
+    let gap_fns: [fn(&mut Xoroshiro128PlusPlus) -> i64; 3] = [
+      |_| 1_000_000_000,
+      |rng| 1_000_000_000 + rng.gen_range(-100_000_000..100_000_000),
+      |rng| {
+        if rng.gen_bool(0.01) {
+          3_600_000_000_000
+        } else {
+          rng.gen_range(0..1_000_000)
+        }
+      },
+    ];
+    for gap_fn in gap_fns {
+      for n in [300, 9000] {
+        let mut t = 1_700_000_000_000_000_000_i64;
+        let nums = (0..n)
+          .map(|_| {
+            t += gap_fn(&mut rng);
+            t
+          })
+          .collect::<Vec<_>>();
+        let meta = new(
+          &nums,
+          &ChunkConfig::default(),
+          FormatVersion::default(),
+        )?
+        .meta;
+        assert!(
+          matches!(
+            meta.delta_encoding,
+            DeltaEncoding::Consecutive(_)
+          ),
+          "n={} chose {:?}",
+          n,
+          meta.delta_encoding,
+        );
+      }
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_classic_config_skips_detection() -> PcoResult<()> {
+    let nums = (0..1000_u64).map(|i| i * 1000).collect::<Vec<_>>();
+    let n_sample_calls = || sampling::N_CHOOSE_SAMPLE_CALLS.with(|n_calls| n_calls.get());
+
+    let n_calls_before = n_sample_calls();
+    let auto_meta = new(
+      &nums,
+      &ChunkConfig::default(),
+      FormatVersion::default(),
+    )?
+    .meta;
+    assert!(matches!(auto_meta.mode, Mode::IntMult(_)));
+    assert!(matches!(
+      auto_meta.delta_encoding,
+      DeltaEncoding::Consecutive(_)
+    ));
+    assert!(n_sample_calls() > n_calls_before);
+
+    let n_calls_before = n_sample_calls();
+    let classic_meta = new(
+      &nums,
+      &ChunkConfig::classic(),
+      FormatVersion::default(),
+    )?
+    .meta;
+    assert_eq!(classic_meta.mode, Mode::Classic);
+    assert_eq!(
+      classic_meta.delta_encoding,
+      DeltaEncoding::None
+    );
+    assert_eq!(n_sample_calls(), n_calls_before);
+    Ok(())
+  }
+
+  #[test]
+  fn test_write_page_with_n_bytes() -> PcoResult<()> {
+    let nums = (0..3000_u64).map(|i| i * i).collect::<Vec<_>>();
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(1000));
+    let cc = new(&nums, &config, FormatVersion::default())?;
+    let mut dst = Vec::new();
+    for page_idx in 0..cc.n_per_page().len() {
+      let start = dst.len();
+      let (new_dst, n_bytes) = cc.write_page_with_n_bytes(page_idx, dst)?;
+      dst = new_dst;
+      assert_eq!(n_bytes, dst.len() - start);
+      assert!(n_bytes > 0);
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_component_sizes() -> PcoResult<()> {
+    let nums = (0..3000)
+      .map(|i| ((i as f32) * 0.1).sin())
+      .collect::<Vec<_>>();
+    for config in [
+      ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(700)),
+      ChunkConfig::default().with_delta_spec(DeltaSpec::TryConsecutive(2)),
+    ] {
+      let cc = new(&nums, &config, FormatVersion::default())?;
+      let sizes = cc.component_sizes()?;
+      assert_eq!(
+        sizes.meta,
+        cc.write_chunk_meta(Vec::new())?.len()
+      );
+      let mut page_bytes = 0;
+      for page_idx in 0..cc.n_per_page().len() {
+        page_bytes += cc.write_page(page_idx, Vec::new())?.len();
+      }
+      assert_eq!(sizes.page_meta + sizes.body, page_bytes);
+      assert!(sizes.body > sizes.page_meta);
+      assert_eq!(
+        cc.exact_compressed_size()?,
+        sizes.meta + page_bytes
+      );
+    }
+
+    // constant data needs no body at all
+    let cc = new(
+      &[7_u32; 100],
+      &ChunkConfig::default(),
+      FormatVersion::default(),
+    )?;
+    assert_eq!(cc.component_sizes()?.body, 0);
+    Ok(())
+  }
+
+  #[test]
+  fn test_chunk_size_hint() -> PcoResult<()> {
+    let nums = (0..3000)
+      .map(|i| ((i as f32) * 0.1).sin())
+      .collect::<Vec<_>>();
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(700));
+    let cc = new(&nums, &config, FormatVersion::default())?;
+    let hint = cc.chunk_size_hint();
+    let mut dst = Vec::with_capacity(hint);
+    dst = cc.write_chunk_meta(dst)?;
+    for page_idx in 0..cc.n_per_page().len() {
+      dst = cc.write_page(page_idx, dst)?;
+    }
+    // the whole chunk should fit without reallocating
+    assert!(dst.len() <= hint);
+    assert_eq!(dst.capacity(), hint);
+    Ok(())
+  }
+
+  #[test]
+  fn test_primary_entropy_bits() -> PcoResult<()> {
+    let config = ChunkConfig::default()
+      .with_delta_spec(DeltaSpec::None)
+      .with_mode_spec(ModeSpec::Classic);
+    // a single bin has no entropy
+    let cc = new(
+      &[7_u32; 100],
+      &config,
+      FormatVersion::default(),
+    )?;
+    assert_eq!(cc.primary_entropy_bits(), 0.0);
+
+    // two equally likely, far apart values should cost 1 bit each
+    let nums = (0..1000_u32)
+      .map(|i| (i % 2) * 1_000_000)
+      .collect::<Vec<_>>();
+    let cc = new(&nums, &config, FormatVersion::default())?;
+    assert_eq!(cc.primary_entropy_bits(), 1.0);
+    Ok(())
+  }
 }
@@ -5,16 +5,23 @@ use crate::data_types::Number;
 use crate::errors::{PcoError, PcoResult};
 use crate::metadata::ChunkMeta;
 use crate::wrapped::PageDecompressor;
+use crate::DecompressorConfig;
 
 /// Holds metadata about a chunk and can produce page decompressors.
 #[derive(Clone, Debug)]
 pub struct ChunkDecompressor<T: Number> {
   pub(crate) meta: ChunkMeta,
+  meta_n_bytes: usize,
+  config: DecompressorConfig,
   phantom: PhantomData<T>,
 }
 
 impl<T: Number> ChunkDecompressor<T> {
-  pub(crate) fn new(meta: ChunkMeta) -> PcoResult<Self> {
+  pub(crate) fn new(
+    meta: ChunkMeta,
+    meta_n_bytes: usize,
+    config: DecompressorConfig,
+  ) -> PcoResult<Self> {
     if !T::mode_is_valid(meta.mode) {
       return Err(PcoError::corruption(format!(
         "invalid mode for data type: {:?}",
@@ -25,6 +32,8 @@ impl<T: Number> ChunkDecompressor<T> {
 
     Ok(Self {
       meta,
+      meta_n_bytes,
+      config,
       phantom: PhantomData,
     })
   }
@@ -34,15 +43,23 @@ impl<T: Number> ChunkDecompressor<T> {
     &self.meta
   }
 
+  /// Returns the number of bytes the chunk metadata occupied in the
+  /// compressed data.
+  pub fn meta_n_bytes(&self) -> usize {
+    self.meta_n_bytes
+  }
+
   /// Reads metadata for a page and returns a `PageDecompressor` and the
   /// remaining input.
   ///
-  /// Will return an error if corruptions or insufficient data are found.
+  /// Will return an error if corruptions or insufficient data are found, or
+  /// if `n` exceeds the decompressor config's `max_n`.
   pub fn page_decompressor<R: BetterBufRead>(
     &self,
     src: R,
     n: usize,
   ) -> PcoResult<PageDecompressor<T, R>> {
+    self.config.check_n(n)?;
     PageDecompressor::<T, R>::new(src, &self.meta, n)
   }
 }
@@ -49,6 +49,19 @@ pub struct FileCompressor {
 }
 
 impl FileCompressor {
+  /// Sets the format version to write in the header (default: the current
+  /// format version).
+  ///
+  /// All chunks compressed by this `FileCompressor` will be constrained to
+  /// the features this version supports; see
+  /// [`ChunkConfig::format_version`][field@ChunkConfig::format_version].
+  ///
+  /// Will return an error if the version exceeds the current format version.
+  pub fn with_format_version(mut self, format_version: u8) -> PcoResult<Self> {
+    self.format_version = FormatVersion::new(format_version)?;
+    Ok(self)
+  }
+
   /// Writes a short header to the destination.
   ///
   /// Will return an error if the provided `Write` errors.
@@ -62,6 +75,14 @@ impl FileCompressor {
   /// Creates a `ChunkCompressor` that can be used to write chunk metadata
   /// and create page compressors.
   ///
+  /// Each chunk can use its own config, e.g. to choose a different mode or
+  /// delta encoding for a different stretch of data.
+  /// Decompressors read these from each chunk's metadata.
+  ///
+  /// The chunk may only use features supported by both the config's format
+  /// version and this file's format version, but its metadata is always
+  /// written in this file's format version.
+  ///
   /// Will return an error if any arguments provided are invalid.
   ///
   /// Although this doesn't write anything yet, it does the bulk of
@@ -71,6 +92,13 @@ impl FileCompressor {
     nums: &[T],
     config: &ChunkConfig,
   ) -> PcoResult<ChunkCompressor> {
-    chunk_compressor::new(nums, config)
+    if config.format_version()? > self.format_version {
+      let config = config
+        .clone()
+        .with_format_version(Some(self.format_version.0));
+      return chunk_compressor::new(nums, &config, self.format_version);
+    }
+
+    chunk_compressor::new(nums, config, self.format_version)
   }
 }
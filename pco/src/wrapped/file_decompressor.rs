@@ -10,12 +10,14 @@ use crate::errors::PcoResult;
 use crate::metadata::chunk::ChunkMeta;
 use crate::metadata::format_version::FormatVersion;
 use crate::wrapped::chunk_decompressor::ChunkDecompressor;
+use crate::DecompressorConfig;
 
 /// Top-level entry point for decompressing wrapped pco files.
 #[derive(Clone, Debug)]
 #[non_exhaustive]
 pub struct FileDecompressor {
   format_version: FormatVersion,
+  config: DecompressorConfig,
 }
 
 impl FileDecompressor {
@@ -24,12 +26,27 @@ impl FileDecompressor {
   ///
   /// Will return an error if any version incompatibilities or
   /// insufficient data are found.
-  pub fn new<R: BetterBufRead>(mut src: R) -> PcoResult<(Self, R)> {
+  pub fn new<R: BetterBufRead>(src: R) -> PcoResult<(Self, R)> {
+    Self::new_with_config(src, &DecompressorConfig::default())
+  }
+
+  /// Reads a short header and returns a `FileDecompressor` that enforces the
+  /// given configuration, as well as the remaining input.
+  ///
+  /// Will return an error if any version incompatibilities or
+  /// insufficient data are found.
+  pub fn new_with_config<R: BetterBufRead>(
+    mut src: R,
+    config: &DecompressorConfig,
+  ) -> PcoResult<(Self, R)> {
     bit_reader::ensure_buf_read_capacity(&mut src, HEADER_PADDING);
     let mut reader_builder = BitReaderBuilder::new(src, HEADER_PADDING, 0);
     let format_version = reader_builder.with_reader(FormatVersion::read_from)?;
     Ok((
-      Self { format_version },
+      Self {
+        format_version,
+        config: config.clone(),
+      },
       reader_builder.into_inner(),
     ))
   }
@@ -55,9 +72,14 @@ impl FileDecompressor {
         &mut reader_builder,
         &self.format_version,
         latent_type,
-      )?
+      )
+      .map_err(|err| err.with_context("in chunk metadata"))?
     };
-    let cd = ChunkDecompressor::new(chunk_meta)?;
+    let cd = ChunkDecompressor::new(
+      chunk_meta,
+      reader_builder.n_bytes_consumed(),
+      self.config.clone(),
+    )?;
     Ok((cd, reader_builder.into_inner()))
   }
 }
@@ -1,5 +1,6 @@
 use crate::data_types::Latent;
 use crate::metadata::chunk_latent_var::ChunkLatentVarMeta;
+use crate::metadata::format_version::FormatVersion;
 use crate::metadata::per_latent_var::PerLatentVar;
 use crate::metadata::{Bin, ChunkMeta, DeltaEncoding, DynBins, Mode};
 
@@ -33,7 +34,8 @@ pub(crate) fn baseline_chunk_meta<L: Latent>() -> ChunkMeta {
 /// Returns the maximum possible byte size of a wrapped chunk for a given
 /// latent type (e.g. u32 or u64) and count of numbers.
 pub fn chunk_size<L: Latent>(n: usize) -> usize {
-  baseline_chunk_meta::<L>().exact_size() + n * L::BITS.div_ceil(8) as usize
+  baseline_chunk_meta::<L>().exact_size(&FormatVersion::default())
+    + n * L::BITS.div_ceil(8) as usize
 }
 
 #[cfg(test)]
@@ -58,7 +60,7 @@ mod tests {
     Ok(())
   }
 
-  fn check_chunk_guarantee<T: Number>(nums: &[T], config: &ChunkConfig) -> PcoResult<()> {
+  fn check_chunk_guarantee<T: Number>(nums: &[T], config: &ChunkConfig) -> PcoResult<bool> {
     let n = nums.len();
     let n_pages = config.paging_spec.n_per_page(n)?.len();
     let mut dst = Vec::new();
@@ -69,7 +71,7 @@ mod tests {
       cc.write_page(i, &mut dst)?;
     }
     assert!(dst.len() <= chunk_size::<T::L>(n));
-    Ok(())
+    Ok(cc.used_fallback())
   }
 
   #[test]
@@ -83,7 +85,8 @@ mod tests {
       paging_spec: PagingSpec::EqualPagesUpTo(10),
       ..Default::default()
     };
-    check_chunk_guarantee(&nums, &config)
+    assert!(!check_chunk_guarantee(&nums, &config)?);
+    Ok(())
   }
 
   #[test]
@@ -99,6 +102,7 @@ mod tests {
       paging_spec: PagingSpec::EqualPagesUpTo(10),
       ..Default::default()
     };
-    check_chunk_guarantee(&nums, &config)
+    assert!(check_chunk_guarantee(&nums, &config)?);
+    Ok(())
   }
 }
@@ -1,14 +1,19 @@
-pub use chunk_compressor::ChunkCompressor;
+#[cfg(feature = "compress")]
+pub use chunk_compressor::{ChunkCompressor, ComponentSizes};
 pub use chunk_decompressor::ChunkDecompressor;
+#[cfg(feature = "compress")]
 pub use file_compressor::FileCompressor;
 pub use file_decompressor::FileDecompressor;
 pub use page_decompressor::PageDecompressor;
 
+#[cfg(feature = "compress")]
 mod chunk_compressor;
 mod chunk_decompressor;
+#[cfg(feature = "compress")]
 mod file_compressor;
 mod file_decompressor;
 /// Functions for guaranteed byte size upper bounds of components
 /// like header and chunk metadata.
+#[cfg(feature = "compress")]
 pub mod guarantee;
 mod page_decompressor;
@@ -47,6 +47,7 @@ struct PageDecompressorInner<R: BetterBufRead> {
 /// Holds metadata about a page and supports decompression.
 pub struct PageDecompressor<T: Number, R: BetterBufRead> {
   inner: PageDecompressorInner<R>,
+  value_range: Option<(T, T)>,
   phantom: PhantomData<T>,
 }
 
@@ -58,12 +59,14 @@ fn convert_from_latents_to_numbers<T: Number>(dst: &mut [T]) {
 }
 
 impl<R: BetterBufRead> PageDecompressorInner<R> {
-  pub(crate) fn new(mut src: R, chunk_meta: &ChunkMeta, n: usize) -> PcoResult<Self> {
+  // also returns the page metadata, which we don't keep
+  pub(crate) fn new(mut src: R, chunk_meta: &ChunkMeta, n: usize) -> PcoResult<(Self, PageMeta)> {
     bit_reader::ensure_buf_read_capacity(&mut src, PERFORMANT_BUF_READ_CAPACITY);
     let mut reader_builder = BitReaderBuilder::new(src, PAGE_PADDING, 0);
 
-    let page_meta =
-      reader_builder.with_reader(|reader| unsafe { PageMeta::read_from(reader, chunk_meta) })?;
+    let page_meta = reader_builder
+      .with_reader(|reader| unsafe { PageMeta::read_from(reader, chunk_meta) })
+      .map_err(|err| err.with_context("in page metadata"))?;
 
     let mode = chunk_meta.mode;
 
@@ -108,7 +111,10 @@ impl<R: BetterBufRead> PageDecompressorInner<R> {
     }
     let latent_decompressors: PerLatentVar<DynLatentPageDecompressor> = states.into();
 
-    fn make_latent_scratch(lpd: Option<&DynLatentPageDecompressor>) -> Option<LatentScratch> {
+    // Batches never exceed the page's count, so tiny pages get tiny scratch
+    // buffers.
+    let scratch_n = min(n, FULL_BATCH_N);
+    let make_latent_scratch = |lpd: Option<&DynLatentPageDecompressor>| -> Option<LatentScratch> {
       let lpd = lpd?;
 
       match_latent_enum!(
@@ -117,16 +123,16 @@ impl<R: BetterBufRead> PageDecompressorInner<R> {
           let maybe_constant_value = inner.maybe_constant_value;
           Some(LatentScratch {
             is_constant: maybe_constant_value.is_some(),
-            dst: DynLatents::new(vec![maybe_constant_value.unwrap_or_default(); FULL_BATCH_N]).unwrap(),
+            dst: DynLatents::new(vec![maybe_constant_value.unwrap_or_default(); scratch_n]).unwrap(),
           })
         }
       )
-    }
+    };
     let delta_scratch = make_latent_scratch(latent_decompressors.delta.as_ref());
     let secondary_scratch = make_latent_scratch(latent_decompressors.secondary.as_ref());
 
     // we don't store the whole ChunkMeta because it can get large due to bins
-    Ok(Self {
+    let inner = Self {
       n,
       mode,
       delta_encoding: chunk_meta.delta_encoding,
@@ -135,7 +141,8 @@ impl<R: BetterBufRead> PageDecompressorInner<R> {
       latent_decompressors,
       delta_scratch,
       secondary_scratch,
-    })
+    };
+    Ok((inner, page_meta))
   }
 
   fn n_remaining(&self) -> usize {
@@ -146,12 +153,35 @@ impl<R: BetterBufRead> PageDecompressorInner<R> {
 impl<T: Number, R: BetterBufRead> PageDecompressor<T, R> {
   #[inline(never)]
   pub(crate) fn new(src: R, chunk_meta: &ChunkMeta, n: usize) -> PcoResult<Self> {
+    let (inner, page_meta) = PageDecompressorInner::new(src, chunk_meta, n)?;
+    let value_range = chunk_meta.page_value_range(
+      n,
+      &page_meta.per_latent_var.primary.delta_state,
+    );
     Ok(Self {
-      inner: PageDecompressorInner::new(src, chunk_meta, n)?,
+      inner,
+      value_range,
       phantom: PhantomData::<T>,
     })
   }
 
+  /// Returns the smallest and largest numbers this page could contain,
+  /// without decompressing it.
+  ///
+  /// Like [`ChunkMeta::value_range`], the bounds are conservative, but they
+  /// also work for consecutive delta encoding, using the page's delta
+  /// moments.
+  /// For sorted data with first-order delta encoding, the lower bound is
+  /// exactly the page's first number.
+  /// Higher delta orders bound each order of deltas in turn, so their bounds
+  /// loosen quickly as the page grows.
+  ///
+  /// Returns `None` if the bounds can't be derived, e.g. for lookback delta
+  /// encoding or float mult mode, or if they would overflow.
+  pub fn value_range(&self) -> Option<(T, T)> {
+    self.value_range
+  }
+
   fn decompress_batch(&mut self, dst: &mut [T]) -> PcoResult<()> {
     let batch_n = dst.len();
     let inner = &mut self.inner;
@@ -270,7 +300,15 @@ impl<T: Number, R: BetterBufRead> PageDecompressor<T, R> {
     let mut n_processed = 0;
     while n_processed < n_to_process {
       let dst_batch_end = min(n_processed + FULL_BATCH_N, n_to_process);
-      self.decompress_batch(&mut num_dst[n_processed..dst_batch_end])?;
+      let batch_start = self.inner.n_processed;
+      self
+        .decompress_batch(&mut num_dst[n_processed..dst_batch_end])
+        .map_err(|err| {
+          err.with_context(format!(
+            "in page batch starting at number {}",
+            batch_start
+          ))
+        })?;
       n_processed = dst_batch_end;
     }
 
@@ -280,6 +318,15 @@ impl<T: Number, R: BetterBufRead> PageDecompressor<T, R> {
     })
   }
 
+  /// Returns the number of bytes of the page consumed so far, including its
+  /// metadata.
+  ///
+  /// Once the page is finished, this is the page's exact size, which can be
+  /// compared against an externally stored length.
+  pub fn n_bytes_consumed(&self) -> usize {
+    self.inner.reader_builder.n_bytes_consumed()
+  }
+
   /// Returns the rest of the compressed data source.
   pub fn into_src(self) -> R {
     self.inner.reader_builder.into_inner()
@@ -0,0 +1,259 @@
+#![doc = include_str!("../README.md")]
+#![deny(clippy::unused_unit)]
+#![deny(dead_code)]
+
+use std::sync::Arc;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::*;
+use arrow_array::{Array, ArrayRef, ArrowPrimitiveType, PrimitiveArray};
+use arrow_buffer::{NullBuffer, ScalarBuffer};
+use arrow_schema::{ArrowError, DataType, IntervalUnit, TimeUnit};
+
+use pco::data_types::{Number, NumberType};
+use pco::errors::PcoError;
+use pco::standalone;
+use pco::ChunkConfig;
+
+fn pco_err(e: PcoError) -> ArrowError {
+  ArrowError::ExternalError(Box::new(e))
+}
+
+// Calls `$f::<P>(...)` for the Arrow primitive type `P` matching the data
+// type, or returns an error if pco doesn't support it.
+macro_rules! dispatch_primitive {
+  ($data_type: expr, $f: ident($($arg: expr),*)) => {
+    match $data_type {
+      DataType::Float16 => $f::<Float16Type>($($arg),*),
+      DataType::Float32 => $f::<Float32Type>($($arg),*),
+      DataType::Float64 => $f::<Float64Type>($($arg),*),
+      DataType::Int16 => $f::<Int16Type>($($arg),*),
+      DataType::Int32 => $f::<Int32Type>($($arg),*),
+      DataType::Int64 => $f::<Int64Type>($($arg),*),
+      DataType::UInt8 => $f::<UInt8Type>($($arg),*),
+      DataType::UInt16 => $f::<UInt16Type>($($arg),*),
+      DataType::UInt32 => $f::<UInt32Type>($($arg),*),
+      DataType::UInt64 => $f::<UInt64Type>($($arg),*),
+      DataType::Date32 => $f::<Date32Type>($($arg),*),
+      DataType::Date64 => $f::<Date64Type>($($arg),*),
+      DataType::Time32(TimeUnit::Second) => $f::<Time32SecondType>($($arg),*),
+      DataType::Time32(TimeUnit::Millisecond) => $f::<Time32MillisecondType>($($arg),*),
+      DataType::Time64(TimeUnit::Microsecond) => $f::<Time64MicrosecondType>($($arg),*),
+      DataType::Time64(TimeUnit::Nanosecond) => $f::<Time64NanosecondType>($($arg),*),
+      DataType::Timestamp(TimeUnit::Second, _) => $f::<TimestampSecondType>($($arg),*),
+      DataType::Timestamp(TimeUnit::Millisecond, _) => {
+        $f::<TimestampMillisecondType>($($arg),*)
+      }
+      DataType::Timestamp(TimeUnit::Microsecond, _) => {
+        $f::<TimestampMicrosecondType>($($arg),*)
+      }
+      DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+        $f::<TimestampNanosecondType>($($arg),*)
+      }
+      DataType::Duration(TimeUnit::Second) => $f::<DurationSecondType>($($arg),*),
+      DataType::Duration(TimeUnit::Millisecond) => $f::<DurationMillisecondType>($($arg),*),
+      DataType::Duration(TimeUnit::Microsecond) => $f::<DurationMicrosecondType>($($arg),*),
+      DataType::Duration(TimeUnit::Nanosecond) => $f::<DurationNanosecondType>($($arg),*),
+      DataType::Interval(IntervalUnit::YearMonth) => $f::<IntervalYearMonthType>($($arg),*),
+      other => Err(ArrowError::NotYetImplemented(format!(
+        "pco does not support Arrow data type {}",
+        other
+      ))),
+    }
+  };
+}
+
+fn number_type_of<P: ArrowPrimitiveType>() -> Result<NumberType, ArrowError>
+where
+  P::Native: Number,
+{
+  Ok(
+    NumberType::from_descriminant(<P::Native as Number>::NUMBER_TYPE_BYTE)
+      .expect("Arrow native type should be a core pco number type"),
+  )
+}
+
+/// Returns the pco number type that Arrow arrays of this data type are
+/// compressed as, or `None` if the data type is unsupported.
+///
+/// For instance, all timestamp types are compressed as `i64`s.
+pub fn number_type(data_type: &DataType) -> Option<NumberType> {
+  let res: Result<NumberType, ArrowError> = dispatch_primitive!(data_type, number_type_of());
+  res.ok()
+}
+
+/// Returns the plain numeric Arrow data type for a pco number type, or `None`
+/// if Arrow has no equivalent.
+///
+/// This is the inverse of [`number_type`] for non-temporal data types; for
+/// instance, `i64`s map to `Int64` rather than any timestamp type.
+pub fn data_type(number_type: NumberType) -> Option<DataType> {
+  let res = match number_type {
+    NumberType::F16 => DataType::Float16,
+    NumberType::F32 => DataType::Float32,
+    NumberType::F64 => DataType::Float64,
+    NumberType::I16 => DataType::Int16,
+    NumberType::I32 => DataType::Int32,
+    NumberType::I64 => DataType::Int64,
+    NumberType::U8 => DataType::UInt8,
+    NumberType::U16 => DataType::UInt16,
+    NumberType::U32 => DataType::UInt32,
+    NumberType::U64 => DataType::UInt64,
+    _ => return None,
+  };
+  Some(res)
+}
+
+fn compress_primitive<P: ArrowPrimitiveType>(
+  array: &dyn Array,
+  config: &ChunkConfig,
+) -> Result<Vec<u8>, ArrowError>
+where
+  P::Native: Number,
+{
+  let array = array.as_primitive::<P>();
+  let validity = match array.nulls() {
+    Some(nulls) => nulls.iter().collect::<Vec<_>>(),
+    None => vec![true; array.len()],
+  };
+  standalone::simple_compress_nullable(array.values(), &validity, config).map_err(pco_err)
+}
+
+fn decompress_primitive<P: ArrowPrimitiveType>(
+  src: &[u8],
+  data_type: &DataType,
+) -> Result<ArrayRef, ArrowError>
+where
+  P::Native: Number,
+{
+  let (nums, validity) =
+    standalone::simple_decompress_nullable::<P::Native>(src).map_err(pco_err)?;
+  let nulls = if validity.iter().all(|&is_valid| is_valid) {
+    None
+  } else {
+    Some(NullBuffer::from(validity))
+  };
+  let array = PrimitiveArray::<P>::new(ScalarBuffer::from(nums), nulls);
+  Ok(Arc::new(
+    array.with_data_type(data_type.clone()),
+  ))
+}
+
+/// Compresses a primitive Arrow array, including its nulls, to bytes.
+///
+/// Nulls are stored as a compressed run-length bitmap, followed by the
+/// non-null values (see
+/// [`simple_compress_nullable`][pco::standalone::simple_compress_nullable]).
+/// Will return an error if the array's data type is unsupported (see
+/// [`number_type`]) or the config is invalid.
+pub fn compress_array(array: &dyn Array, config: &ChunkConfig) -> Result<Vec<u8>, ArrowError> {
+  dispatch_primitive!(
+    array.data_type(),
+    compress_primitive(array, config)
+  )
+}
+
+/// Takes in bytes produced by [`compress_array`] and returns an array of
+/// the given data type.
+///
+/// The data type must be the one the array was compressed with, or at least
+/// one compressed as the same pco number type (e.g. a timestamp with a
+/// different time zone).
+/// Will return an error if the data type is unsupported, mismatched, or if
+/// there are any corruption or insufficient data issues.
+pub fn decompress_array(src: &[u8], data_type: &DataType) -> Result<ArrayRef, ArrowError> {
+  dispatch_primitive!(
+    data_type,
+    decompress_primitive(src, data_type)
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use arrow_array::{Float32Array, Int32Array, StringArray, TimestampMillisecondArray, UInt8Array};
+
+  use super::*;
+
+  fn assert_recovers(array: &dyn Array) -> Result<(), ArrowError> {
+    let compressed = compress_array(array, &ChunkConfig::default())?;
+    let decompressed = decompress_array(&compressed, array.data_type())?;
+    assert_eq!(decompressed.as_ref(), array);
+    Ok(())
+  }
+
+  #[test]
+  fn test_recovers_without_nulls() -> Result<(), ArrowError> {
+    assert_recovers(&Float32Array::from(
+      (0..1000).map(|i| i as f32 / 7.0).collect::<Vec<_>>(),
+    ))?;
+    assert_recovers(&Int32Array::from(Vec::<i32>::new()))?;
+    assert_recovers(&UInt8Array::from(
+      (0..1000).map(|i| (i % 5) as u8).collect::<Vec<_>>(),
+    ))?;
+    Ok(())
+  }
+
+  #[test]
+  fn test_recovers_with_nulls() -> Result<(), ArrowError> {
+    let values = (0..1000)
+      .map(|i| {
+        if i % 3 == 0 || i < 10 {
+          None
+        } else {
+          Some(i * 100)
+        }
+      })
+      .collect::<Vec<_>>();
+    assert_recovers(&Int32Array::from(values))?;
+    assert_recovers(&Int32Array::from(vec![None, None]))?;
+    assert_recovers(&Int32Array::from(vec![Some(1), None]))?;
+
+    let timestamps = TimestampMillisecondArray::from(vec![Some(1_i64), None, Some(3)])
+      .with_timezone("+01:00".to_string());
+    assert_recovers(&timestamps)?;
+    Ok(())
+  }
+
+  #[test]
+  fn test_errors() {
+    let strings = StringArray::from(vec!["a", "b"]);
+    assert!(compress_array(&strings, &ChunkConfig::default()).is_err());
+
+    let compressed = compress_array(
+      &Int32Array::from(vec![1, 2, 3]),
+      &ChunkConfig::default(),
+    )
+    .unwrap();
+    assert!(decompress_array(&compressed, &DataType::Int64).is_err());
+    assert!(decompress_array(&compressed[..3], &DataType::Int32).is_err());
+    assert!(decompress_array(&compressed, &DataType::Utf8).is_err());
+  }
+
+  #[test]
+  fn test_number_type() {
+    assert_eq!(
+      number_type(&DataType::Timestamp(
+        TimeUnit::Nanosecond,
+        None
+      )),
+      Some(NumberType::I64)
+    );
+    assert_eq!(
+      number_type(&DataType::Float16),
+      Some(NumberType::F16)
+    );
+    assert_eq!(number_type(&DataType::Utf8), None);
+  }
+
+  #[test]
+  fn test_data_type() {
+    assert_eq!(
+      data_type(NumberType::I64),
+      Some(DataType::Int64)
+    );
+    assert_eq!(
+      data_type(NumberType::F16).and_then(|dtype| number_type(&dtype)),
+      Some(NumberType::F16)
+    );
+  }
+}
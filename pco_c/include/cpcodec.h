@@ -15,6 +15,7 @@ extern "C" {
 #define PCO_TYPE_U16 7
 #define PCO_TYPE_I16 8
 #define PCO_TYPE_F16 9
+#define PCO_TYPE_U8 10
 
 #if defined (__cplusplus)
 }
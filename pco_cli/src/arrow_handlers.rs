@@ -5,6 +5,7 @@ use arrow::datatypes::*;
 
 use crate::bench::handler::BenchHandler;
 use crate::compress::handler::CompressHandler;
+use crate::dtypes;
 use crate::dtypes::ArrowNumber;
 
 fn new_boxed_handler<P: ArrowNumber>() -> Box<dyn ArrowHandler> {
@@ -16,6 +17,10 @@ fn new_boxed_handler<P: ArrowNumber>() -> Box<dyn ArrowHandler> {
 pub fn from_dtype(dtype: &DataType) -> Result<Box<dyn ArrowHandler>> {
   use DataType::*;
 
+  // This should cover every data type pco_arrow can compress, so that
+  // anything dtypes::from_arrow accepts has a handler.
+  let dtype = dtypes::value_dtype(dtype);
+
   macro_rules! match_dtype {
     {$($name:pat => $t:ty,)+} => {
       match dtype {
@@ -32,6 +37,7 @@ pub fn from_dtype(dtype: &DataType) -> Result<Box<dyn ArrowHandler>> {
     Int16 => Int16Type,
     Int32 => Int32Type,
     Int64 => Int64Type,
+    UInt8 => UInt8Type,
     UInt16 => UInt16Type,
     UInt32 => UInt32Type,
     UInt64 => UInt64Type,
@@ -39,6 +45,17 @@ pub fn from_dtype(dtype: &DataType) -> Result<Box<dyn ArrowHandler>> {
     Timestamp(TimeUnit::Millisecond, _) => TimestampMillisecondType,
     Timestamp(TimeUnit::Microsecond, _) => TimestampMicrosecondType,
     Timestamp(TimeUnit::Nanosecond, _) => TimestampNanosecondType,
+    Date32 => Date32Type,
+    Date64 => Date64Type,
+    Time32(TimeUnit::Second) => Time32SecondType,
+    Time32(TimeUnit::Millisecond) => Time32MillisecondType,
+    Time64(TimeUnit::Microsecond) => Time64MicrosecondType,
+    Time64(TimeUnit::Nanosecond) => Time64NanosecondType,
+    Duration(TimeUnit::Second) => DurationSecondType,
+    Duration(TimeUnit::Millisecond) => DurationMillisecondType,
+    Duration(TimeUnit::Microsecond) => DurationMicrosecondType,
+    Duration(TimeUnit::Nanosecond) => DurationNanosecondType,
+    Interval(IntervalUnit::YearMonth) => IntervalYearMonthType,
   )
 }
 
@@ -0,0 +1,83 @@
+use std::convert::TryInto;
+
+use clap::Parser;
+
+use pco::data_types::Latent;
+
+use crate::bench::codecs::CodecInternal;
+use crate::dtypes::PcoNumber;
+
+// n (4 bytes) + bit width (1 byte) + min latent (8 bytes)
+const HEADER_SIZE: usize = 13;
+
+// Stores each number as its offset from the minimum, using the fewest bits
+// that can represent every offset in the column. There is no entropy coding,
+// so this is a useful floor to compare other codecs against.
+#[derive(Clone, Debug, Parser)]
+pub struct BitpackConfig {}
+
+impl CodecInternal for BitpackConfig {
+  fn name(&self) -> &'static str {
+    "bitpack"
+  }
+
+  fn get_confs(&self) -> Vec<(&'static str, String)> {
+    vec![]
+  }
+
+  fn compress<T: PcoNumber>(&self, nums: &[T]) -> Vec<u8> {
+    let latents = nums
+      .iter()
+      .map(|x| x.to_latent_ordered())
+      .collect::<Vec<_>>();
+    let min = latents.iter().copied().min().unwrap_or(T::L::ZERO);
+    let max = latents.iter().copied().max().unwrap_or(T::L::ZERO);
+    let bit_width = T::L::BITS - (max - min).leading_zeros();
+
+    let mut res = Vec::with_capacity(HEADER_SIZE + (nums.len() * bit_width as usize).div_ceil(8));
+    res.extend((nums.len() as u32).to_le_bytes());
+    res.push(bit_width as u8);
+    res.extend(min.to_u64().to_le_bytes());
+
+    let mut acc = 0_u128;
+    let mut acc_bits = 0;
+    for &l in &latents {
+      acc |= ((l - min).to_u64() as u128) << acc_bits;
+      acc_bits += bit_width;
+      while acc_bits >= 8 {
+        res.push(acc as u8);
+        acc >>= 8;
+        acc_bits -= 8;
+      }
+    }
+    if acc_bits > 0 {
+      res.push(acc as u8);
+    }
+    res
+  }
+
+  fn decompress<T: PcoNumber>(&self, bytes: &[u8]) -> Vec<T> {
+    let n = u32::from_le_bytes(bytes[0..4].try_into().unwrap()) as usize;
+    let bit_width = bytes[4] as u32;
+    let min = T::L::from_u64(u64::from_le_bytes(
+      bytes[5..HEADER_SIZE].try_into().unwrap(),
+    ));
+    let mask = (1_u128 << bit_width) - 1;
+
+    let mut res = Vec::with_capacity(n);
+    let mut packed = bytes[HEADER_SIZE..].iter();
+    let mut acc = 0_u128;
+    let mut acc_bits = 0;
+    for _ in 0..n {
+      while acc_bits < bit_width {
+        acc |= (*packed.next().unwrap() as u128) << acc_bits;
+        acc_bits += 8;
+      }
+      let offset = T::L::from_u64((acc & mask) as u64);
+      acc >>= bit_width;
+      acc_bits -= bit_width;
+      res.push(T::from_latent_ordered(min + offset));
+    }
+    res
+  }
+}
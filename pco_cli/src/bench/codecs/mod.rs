@@ -8,6 +8,7 @@ use std::time::{Duration, Instant};
 use anyhow::{anyhow, Result};
 use clap::{CommandFactory, FromArgMatches};
 
+use crate::bench::codecs::bitpack::BitpackConfig;
 #[cfg(feature = "full_bench")]
 use crate::bench::codecs::blosc::BloscConfig;
 use crate::bench::codecs::parquet::ParquetConfig;
@@ -20,13 +21,14 @@ use crate::bench::codecs::spdp::SpdpConfig;
 use crate::bench::codecs::turbo_pfor::TurboPforConfig;
 use crate::bench::codecs::zstd::ZstdConfig;
 use crate::bench::IterOpt;
-use crate::bench::{BenchStat, Precomputed};
+use crate::bench::{decode_timing, peak_alloc, BenchStat, Precomputed};
 use crate::chunk_config_opt::ChunkConfigOpt;
 use crate::dtypes::PcoNumber;
 use crate::num_vec::NumVec;
 use ::pco::data_types::NumberType;
 use ::pco::match_number_enum;
 
+mod bitpack;
 #[cfg(feature = "full_bench")]
 mod blosc;
 mod parquet;
@@ -51,6 +53,12 @@ trait CodecInternal: Clone + CommandFactory + Debug + FromArgMatches + Send + Sy
   fn compress<T: PcoNumber>(&self, nums: &[T]) -> Vec<u8>;
   fn decompress<T: PcoNumber>(&self, compressed: &[u8]) -> Vec<T>;
 
+  // Also returns how many chunks fell back to a trivial baseline, for codecs
+  // that have one.
+  fn compress_counting_fallbacks<T: PcoNumber>(&self, nums: &[T]) -> (Vec<u8>, usize) {
+    (self.compress(nums), 0)
+  }
+
   // sad manual dynamic dispatch, but at least we don't need all combinations
   // of (dtype x codec)
   fn compress_dynamic(&self, num_vec: &NumVec) -> Vec<u8> {
@@ -60,6 +68,13 @@ trait CodecInternal: Clone + CommandFactory + Debug + FromArgMatches + Send + Sy
     )
   }
 
+  fn compress_counting_fallbacks_dynamic(&self, num_vec: &NumVec) -> (Vec<u8>, usize) {
+    match_number_enum!(
+      num_vec,
+      NumVec<T>(nums) => { self.compress_counting_fallbacks(nums) }
+    )
+  }
+
   fn decompress_dynamic(&self, dtype: NumberType, compressed: &[u8]) -> NumVec {
     match_number_enum!(
       dtype,
@@ -139,7 +154,7 @@ impl<C: CodecInternal> CodecSurface for C {
     let dtype = num_vec.dtype();
 
     // compress
-    let compressed = self.compress_dynamic(num_vec);
+    let (compressed, n_fallback_chunks) = self.compress_counting_fallbacks_dynamic(num_vec);
 
     // write to disk
     if let Some(dir) = opt.save_dir.as_ref() {
@@ -161,7 +176,10 @@ impl<C: CodecInternal> CodecSurface for C {
       }
     }
 
-    Ok(Precomputed { compressed })
+    Ok(Precomputed {
+      compressed,
+      n_fallback_chunks,
+    })
   }
 
   fn stats_iter(
@@ -171,27 +189,43 @@ impl<C: CodecInternal> CodecSurface for C {
     opt: &IterOpt,
   ) -> Result<BenchStat> {
     // compress
-    let compress_dt = if !opt.no_compress {
+    let (compress_dt, compress_peak_bytes) = if !opt.no_compress {
       let t = Instant::now();
-      let _ = self.compress_dynamic(num_vec);
-      Instant::now() - t
+      let (_, peak_bytes) = peak_alloc::measure(|| self.compress_dynamic(num_vec));
+      (Instant::now() - t, peak_bytes)
     } else {
-      Duration::ZERO
+      (Duration::ZERO, 0)
     };
 
     // decompress
-    let decompress_dt = if !opt.no_decompress {
+    let (decompress_dt, decompress_peak_bytes, ans_dt, offsets_dt) = if !opt.no_decompress {
       let t = Instant::now();
-      let _ = self.decompress_dynamic(num_vec.dtype(), &precomputed.compressed);
-      Instant::now() - t
+      let ((_, peak_bytes), ans_dt, offsets_dt) = decode_timing::measure(|| {
+        peak_alloc::measure(|| self.decompress_dynamic(num_vec.dtype(), &precomputed.compressed))
+      });
+      (
+        Instant::now() - t,
+        peak_bytes,
+        ans_dt,
+        offsets_dt,
+      )
     } else {
-      Duration::ZERO
+      (
+        Duration::ZERO,
+        0,
+        Duration::ZERO,
+        Duration::ZERO,
+      )
     };
 
     Ok(BenchStat {
       compressed_size: precomputed.compressed.len(),
+      n_fallback_chunks: precomputed.n_fallback_chunks,
       compress_dt,
       decompress_dt,
+      peak_bytes: compress_peak_bytes.max(decompress_peak_bytes),
+      ans_dt,
+      offsets_dt,
     })
   }
 
@@ -223,6 +257,7 @@ impl FromStr for CodecConfig {
     }
 
     let codec: Result<Box<dyn CodecSurface>> = match name {
+      "bitpack" => BitpackConfig::from_kv_args(&clap_kv_args),
       #[cfg(feature = "full_bench")]
       "blosc" => BloscConfig::from_kv_args(&clap_kv_args),
       "parquet" => ParquetConfig::from_kv_args(&clap_kv_args),
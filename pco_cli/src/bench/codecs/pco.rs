@@ -19,6 +19,7 @@ fn unparse_mode_spec(spec: &ModeSpec) -> String {
     ModeSpec::Auto => "Auto".to_string(),
     ModeSpec::Classic => "Classic".to_string(),
     ModeSpec::TryFloatMult(base) => format!("FloatMult@{}", base),
+    ModeSpec::TryFloatMultLossy(base) => format!("FloatMultLossy@{}", base),
     ModeSpec::TryFloatQuant(k) => format!("FloatQuant@{}", k),
     ModeSpec::TryIntMult(base) => format!("IntMult@{}", base),
     _ => "Unknown".to_string(),
@@ -34,6 +35,10 @@ impl CodecInternal for ChunkConfigOpt {
     vec![
       ("level", self.level.to_string()),
       ("delta", unparse_delta_spec(&self.delta)),
+      (
+        "secondary-uses-delta",
+        self.secondary_uses_delta.to_string(),
+      ),
       ("mode", unparse_mode_spec(&self.mode)),
       ("chunk-n", self.chunk_n.to_string()),
     ]
@@ -47,4 +52,15 @@ impl CodecInternal for ChunkConfigOpt {
   fn decompress<T: PcoNumber>(&self, bytes: &[u8]) -> Vec<T> {
     pco::standalone::simple_decompress::<T>(bytes).expect("could not decompress")
   }
+
+  fn compress_counting_fallbacks<T: PcoNumber>(&self, nums: &[T]) -> (Vec<u8>, usize) {
+    let chunk_config = ChunkConfig::from(self);
+    let mut n_fallback_chunks = 0;
+    let compressed =
+      pco::standalone::simple_compress_with_callback(nums, &chunk_config, &mut |decision| {
+        n_fallback_chunks += decision.used_fallback as usize
+      })
+      .expect("invalid config");
+    (compressed, n_fallback_chunks)
+  }
 }
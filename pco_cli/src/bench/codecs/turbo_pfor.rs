@@ -64,6 +64,29 @@ macro_rules! impl_pforable {
   };
 }
 
+// TurboPFor's 128-wide vectorized codecs don't support 8-bit numbers, so we
+// widen to u16.
+impl TurboPforable for u8 {
+  unsafe fn encode(src: &mut [Self], dst: &mut [u8]) -> usize {
+    let mut widened = src.iter().map(|&x| x as u16).collect::<Vec<_>>();
+    // u16 encoding might briefly need more room than dst has, even though
+    // 8-bit values pack back down to about a byte each
+    let mut widened_dst = vec![0; 64 + 2 * dst.len()];
+    let byte_len = u16::encode(&mut widened, &mut widened_dst);
+    dst[..byte_len].copy_from_slice(&widened_dst[..byte_len]);
+    byte_len
+  }
+  unsafe fn decode(src: &mut [u8], n: usize, dst: &mut [Self]) {
+    let mut widened = vec![0_u16; n];
+    u16::decode(src, n, &mut widened);
+    // dst may be uninitialized, so we write through its pointer
+    let dst_ptr = dst.as_mut_ptr();
+    for (i, &x) in widened.iter().enumerate() {
+      *dst_ptr.add(i) = x as u8;
+    }
+  }
+}
+
 impl_pforable!(u16, u16, p4nenc128v16, p4ndec128v16);
 impl_pforable!(u32, u32, p4nenc128v32, p4ndec128v32);
 impl_pforable!(u64, u64, p4nenc128v64, p4ndec128v64);
@@ -0,0 +1,21 @@
+// Splits pco decompression time between ANS symbol decoding and offset
+// reading while running a closure.
+// This is only enabled with the decode_timing feature, since the timers slow
+// down pco decompression.
+
+use std::time::Duration;
+
+#[cfg(feature = "decode_timing")]
+pub fn measure<R, F: FnOnce() -> R>(f: F) -> (R, Duration, Duration) {
+  // discard anything accumulated outside this measurement
+  pco::take_decode_timing();
+  let res = f();
+  let timing = pco::take_decode_timing();
+  (res, timing.ans, timing.offsets)
+}
+
+// Without the feature, we still run the closure but report 0 durations.
+#[cfg(not(feature = "decode_timing"))]
+pub fn measure<R, F: FnOnce() -> R>(f: F) -> (R, Duration, Duration) {
+  (f(), Duration::ZERO, Duration::ZERO)
+}
@@ -5,9 +5,9 @@ use std::ops::AddAssign;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::time::Duration;
-use std::{any, fs};
+use std::{any, fs, slice};
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use arrow::datatypes::{DataType, Schema};
 use clap::{Args, Parser};
 use indicatif::{ProgressBar, ProgressStyle};
@@ -19,11 +19,15 @@ use pco::data_types::NumberType;
 use pco::match_number_enum;
 
 use crate::bench::codecs::CodecConfig;
+use crate::bench::synthetic::SyntheticSpec;
 use crate::input::{Format, InputColumnOpt, InputFileOpt};
 use crate::{arrow_handlers, dtypes, input, parse};
 
 mod codecs;
+mod decode_timing;
 pub mod handler;
+mod peak_alloc;
+pub mod synthetic;
 
 const DEFAULT_BINARY_DIR: &str = "data/binary";
 // if this delta order is specified, use a dataset-specific order
@@ -37,6 +41,9 @@ const DEFAULT_BINARY_DIR: &str = "data/binary";
 /// loaded into memory prior to benchmarking each dataset.
 /// By default, if no inputs are specified, the bench will use the
 /// relative directory `data/binary/` as binary input.
+/// If a directory is given without an input format, each file's format is
+/// inferred from its extension.
+/// Synthetic datasets can also be generated in-process with --synthetic.
 #[derive(Clone, Debug, Parser)]
 pub struct BenchOpt {
   /// Comma-separated list of codecs to benchmark, optionally with
@@ -76,6 +83,19 @@ pub struct BenchOpt {
   /// If you're not writing the results to a CSV, ignore this.
   #[arg(long)]
   pub input_name: Option<String>,
+  /// Comma-separated synthetic datasets to generate in-process, each of the
+  /// form `<pattern>:<count>`, e.g. `linear:1e6,timestamps:1000`.
+  ///
+  /// Patterns: constant, linear, noisy_linear, sparse_spikes, geometric,
+  /// and timestamps.
+  /// If given without other input, only synthetic datasets are benchmarked.
+  #[arg(long, value_parser = SyntheticSpec::from_str, value_delimiter = ',')]
+  pub synthetic: Vec<SyntheticSpec>,
+  /// Replace each pco codec with one for every compression level from 0 to
+  /// the max, keeping its other configurations.
+  /// The `<sum>` row for each codec then gives the totals per level.
+  #[arg(long)]
+  pub sweep_levels: bool,
   #[command(flatten)]
   pub input: InputFileOpt,
   #[command(flatten)]
@@ -117,6 +137,7 @@ impl BenchOpt {
 
 pub struct Precomputed {
   compressed: Vec<u8>,
+  n_fallback_chunks: usize,
 }
 
 fn make_progress_bar(n_columns: usize, opt: &BenchOpt) -> ProgressBar {
@@ -145,6 +166,23 @@ pub struct BenchStat {
   #[tabled(display_with = "display_duration")]
   pub decompress_dt: Duration,
   pub compressed_size: usize,
+  /// How many chunks fell back to the trivial baseline; always 0 for codecs
+  /// other than pco.
+  pub n_fallback_chunks: usize,
+  /// The most heap memory allocated during a single compress or decompress
+  /// call; only measured with the peak_memory feature.
+  #[cfg_attr(not(feature = "peak_memory"), tabled(skip))]
+  pub peak_bytes: usize,
+  /// Time pco spent decoding ANS symbols during decompression; only
+  /// measured with the decode_timing feature.
+  #[cfg_attr(not(feature = "decode_timing"), tabled(skip))]
+  #[tabled(display_with = "display_duration")]
+  pub ans_dt: Duration,
+  /// Time pco spent reading offset bits during decompression; only
+  /// measured with the decode_timing feature.
+  #[cfg_attr(not(feature = "decode_timing"), tabled(skip))]
+  #[tabled(display_with = "display_duration")]
+  pub offsets_dt: Duration,
 }
 
 #[derive(Clone, Tabled)]
@@ -158,14 +196,19 @@ pub struct PrintStat {
 impl AddAssign for BenchStat {
   fn add_assign(&mut self, rhs: Self) {
     self.compressed_size += rhs.compressed_size;
+    self.n_fallback_chunks += rhs.n_fallback_chunks;
     self.compress_dt += rhs.compress_dt;
     self.decompress_dt += rhs.decompress_dt;
+    self.peak_bytes = self.peak_bytes.max(rhs.peak_bytes);
+    self.ans_dt += rhs.ans_dt;
+    self.offsets_dt += rhs.offsets_dt;
   }
 }
 
 impl BenchStat {
   fn aggregate_median(benches: &[BenchStat]) -> Self {
     let compressed_size = benches[0].compressed_size;
+    let n_fallback_chunks = benches[0].n_fallback_chunks;
     let compress_dts = benches
       .iter()
       .map(|bench| bench.compress_dt)
@@ -174,11 +217,25 @@ impl BenchStat {
       .iter()
       .map(|bench| bench.decompress_dt)
       .collect::<Vec<_>>();
+    let peak_bytes = benches
+      .iter()
+      .map(|bench| bench.peak_bytes)
+      .max()
+      .unwrap_or_default();
+    let ans_dts = benches.iter().map(|bench| bench.ans_dt).collect::<Vec<_>>();
+    let offsets_dts = benches
+      .iter()
+      .map(|bench| bench.offsets_dt)
+      .collect::<Vec<_>>();
 
     BenchStat {
       compressed_size,
+      n_fallback_chunks,
       compress_dt: median_duration(compress_dts),
       decompress_dt: median_duration(decompress_dts),
+      peak_bytes,
+      ans_dt: median_duration(ans_dts),
+      offsets_dt: median_duration(offsets_dts),
     }
   }
 }
@@ -196,14 +253,47 @@ fn core_dtype_to_str(dtype: NumberType) -> String {
   )
 }
 
+// Returns a schema for each input file, along with the options to read it
+// with. If we're given a directory without an input format, we infer each
+// file's format from its extension, so directories of mixed formats work.
+fn get_schemas(input: &InputFileOpt) -> Result<Vec<(Schema, InputFileOpt)>> {
+  let path = input.input.as_ref().unwrap();
+  if input.input_format.is_some() || !path.is_dir() {
+    let schema = input::get_schema(&InputColumnOpt::default(), input)?;
+    return Ok(vec![(schema, input.clone())]);
+  }
+
+  let mut files = Vec::new();
+  for entry in fs::read_dir(path)? {
+    let file = entry?.path();
+    if file.is_file() {
+      files.push(file);
+    }
+  }
+  files.sort();
+
+  let mut res = Vec::new();
+  for file in files {
+    let file_input = InputFileOpt {
+      input: Some(file.clone()),
+      ..input.clone()
+    };
+    let schema = input::get_schema(&InputColumnOpt::default(), &file_input)
+      .with_context(|| format!("while reading {:?}", file))?;
+    res.push((schema, file_input));
+  }
+  Ok(res)
+}
+
 fn handle_column(
   schema: &Schema,
   col_idx: usize,
+  input: &InputFileOpt,
   opt: &BenchOpt,
   progress_bar: &mut ProgressBar,
 ) -> Result<Vec<PrintStat>> {
   let field = &schema.fields[col_idx];
-  let reader = input::new_column_reader(schema, col_idx, &opt.input)?;
+  let reader = input::new_column_reader(schema, col_idx, input)?;
   let mut arrays = Vec::new();
   for array_result in reader {
     arrays.push(array_result?);
@@ -212,6 +302,30 @@ fn handle_column(
   handler.bench(&arrays, field.name(), opt, progress_bar)
 }
 
+fn sweep_pco_levels(codecs: &[CodecConfig]) -> Result<Vec<CodecConfig>> {
+  let mut res = Vec::new();
+  for codec in codecs {
+    if codec.name() != "pco" {
+      res.push(codec.clone());
+      continue;
+    }
+
+    let other_confs = codec
+      .details(true)
+      .split(':')
+      .filter(|conf| !conf.is_empty() && !conf.starts_with("level="))
+      .map(|conf| format!(":{}", conf))
+      .collect::<String>();
+    for level in 0..=pco::MAX_COMPRESSION_LEVEL {
+      res.push(CodecConfig::from_str(&format!(
+        "pco:level={}{}",
+        level, other_confs
+      ))?);
+    }
+  }
+  Ok(res)
+}
+
 fn update_results_csv(
   aggregate_by_codec: &HashMap<String, BenchStat>,
   opt: &BenchOpt,
@@ -318,32 +432,66 @@ pub fn bench(mut opt: BenchOpt) -> Result<()> {
       "input-name must be specified when results-csv is"
     ));
   }
+  if opt.sweep_levels {
+    opt.codecs = sweep_pco_levels(&opt.codecs)?;
+  }
   let input = &mut opt.input;
-  if input.input.is_none() && input.input_format.is_none() {
+  if input.input.is_none() && input.input_format.is_none() && opt.synthetic.is_empty() {
     input.input = Some(PathBuf::from(DEFAULT_BINARY_DIR));
     input.input_format = Some(Format::Binary);
   }
 
-  let schema = input::get_schema(&InputColumnOpt::default(), input)?;
-
-  let col_idxs = schema
-    .fields
+  let schemas = if input.input.is_some() {
+    get_schemas(input)?
+  } else {
+    Vec::new()
+  };
+  let synthetic_arrays = opt
+    .synthetic
     .iter()
-    .enumerate()
-    .filter_map(|(i, field)| {
-      if opt.includes_dataset(field.data_type(), field.name()) {
-        Some(i)
-      } else {
-        None
-      }
-    })
+    .map(|spec| (spec.name(), spec.generate()))
+    .filter(|(name, array)| opt.includes_dataset(array.data_type(), name))
     .collect::<Vec<_>>();
-  let mut progress_bar = make_progress_bar(col_idxs.len(), &opt);
+
+  let mut col_idxs_by_input = Vec::new();
+  for (schema, input) in &schemas {
+    let col_idxs = schema
+      .fields
+      .iter()
+      .enumerate()
+      .filter_map(|(i, field)| {
+        if opt.includes_dataset(field.data_type(), field.name()) {
+          Some(i)
+        } else {
+          None
+        }
+      })
+      .collect::<Vec<_>>();
+    col_idxs_by_input.push((schema, input, col_idxs));
+  }
+  let n_columns = col_idxs_by_input
+    .iter()
+    .map(|(_, _, col_idxs)| col_idxs.len())
+    .sum::<usize>()
+    + synthetic_arrays.len();
+  let mut progress_bar = make_progress_bar(n_columns, &opt);
   let mut stats = Vec::new();
-  for col_idx in col_idxs {
-    stats.extend(handle_column(
-      &schema,
-      col_idx,
+  for (schema, input, col_idxs) in col_idxs_by_input {
+    for col_idx in col_idxs {
+      stats.extend(handle_column(
+        schema,
+        col_idx,
+        input,
+        &opt,
+        &mut progress_bar,
+      )?);
+    }
+  }
+  for (name, array) in &synthetic_arrays {
+    let handler = arrow_handlers::from_dtype(array.data_type())?;
+    stats.extend(handler.bench(
+      slice::from_ref(array),
+      name,
       &opt,
       &mut progress_bar,
     )?);
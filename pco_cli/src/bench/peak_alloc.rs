@@ -0,0 +1,64 @@
+// Tracks the peak number of heap bytes allocated while running a closure.
+// This is only enabled with the peak_memory feature, since wrapping the
+// global allocator adds a small overhead to every allocation.
+
+#[cfg(feature = "peak_memory")]
+mod tracking {
+  use std::alloc::{GlobalAlloc, Layout, System};
+  use std::sync::atomic::{AtomicUsize, Ordering};
+
+  static CURRENT: AtomicUsize = AtomicUsize::new(0);
+  static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+  struct PeakAlloc;
+
+  unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+      let ptr = System.alloc(layout);
+      if !ptr.is_null() {
+        let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK.fetch_max(current, Ordering::Relaxed);
+      }
+      ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+      System.dealloc(ptr, layout);
+      CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+      let new_ptr = System.realloc(ptr, layout, new_size);
+      if !new_ptr.is_null() {
+        if new_size > layout.size() {
+          let added = new_size - layout.size();
+          let current = CURRENT.fetch_add(added, Ordering::Relaxed) + added;
+          PEAK.fetch_max(current, Ordering::Relaxed);
+        } else {
+          CURRENT.fetch_sub(layout.size() - new_size, Ordering::Relaxed);
+        }
+      }
+      new_ptr
+    }
+  }
+
+  #[global_allocator]
+  static ALLOCATOR: PeakAlloc = PeakAlloc;
+
+  pub fn measure<R, F: FnOnce() -> R>(f: F) -> (R, usize) {
+    let start = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(start, Ordering::Relaxed);
+    let res = f();
+    let peak = PEAK.load(Ordering::Relaxed);
+    (res, peak.saturating_sub(start))
+  }
+}
+
+#[cfg(feature = "peak_memory")]
+pub use tracking::measure;
+
+// Without the feature, we still run the closure but report 0 bytes.
+#[cfg(not(feature = "peak_memory"))]
+pub fn measure<R, F: FnOnce() -> R>(f: F) -> (R, usize) {
+  (f(), 0)
+}
@@ -0,0 +1,168 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use arrow::array::{ArrayRef, Float64Array, Int64Array, TimestampMicrosecondArray};
+
+// Synthetic datasets generated in-process, so benchmarks can run without any
+// files on disk. Everything is seeded deterministically, so the same spec
+// always yields the same numbers.
+
+// splitmix64, which is plenty random for benchmark data and saves us a
+// dependency
+struct Rng(u64);
+
+impl Rng {
+  fn new() -> Self {
+    Self(0)
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = self.0;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+  }
+
+  // uniform in [0, 1)
+  fn next_f64(&mut self) -> f64 {
+    (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+  }
+}
+
+pub fn constant(n: usize) -> Vec<i64> {
+  vec![1234567; n]
+}
+
+pub fn linear(n: usize) -> Vec<i64> {
+  (0..n as i64).map(|i| 1000 + 7 * i).collect()
+}
+
+pub fn noisy_linear(n: usize) -> Vec<f64> {
+  let mut rng = Rng::new();
+  (0..n)
+    .map(|i| i as f64 * 0.01 + rng.next_f64() - 0.5)
+    .collect()
+}
+
+pub fn sparse_spikes(n: usize) -> Vec<i64> {
+  let mut rng = Rng::new();
+  (0..n)
+    .map(|_| {
+      if rng.next_f64() < 0.01 {
+        (rng.next_u64() % 1_000_000) as i64
+      } else {
+        0
+      }
+    })
+    .collect()
+}
+
+pub fn geometric(n: usize) -> Vec<f64> {
+  let mut rng = Rng::new();
+  (0..n)
+    .map(|_| 2.0_f64.powi((rng.next_u64() % 64) as i32 - 32))
+    .collect()
+}
+
+// microseconds since the epoch, about 1 second apart with some jitter
+pub fn timestamps(n: usize) -> Vec<i64> {
+  let mut rng = Rng::new();
+  let mut t = 1_700_000_000_000_000_i64;
+  (0..n)
+    .map(|_| {
+      t += 1_000_000 + (rng.next_u64() % 1000) as i64;
+      t
+    })
+    .collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pattern {
+  Constant,
+  Linear,
+  NoisyLinear,
+  SparseSpikes,
+  Geometric,
+  Timestamps,
+}
+
+const PATTERN_NAMES: [(&str, Pattern); 6] = [
+  ("constant", Pattern::Constant),
+  ("linear", Pattern::Linear),
+  ("noisy_linear", Pattern::NoisyLinear),
+  ("sparse_spikes", Pattern::SparseSpikes),
+  ("geometric", Pattern::Geometric),
+  ("timestamps", Pattern::Timestamps),
+];
+
+impl Pattern {
+  fn name(&self) -> &'static str {
+    PATTERN_NAMES
+      .iter()
+      .find(|(_, pattern)| pattern == self)
+      .unwrap()
+      .0
+  }
+}
+
+/// A synthetic dataset, specified as `<pattern>:<count>`, e.g. `linear:1e6`.
+#[derive(Clone, Debug)]
+pub struct SyntheticSpec {
+  pub pattern: Pattern,
+  pub n: usize,
+}
+
+impl SyntheticSpec {
+  pub fn name(&self) -> String {
+    format!("synthetic_{}", self.pattern.name())
+  }
+
+  pub fn generate(&self) -> ArrayRef {
+    let n = self.n;
+    match self.pattern {
+      Pattern::Constant => Arc::new(Int64Array::from(constant(n))),
+      Pattern::Linear => Arc::new(Int64Array::from(linear(n))),
+      Pattern::NoisyLinear => Arc::new(Float64Array::from(noisy_linear(n))),
+      Pattern::SparseSpikes => Arc::new(Int64Array::from(sparse_spikes(n))),
+      Pattern::Geometric => Arc::new(Float64Array::from(geometric(n))),
+      Pattern::Timestamps => Arc::new(TimestampMicrosecondArray::from(timestamps(
+        n,
+      ))),
+    }
+  }
+}
+
+impl FromStr for SyntheticSpec {
+  type Err = anyhow::Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    let err = || {
+      let names = PATTERN_NAMES
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>();
+      anyhow!(
+        "invalid synthetic dataset: {}; expected <pattern>:<count> with a pattern in {:?}",
+        s,
+        names,
+      )
+    };
+    let (name, n_str) = s.split_once(':').ok_or_else(err)?;
+    let pattern = PATTERN_NAMES
+      .iter()
+      .find(|(pattern_name, _)| *pattern_name == name.to_lowercase())
+      .ok_or_else(err)?
+      .1;
+    // parse as a float so counts like 1e6 work
+    let n = n_str.parse::<f64>().map_err(|_| err())?;
+    if !(n >= 0.0 && n.fract() == 0.0) {
+      return Err(err());
+    }
+    Ok(Self {
+      pattern,
+      n: n as usize,
+    })
+  }
+}
@@ -16,8 +16,12 @@ pub struct ChunkConfigOpt {
   /// which tries to automatically detect the best delta encoding order.
   #[arg(long, default_value = "Auto", value_parser = parse::delta_spec)]
   pub delta: DeltaSpec,
-  /// Can be "Auto", "Classic", "FloatMult@<base>", "FloatQuant@<k>", or
-  /// "IntMult@<base>".
+  /// Whether to also apply the chosen delta encoding to secondary latent
+  /// variables, such as int mult adjustments.
+  #[arg(long, default_value_t = false, action = clap::ArgAction::Set)]
+  pub secondary_uses_delta: bool,
+  /// Can be "Auto", "Classic", "FloatMult@<base>", "FloatMultLossy@<base>",
+  /// "FloatQuant@<k>", or "IntMult@<base>".
   ///
   /// Specs other than Auto and Classic will try the given mode and fall back to
   /// classic if the given mode is especially bad.
@@ -32,6 +36,7 @@ impl From<&ChunkConfigOpt> for ChunkConfig {
     ChunkConfig::default()
       .with_compression_level(opt.level)
       .with_delta_spec(opt.delta)
+      .with_secondary_uses_delta(opt.secondary_uses_delta)
       .with_mode_spec(opt.mode)
       .with_paging_spec(PagingSpec::EqualPagesUpTo(opt.chunk_n))
   }
@@ -1,11 +1,15 @@
 use std::cmp::min;
 use std::fs::OpenOptions;
+use std::io;
+use std::io::{BufWriter, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use arrow::datatypes::Schema;
 
 use pco::standalone::FileCompressor;
-use pco::ChunkConfig;
+use pco::{ChunkConfig, DeltaSpec, ModeSpec};
 
 use crate::arrow_handlers::ArrowHandlerImpl;
 use crate::compress::CompressOpt;
@@ -16,22 +20,144 @@ pub trait CompressHandler {
   fn compress(&self, opt: &CompressOpt, schema: &Schema) -> Result<()>;
 }
 
+// Counts bytes written so we can report chunk sizes even when the
+// destination (e.g. stdout) isn't seekable.
+struct CountingWriter<W: Write> {
+  inner: W,
+  count: u64,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    let n = self.inner.write(buf)?;
+    self.count += n as u64;
+    Ok(n)
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    self.inner.flush()
+  }
+}
+
+// E.g. out.pco -> out.003.pco.
+fn part_path(path: &Path, part_idx: usize) -> PathBuf {
+  let mut file_name = path.file_stem().unwrap_or_default().to_os_string();
+  file_name.push(format!(".{:03}", part_idx));
+  if let Some(ext) = path.extension() {
+    file_name.push(".");
+    file_name.push(ext);
+  }
+  path.with_file_name(file_name)
+}
+
+fn open_dst(opt: &CompressOpt, part_idx: usize) -> Result<CountingWriter<Box<dyn Write>>> {
+  let inner = open_dst_inner(opt, part_idx)?;
+  Ok(CountingWriter { inner, count: 0 })
+}
+
+fn open_dst_inner(opt: &CompressOpt, part_idx: usize) -> Result<Box<dyn Write>> {
+  if opt.writes_to_stdout() {
+    if opt.split_bytes.is_some() {
+      return Err(anyhow!(
+        "cannot split output written to stdout"
+      ));
+    }
+    let stdout = io::stdout();
+    if stdout.is_terminal() {
+      return Err(anyhow!(
+        "refusing to write compressed data to a terminal; redirect stdout or give an output path"
+      ));
+    }
+    return Ok(Box::new(BufWriter::new(stdout.lock())));
+  }
+
+  let mut open_options = OpenOptions::new();
+  open_options.write(true);
+  if opt.overwrite {
+    open_options.create(true);
+    open_options.truncate(true);
+  } else {
+    open_options.create_new(true);
+  }
+  let path = if opt.split_bytes.is_some() {
+    part_path(opt.path(), part_idx)
+  } else {
+    opt.path().to_path_buf()
+  };
+  let file = open_options.open(path)?;
+  Ok(Box::new(BufWriter::new(file)))
+}
+
+// Compresses only the first chunk, reading the rest of the input just to
+// count the numbers, and extrapolates the total size from it.
+fn dry_run<P: ArrowNumber>(opt: &CompressOpt, schema: &Schema) -> Result<()> {
+  let config = ChunkConfig::from(&opt.chunk_config);
+  let chunk_size = opt.chunk_config.chunk_n;
+  let col_idx = utils::find_col_idx(
+    schema,
+    opt.input_column.col_idx,
+    &opt.input_column.col_name,
+  )?;
+  let reader = input::new_column_reader(schema, col_idx, &opt.input_file)?;
+  let mut sample = Vec::<P::Pco>::new();
+  let mut n = 0;
+  for array_result in reader {
+    let nums = utils::arrow_to_nums::<P>(array_result?);
+    n += nums.len();
+    let n_to_take = min(chunk_size - sample.len(), nums.len());
+    sample.extend_from_slice(&nums[..n_to_take]);
+  }
+
+  let fc = FileCompressor::default();
+  let header_and_footer_size =
+    fc.write_header(Vec::new())?.len() + fc.write_footer(Vec::new())?.len();
+  println!("n = {}", n);
+  if sample.is_empty() {
+    println!("estimated_size = {}", header_and_footer_size);
+    return Ok(());
+  }
+
+  let cc = fc.chunk_compressor(&sample, &config)?;
+  let sample_size = cc.write_chunk(Vec::new())?.len();
+  let meta = cc.meta();
+  println!("mode = {:?}", meta.mode);
+  println!("delta_encoding = {:?}", meta.delta_encoding);
+  println!(
+    "compression_level = {}",
+    config.compression_level
+  );
+  println!("fallback = {}", cc.used_fallback());
+  println!("sample_n = {}", sample.len());
+  println!("sample_size = {}", sample_size);
+  let estimated_size =
+    header_and_footer_size as f64 + sample_size as f64 * n as f64 / sample.len() as f64;
+  println!("estimated_size = {}", estimated_size.round());
+  Ok(())
+}
+
 impl<P: ArrowNumber> CompressHandler for ArrowHandlerImpl<P> {
   fn compress(&self, opt: &CompressOpt, schema: &Schema) -> Result<()> {
-    let mut open_options = OpenOptions::new();
-    open_options.write(true);
-    if opt.overwrite {
-      open_options.create(true);
-      open_options.truncate(true);
-    } else {
-      open_options.create_new(true);
+    if opt.dry_run {
+      return dry_run::<P>(opt, schema);
     }
-    let file = open_options.open(&opt.path)?;
+
+    let mut part_idx = 0;
+    let mut dst = open_dst(opt, part_idx)?;
 
     let config = ChunkConfig::from(&opt.chunk_config);
+    let fast_config = config
+      .clone()
+      .with_mode_spec(ModeSpec::Classic)
+      .with_delta_spec(DeltaSpec::None);
+    let max_duration = opt.max_seconds.map(Duration::from_secs_f64);
+    let mut compress_duration = Duration::ZERO;
+    let mut last_chunk_duration = Duration::ZERO;
     let chunk_size = opt.chunk_config.chunk_n;
     let fc = FileCompressor::default();
-    fc.write_header(&file)?;
+    fc.write_header(&mut dst)?;
+    let header_size = dst.count;
+    let footer_size = fc.write_footer(Vec::new())?.len() as u64;
+    let mut chunk_bytes = Vec::new();
 
     let col_idx = utils::find_col_idx(
       schema,
@@ -41,7 +167,8 @@ impl<P: ArrowNumber> CompressHandler for ArrowHandlerImpl<P> {
     let reader = input::new_column_reader(schema, col_idx, &opt.input_file)?;
     let mut num_buffer = Vec::<P::Pco>::new();
 
-    let write_chunks = |num_buffer: &mut Vec<P::Pco>, finish: bool| -> Result<()> {
+    let mut chunk_idx = 0;
+    let mut write_chunks = |num_buffer: &mut Vec<P::Pco>, finish: bool| -> Result<()> {
       let n = num_buffer.len();
       let n_chunks = if finish {
         n.div_ceil(chunk_size)
@@ -52,14 +179,60 @@ impl<P: ArrowNumber> CompressHandler for ArrowHandlerImpl<P> {
       let mut end = 0;
       for _ in 0..n_chunks {
         end = min(start + chunk_size, num_buffer.len());
-        fc.chunk_compressor(&num_buffer[start..end], &config)?
-          .write_chunk(&file)?;
+        // We assume the next chunk will take about as long as the last one.
+        let over_budget = max_duration
+          .is_some_and(|max_duration| compress_duration + last_chunk_duration >= max_duration);
+        let chunk_config = if over_budget { &fast_config } else { &config };
+        let chunk_start = Instant::now();
+        let cc = fc.chunk_compressor(&num_buffer[start..end], chunk_config)?;
+        last_chunk_duration = chunk_start.elapsed();
+        compress_duration += last_chunk_duration;
+        chunk_bytes.clear();
+        chunk_bytes.reserve(cc.chunk_size_hint());
+        cc.write_chunk(&mut chunk_bytes)?;
+        let chunk_n_bytes = chunk_bytes.len() as u64;
+
+        if let Some(split_bytes) = opt.split_bytes {
+          if dst.count + chunk_n_bytes + footer_size > split_bytes {
+            if header_size + chunk_n_bytes + footer_size > split_bytes {
+              return Err(anyhow!(
+                "chunk {} takes {} bytes, which doesn't fit in a file of {} bytes; consider lowering --chunk-n",
+                chunk_idx,
+                chunk_n_bytes,
+                split_bytes,
+              ));
+            }
+            // roll over to a new file, which this chunk fits in on its own
+            fc.write_footer(&mut dst)?;
+            dst.flush()?;
+            part_idx += 1;
+            dst = open_dst(opt, part_idx)?;
+            fc.write_header(&mut dst)?;
+          }
+        }
+
+        dst.write_all(&chunk_bytes)?;
+        if opt.verbose {
+          let meta = cc.meta();
+          eprintln!(
+            "chunk {}: n={} mode={:?} delta={:?} fallback={} bytes={}",
+            chunk_idx,
+            end - start,
+            meta.mode,
+            meta.delta_encoding,
+            cc.used_fallback(),
+            chunk_n_bytes,
+          );
+        }
+        chunk_idx += 1;
         start = end;
       }
       num_buffer.drain(..end);
       Ok(())
     };
 
+    // We write chunks as soon as they fill, so we only ever hold about one
+    // chunk plus one input batch in memory, no matter how large the column is.
     for array_result in reader {
       let array = array_result?;
       num_buffer.extend(utils::arrow_to_nums::<P>(array));
@@ -68,7 +241,8 @@ impl<P: ArrowNumber> CompressHandler for ArrowHandlerImpl<P> {
 
     write_chunks(&mut num_buffer, true)?;
 
-    fc.write_footer(&file)?;
+    fc.write_footer(&mut dst)?;
+    dst.flush()?;
     Ok(())
   }
 }
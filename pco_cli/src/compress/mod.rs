@@ -1,11 +1,12 @@
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
+use arrow::datatypes::Schema;
 use clap::Parser;
 
 use crate::input::{InputColumnOpt, InputFileOpt};
-use crate::utils;
-use crate::{arrow_handlers, chunk_config_opt, input};
+use crate::{arrow_handlers, chunk_config_opt, input, parse, utils};
 
 pub mod handler;
 
@@ -15,6 +16,30 @@ pub struct CompressOpt {
   /// Overwrite the output path (if it exists) instead of failing.
   #[arg(long)]
   pub overwrite: bool,
+  /// Print the size and chosen mode and delta encoding of each chunk to
+  /// stderr as it is written.
+  #[arg(short, long)]
+  pub verbose: bool,
+  /// A rough wall-clock budget for compression, in seconds.
+  /// Once the next chunk is projected to exceed it, the remaining chunks skip
+  /// the mode and delta encoding search, compressing with classic mode and no
+  /// delta encoding instead.
+  /// Reading the input is not included.
+  #[arg(long)]
+  pub max_seconds: Option<f64>,
+  /// Splits the output into multiple standalone .pco files, each no larger
+  /// than this many bytes, e.g. 64MiB.
+  /// Files are named like out.000.pco, out.001.pco, etc. and split at chunk
+  /// boundaries, so each can be decompressed on its own.
+  /// Fails if a single chunk doesn't fit; lower --chunk-n in that case.
+  #[arg(long, value_parser = parse::byte_size)]
+  pub split_bytes: Option<u64>,
+  /// Instead of writing anything, compress just the first chunk and print
+  /// the mode and delta encoding chosen for it, along with an estimate of
+  /// the compressed size.
+  /// The rest of the input is still read to count the numbers.
+  #[arg(long)]
+  pub dry_run: bool,
   #[command(flatten)]
   pub input_file: InputFileOpt,
   #[command(flatten)]
@@ -22,18 +47,73 @@ pub struct CompressOpt {
   #[command(flatten)]
   pub chunk_config: chunk_config_opt::ChunkConfigOpt,
 
-  /// Output .pco path to write to.
-  pub path: PathBuf,
+  /// Output .pco path to write to, or `-` to write to stdout (which must not
+  /// be a terminal).
+  /// With --col-regex, this is instead a directory to write each matched
+  /// column to as `<column name>.pco`.
+  /// Not needed with --dry-run.
+  #[arg(required_unless_present = "dry_run")]
+  pub path: Option<PathBuf>,
 }
 
-pub fn compress(opt: CompressOpt) -> Result<()> {
-  let schema = input::get_schema(&opt.input_column, &opt.input_file)?;
+impl CompressOpt {
+  // Only valid when not doing a dry run.
+  pub fn path(&self) -> &Path {
+    self.path.as_deref().expect("output path is required")
+  }
+
+  pub fn writes_to_stdout(&self) -> bool {
+    self.path().as_os_str() == "-"
+  }
+}
+
+fn compress_column(opt: &CompressOpt, schema: &Schema) -> Result<()> {
   let col_idx = utils::find_col_idx(
-    &schema,
+    schema,
     opt.input_column.col_idx,
     &opt.input_column.col_name,
   )?;
   let dtype = schema.field(col_idx).data_type();
   let handler = arrow_handlers::from_dtype(dtype)?;
-  handler.compress(&opt, &schema)
+  handler.compress(opt, schema)
+}
+
+pub fn compress(opt: CompressOpt) -> Result<()> {
+  let schema = input::get_schema(&opt.input_column, &opt.input_file)?;
+  if opt.input_column.col_regex.is_none() {
+    return compress_column(&opt, &schema);
+  }
+
+  if opt.dry_run {
+    for col_idx in utils::find_col_idxs(&schema, &opt.input_column)? {
+      let col_name = schema.field(col_idx).name();
+      let mut col_opt = opt.clone();
+      col_opt.input_column.col_idx = Some(col_idx);
+      println!("[{}]", col_name);
+      compress_column(&col_opt, &schema).with_context(|| format!("in column {}", col_name))?;
+    }
+    return Ok(());
+  }
+
+  if opt.writes_to_stdout() {
+    return Err(anyhow!(
+      "cannot write multiple columns to stdout"
+    ));
+  }
+  fs::create_dir_all(opt.path())?;
+  for col_idx in utils::find_col_idxs(&schema, &opt.input_column)? {
+    let col_name = schema.field(col_idx).name();
+    let mut col_opt = opt.clone();
+    col_opt.input_column.col_idx = Some(col_idx);
+    col_opt.path = Some(opt.path().join(format!("{}.pco", col_name)));
+    if opt.verbose {
+      eprintln!(
+        "compressing column {} to {}",
+        col_name,
+        col_opt.path().display()
+      );
+    }
+    compress_column(&col_opt, &schema).with_context(|| format!("in column {}", col_name))?;
+  }
+  Ok(())
 }
@@ -5,7 +5,9 @@ use std::path::PathBuf;
 use anyhow::Result;
 use clap::{Parser, ValueEnum};
 
-use crate::{core_handlers, utils};
+use pco::standalone::peek_dtype;
+
+use crate::core_handlers;
 
 pub mod handler;
 
@@ -37,7 +39,7 @@ pub fn decompress(opt: DecompressOpt) -> Result<()> {
     Err(e) if matches!(e.kind(), ErrorKind::UnexpectedEof) => (),
     other => other?,
   };
-  let Some(dtype) = utils::get_standalone_dtype(&initial_bytes)? else {
+  let Some(dtype) = peek_dtype(&initial_bytes)? else {
     // file terminated; nothing to decompress
     return Ok(());
   };
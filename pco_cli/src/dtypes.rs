@@ -40,7 +40,7 @@ pub trait Parquetable: Sized {
 pub trait QCompressable: Sized {
   type Qco: q_compress::data_types::NumberLike;
 
-  fn nums_to_qco(nums: &[Self]) -> &[Self::Qco];
+  fn nums_to_qco(nums: &[Self]) -> std::borrow::Cow<[Self::Qco]>;
   fn qco_to_nums(vec: Vec<Self::Qco>) -> Vec<Self>;
 }
 
@@ -101,20 +101,24 @@ macro_rules! parquetable {
   };
 }
 
-macro_rules! trivial {
-  ($t: ty, $name: ident, $p: ty) => {
+macro_rules! qcompressable {
+  ($t: ty) => {
     #[cfg(feature = "full_bench")]
     impl QCompressable for $t {
       type Qco = $t;
 
-      fn nums_to_qco(nums: &[Self]) -> &[Self::Qco] {
-        nums
+      fn nums_to_qco(nums: &[Self]) -> std::borrow::Cow<[Self::Qco]> {
+        std::borrow::Cow::Borrowed(nums)
       }
       fn qco_to_nums(vec: Vec<Self::Qco>) -> Vec<Self> {
         vec
       }
     }
+  };
+}
 
+macro_rules! trivial {
+  ($t: ty, $name: ident, $p: ty) => {
     impl PcoNumber for $t {
       const ARROW_DTYPE: DataType = <$p as ArrowPrimitiveType>::DATA_TYPE;
 
@@ -168,8 +172,8 @@ parquetable!(f64, parquet::data_type::DoubleType, "DOUBLE");
 parquetable!(i32, parquet::data_type::Int32Type, "INT32");
 parquetable!(i64, parquet::data_type::Int64Type, "INT64");
 
-// For 16-bit types, we have no way to transmute into parquet types, so we need
-// to copy.
+// For 8- and 16-bit types, we have no way to transmute into parquet types, so
+// we need to copy.
 impl Parquetable for f16 {
   const PARQUET_DTYPE_STR: &'static str = "FLOAT";
   const TRANSMUTABLE: bool = false;
@@ -198,6 +202,19 @@ impl Parquetable for i16 {
   }
 }
 
+impl Parquetable for u8 {
+  const PARQUET_DTYPE_STR: &'static str = "INT32";
+  const TRANSMUTABLE: bool = false;
+  type Parquet = parquet::data_type::Int32Type;
+
+  fn copy_nums_to_parquet(nums: &[Self]) -> Vec<i32> {
+    nums.iter().map(|&x| x as i32).collect()
+  }
+  fn parquet_to_nums(vec: Vec<i32>) -> Vec<Self> {
+    vec.into_iter().map(|x| x as u8).collect()
+  }
+}
+
 impl Parquetable for u16 {
   const PARQUET_DTYPE_STR: &'static str = "INT32";
   const TRANSMUTABLE: bool = false;
@@ -242,12 +259,25 @@ impl Parquetable for u64 {
   }
 }
 
+// q_compress doesn't support u8, so we widen to u16.
+#[cfg(feature = "full_bench")]
+impl QCompressable for u8 {
+  type Qco = u16;
+
+  fn nums_to_qco(nums: &[Self]) -> std::borrow::Cow<[Self::Qco]> {
+    std::borrow::Cow::Owned(nums.iter().map(|&x| x as u16).collect())
+  }
+  fn qco_to_nums(vec: Vec<Self::Qco>) -> Vec<Self> {
+    vec.into_iter().map(|x| x as u8).collect()
+  }
+}
+
 #[cfg(feature = "full_bench")]
 impl QCompressable for f16 {
   type Qco = u16;
 
-  fn nums_to_qco(nums: &[Self]) -> &[Self::Qco] {
-    unsafe { mem::transmute(nums) }
+  fn nums_to_qco(nums: &[Self]) -> std::borrow::Cow<[Self::Qco]> {
+    std::borrow::Cow::Borrowed(unsafe { mem::transmute::<&[f16], &[u16]>(nums) })
   }
   fn qco_to_nums(vec: Vec<Self::Qco>) -> Vec<Self> {
     unsafe { mem::transmute(vec) }
@@ -272,11 +302,21 @@ impl PcoNumber for f16 {
   }
 }
 
+qcompressable!(f32);
+qcompressable!(f64);
+qcompressable!(i16);
+qcompressable!(i32);
+qcompressable!(i64);
+qcompressable!(u16);
+qcompressable!(u32);
+qcompressable!(u64);
+
 trivial!(f32, F32, arrow_dtypes::Float32Type);
 trivial!(f64, F64, arrow_dtypes::Float64Type);
 trivial!(i16, I16, arrow_dtypes::Int16Type);
 trivial!(i32, I32, arrow_dtypes::Int32Type);
 trivial!(i64, I64, arrow_dtypes::Int64Type);
+trivial!(u8, U8, arrow_dtypes::UInt8Type);
 trivial!(u16, U16, arrow_dtypes::UInt16Type);
 trivial!(u32, U32, arrow_dtypes::UInt32Type);
 trivial!(u64, U64, arrow_dtypes::UInt64Type);
@@ -286,43 +326,40 @@ extra_arrow!(i64, arrow_dtypes::TimestampSecondType);
 extra_arrow!(i64, arrow_dtypes::TimestampMillisecondType);
 extra_arrow!(i64, arrow_dtypes::TimestampMicrosecondType);
 extra_arrow!(i64, arrow_dtypes::TimestampNanosecondType);
+extra_arrow!(i32, arrow_dtypes::Date32Type);
+extra_arrow!(i64, arrow_dtypes::Date64Type);
+extra_arrow!(i32, arrow_dtypes::Time32SecondType);
+extra_arrow!(i32, arrow_dtypes::Time32MillisecondType);
+extra_arrow!(i64, arrow_dtypes::Time64MicrosecondType);
+extra_arrow!(i64, arrow_dtypes::Time64NanosecondType);
+extra_arrow!(i64, arrow_dtypes::DurationSecondType);
+extra_arrow!(i64, arrow_dtypes::DurationMillisecondType);
+extra_arrow!(i64, arrow_dtypes::DurationMicrosecondType);
+extra_arrow!(i64, arrow_dtypes::DurationNanosecondType);
+extra_arrow!(i32, arrow_dtypes::IntervalYearMonthType);
+
+// Dictionary-encoded columns get decoded and compressed as their values.
+pub fn value_dtype(arrow_dtype: &ArrowDataType) -> &ArrowDataType {
+  match arrow_dtype {
+    ArrowDataType::Dictionary(_, value_dtype) => value_dtype,
+    _ => arrow_dtype,
+  }
+}
 
 pub fn from_arrow(arrow_dtype: &ArrowDataType) -> Result<NumberType> {
-  let res = match arrow_dtype {
-    ArrowDataType::Float16 => NumberType::F16,
-    ArrowDataType::Float32 => NumberType::F32,
-    ArrowDataType::Float64 => NumberType::F64,
-    ArrowDataType::Int16 => NumberType::I16,
-    ArrowDataType::Int32 => NumberType::I32,
-    ArrowDataType::Int64 => NumberType::I64,
-    ArrowDataType::UInt16 => NumberType::U16,
-    ArrowDataType::UInt32 => NumberType::U32,
-    ArrowDataType::UInt64 => NumberType::U64,
-    ArrowDataType::Timestamp(_, _) => NumberType::I64,
-    _ => {
-      return Err(anyhow!(
-        "unable to convert arrow dtype {:?} to pco",
-        arrow_dtype
-      ))
-    }
-  };
-  Ok(res)
+  pco_arrow::number_type(value_dtype(arrow_dtype)).ok_or_else(|| {
+    anyhow!(
+      "unable to convert arrow dtype {:?} to pco",
+      arrow_dtype
+    )
+  })
 }
 
-pub fn to_arrow(dtype: NumberType) -> ArrowDataType {
-  match dtype {
-    NumberType::F16 => ArrowDataType::Float16,
-    NumberType::F32 => ArrowDataType::Float32,
-    NumberType::F64 => ArrowDataType::Float64,
-    NumberType::I16 => ArrowDataType::Int16,
-    NumberType::I32 => ArrowDataType::Int32,
-    NumberType::I64 => ArrowDataType::Int64,
-    NumberType::U16 => ArrowDataType::UInt16,
-    NumberType::U32 => ArrowDataType::UInt32,
-    NumberType::U64 => ArrowDataType::UInt64,
-    other => panic!(
+pub fn to_arrow(dtype: NumberType) -> Result<ArrowDataType> {
+  pco_arrow::data_type(dtype).ok_or_else(|| {
+    anyhow!(
       "number type {:?} not yet supported in pco_cli",
-      other
-    ),
-  }
+      dtype
+    )
+  })
 }
@@ -1,26 +1,25 @@
 use std::collections::HashMap;
 use std::fs;
 use std::fs::File;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use anyhow::{anyhow, Context, Result};
-use arrow::array::{
-  ArrayData, ArrayRef, Float16Array, Float32Array, Float64Array, Int16Array, Int32Array,
-  Int64Array, UInt16Array, UInt32Array, UInt64Array,
-};
-use arrow::buffer::Buffer;
+use arrow::array::{ArrayData, ArrayRef, PrimitiveArray};
+use arrow::buffer::{Buffer, ScalarBuffer};
+use arrow::compute;
 use arrow::csv;
 use arrow::csv::Reader as CsvReader;
-use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::datatypes::*;
 use arrow::record_batch::RecordBatchReader;
 use clap::Parser;
-use half::f16;
 use parquet::arrow::arrow_reader::{ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder};
 use parquet::arrow::ProjectionMask;
+use regex::Regex;
 
-use pco::data_types::NumberType;
-use pco::standalone::simple_decompress;
+use pco::data_types::{Number, NumberType};
+use pco::standalone::{peek_dtype, simple_decompress};
 
 use crate::{dtypes, parse, utils};
 
@@ -29,18 +28,33 @@ mod audio;
 
 const MAX_INFER_SCHEMA_RECORDS: usize = 1000;
 
+#[derive(Clone, Debug)]
+pub enum DtypeOverride {
+  /// Applies to every selected column.
+  Selected(DataType),
+  /// Applies to each named column, whether or not it is selected.
+  Named(Vec<(String, DataType)>),
+}
+
 #[derive(Clone, Debug, Default, Parser)]
 pub struct InputColumnOpt {
   /// A specific data type to interpret the column as. Only useful for data
   /// coming from CSVs where the type is ambiguous.
-  #[arg(long, value_parser = parse::arrow_dtype)]
-  pub dtype: Option<DataType>,
-  /// Either this or col-idx must be specified.
+  /// Alternatively, a comma-separated list of name:dtype pairs, e.g.
+  /// 'a:i32,b:f32', to override the data types of those columns.
+  #[arg(long, value_parser = parse::dtype_override)]
+  pub dtype: Option<DtypeOverride>,
+  /// Either this, col-idx, or col-regex must be specified.
   #[arg(long)]
   pub col_name: Option<String>,
-  /// Either this or col-name must be specified.
+  /// Either this, col-name, or col-regex must be specified.
   #[arg(long)]
   pub col_idx: Option<usize>,
+  /// A regex selecting every column whose name it matches, e.g.
+  /// '^sensor_\d+$'. Only supported by commands that handle multiple
+  /// columns.
+  #[arg(long, conflicts_with_all = ["col_name", "col_idx"])]
+  pub col_regex: Option<Regex>,
 }
 
 #[derive(clap::ValueEnum, Clone, Copy, Debug, Parser)]
@@ -52,6 +66,13 @@ pub enum Format {
   Wav,
 }
 
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Endianness {
+  #[default]
+  Little,
+  Big,
+}
+
 #[derive(Clone, Debug, Parser)]
 pub struct InputFileOpt {
   /// File or directory to be used as input.
@@ -64,6 +85,9 @@ pub struct InputFileOpt {
   pub csv_has_header: bool,
   #[arg(long, default_value = ",")]
   pub csv_delimiter: char,
+  /// Byte order of each number in binary input files.
+  #[arg(long, default_value = "little")]
+  pub binary_endian: Endianness,
 }
 
 impl InputFileOpt {
@@ -72,12 +96,9 @@ impl InputFileOpt {
       return Ok(format);
     }
 
-    let ext = self
-      .input
-      .as_ref()
-      .and_then(|path| path.extension())
-      .and_then(|ext| ext.to_str());
+    let ext = self.input.as_deref().and_then(uncompressed_extension);
     let format = match ext {
+      Some("bin") => Format::Binary,
       Some("csv") => Format::Csv,
       Some("parquet") => Format::Parquet,
       Some("pco") => Format::Pco,
@@ -92,6 +113,48 @@ impl InputFileOpt {
   }
 }
 
+fn extension(path: &Path) -> Option<&str> {
+  path.extension().and_then(|ext| ext.to_str())
+}
+
+fn is_compressed_extension(ext: &str) -> bool {
+  matches!(ext, "gz" | "zst")
+}
+
+// The extension of the file before any gzip or zstd compression, e.g. csv
+// for my_data.csv.gz.
+fn uncompressed_extension(path: &Path) -> Option<&str> {
+  match extension(path) {
+    Some(ext) if is_compressed_extension(ext) => {
+      path.file_stem().and_then(|stem| extension(Path::new(stem)))
+    }
+    ext => ext,
+  }
+}
+
+#[cfg(feature = "gzip")]
+fn new_gzip_reader(file: File) -> Result<Box<dyn Read>> {
+  Ok(Box::new(flate2::read::MultiGzDecoder::new(
+    file,
+  )))
+}
+
+#[cfg(not(feature = "gzip"))]
+fn new_gzip_reader(_file: File) -> Result<Box<dyn Read>> {
+  Err(anyhow!("not compiled with gzip feature"))
+}
+
+// Opens the file, transparently decompressing it if it has a .gz or .zst
+// extension.
+fn open_maybe_compressed(path: &Path) -> Result<Box<dyn Read>> {
+  let file = File::open(path)?;
+  match extension(path) {
+    Some("gz") => new_gzip_reader(file),
+    Some("zst") => Ok(Box::new(zstd::Decoder::new(file)?)),
+    _ => Ok(Box::new(file)),
+  }
+}
+
 fn schema_from_field_paths(mut field_paths: Vec<(Field, PathBuf)>) -> Result<Schema> {
   field_paths.sort_by_key(|(field, _)| field.name().to_string());
   let mut metadata = HashMap::new();
@@ -155,32 +218,53 @@ fn infer_binary_schema(dir: &Path) -> Result<Schema> {
   single_column_or_filtered_dir_schema(dir, get_binary_field)
 }
 
-fn infer_csv_schema(col_opt: &InputColumnOpt, file_opt: &InputFileOpt) -> Result<Schema> {
-  // arrow API is kinda bad right now, so we have to convert the paths
-  // back to strings
-  let inferred_schema = csv::infer_schema_from_files(
-    &[file_opt
-      .input
+fn is_selected(col_opt: &InputColumnOpt, col_idx: usize, name: &str) -> bool {
+  match (&col_opt.col_name, &col_opt.col_idx) {
+    (Some(selected_name), None) if selected_name == name => true,
+    (None, Some(selected_idx)) if *selected_idx == col_idx => true,
+    _ => col_opt
+      .col_regex
       .as_ref()
-      .unwrap()
-      .to_str()
-      .unwrap()
-      .to_string()],
-    file_opt.csv_delimiter as u8,
-    Some(MAX_INFER_SCHEMA_RECORDS),
-    file_opt.csv_has_header,
-  )?;
-
-  let Some(dtype) = &col_opt.dtype else {
+      .is_some_and(|regex| regex.is_match(name)),
+  }
+}
+
+fn infer_csv_schema(col_opt: &InputColumnOpt, file_opt: &InputFileOpt) -> Result<Schema> {
+  let reader = open_maybe_compressed(file_opt.input.as_ref().unwrap())?;
+  let (inferred_schema, _) = csv::reader::Format::default()
+    .with_header(file_opt.csv_has_header)
+    .with_delimiter(file_opt.csv_delimiter as u8)
+    .infer_schema(reader, Some(MAX_INFER_SCHEMA_RECORDS))?;
+
+  let Some(dtype_override) = &col_opt.dtype else {
     return Ok(inferred_schema);
   };
 
+  if let DtypeOverride::Named(named) = dtype_override {
+    for (name, _) in named {
+      if inferred_schema.field_with_name(name).is_err() {
+        return Err(anyhow!(
+          "dtype override for column {:?} not found in CSV",
+          name
+        ));
+      }
+    }
+  }
+
   let mut fields = Vec::new();
   for (col_idx, field) in inferred_schema.fields().iter().enumerate() {
-    let new_field = match (&col_opt.col_name, &col_opt.col_idx) {
-      (Some(name), None) if name == field.name() => Field::new(name, dtype.clone(), false),
-      (None, Some(idx)) if *idx == col_idx => Field::new(field.name(), dtype.clone(), false),
-      _ => field.as_ref().clone(),
+    let dtype = match dtype_override {
+      DtypeOverride::Selected(dtype) => {
+        is_selected(col_opt, col_idx, field.name()).then_some(dtype)
+      }
+      DtypeOverride::Named(named) => named
+        .iter()
+        .find(|(name, _)| name == field.name())
+        .map(|(_, dtype)| dtype),
+    };
+    let new_field = match dtype {
+      Some(dtype) => Field::new(field.name(), dtype.clone(), false),
+      None => field.as_ref().clone(),
     };
     fields.push(new_field);
   }
@@ -192,10 +276,19 @@ fn infer_parquet_schema(col_opt: &InputColumnOpt, path: &Path) -> Result<Schema>
   let reader = ParquetRecordBatchReaderBuilder::try_new(file)?.build()?;
   let schema = reader.schema().as_ref().clone();
 
-  if let Some(arrow_dtype) = &col_opt.dtype {
-    let col_idx = utils::find_col_idx(&schema, col_opt.col_idx, &col_opt.col_name)?;
-    let field = schema.field(col_idx);
-    if field.data_type() != arrow_dtype {
+  let overridden = match &col_opt.dtype {
+    None => vec![],
+    Some(DtypeOverride::Selected(arrow_dtype)) => utils::find_col_idxs(&schema, col_opt)?
+      .into_iter()
+      .map(|col_idx| (schema.field(col_idx), arrow_dtype))
+      .collect(),
+    Some(DtypeOverride::Named(named)) => named
+      .iter()
+      .map(|(name, arrow_dtype)| Ok((schema.field_with_name(name)?, arrow_dtype)))
+      .collect::<Result<Vec<_>>>()?,
+  };
+  for (field, arrow_dtype) in overridden {
+    if dtypes::value_dtype(field.data_type()) != arrow_dtype {
       return Err(anyhow!(
         "optionally specified dtype {:?} did not match parquet schema {:?}",
         arrow_dtype,
@@ -209,11 +302,16 @@ fn infer_parquet_schema(col_opt: &InputColumnOpt, path: &Path) -> Result<Schema>
 fn get_pco_field(path: &Path) -> Result<Option<Field>> {
   // horribly inefficient, but we're not making performance a concern here yet
   let compressed = fs::read(path)?;
-  let field = utils::get_standalone_dtype(&compressed)?.map(|dtype| {
-    let name = path.file_stem().unwrap().to_str().unwrap();
-    Field::new(name, dtypes::to_arrow(dtype), false)
-  });
-  Ok(field)
+  peek_dtype(&compressed)?
+    .map(|dtype| {
+      let name = path.file_stem().unwrap().to_str().unwrap();
+      Ok(Field::new(
+        name,
+        dtypes::to_arrow(dtype)?,
+        false,
+      ))
+    })
+    .transpose()
 }
 
 fn infer_pco_schema(path: &Path) -> Result<Schema> {
@@ -271,7 +369,11 @@ pub fn new_column_reader(
 ) -> Result<Box<dyn Iterator<Item = Result<ArrayRef>>>> {
   let path = opt.input.as_ref().unwrap();
   let res: Box<dyn Iterator<Item = Result<ArrayRef>>> = match opt.format()? {
-    Format::Binary => Box::new(BinaryColumnReader::new(schema, col_idx)?),
+    Format::Binary => Box::new(BinaryColumnReader::new(
+      schema,
+      col_idx,
+      opt.binary_endian,
+    )?),
     Format::Csv => Box::new(CsvColumnReader::new(
       schema, path, col_idx, opt,
     )?),
@@ -284,33 +386,107 @@ pub fn new_column_reader(
   Ok(res)
 }
 
+// Binary columns are read in batches of this many bytes, so that files
+// larger than memory can be compressed. This is a multiple of every data
+// type's width.
+const BINARY_BATCH_BYTES: usize = 1 << 24;
+
+enum BinarySource {
+  File(File),
+  // Little-endian numbers can be used exactly as they are on disk, so we
+  // avoid copying them onto the heap and just slice the mapped file.
+  #[cfg(feature = "mmap")]
+  Mapped {
+    buffer: Buffer,
+    pos: usize,
+  },
+}
+
 struct BinaryColumnReader {
-  col_path: PathBuf,
+  source: BinarySource,
   dtype: DataType,
+  endian: Endianness,
   did_read: bool,
 }
 
 impl BinaryColumnReader {
-  fn new(schema: &Schema, col_idx: usize) -> Result<Self> {
+  fn new(schema: &Schema, col_idx: usize, endian: Endianness) -> Result<Self> {
     let col_path = PathBuf::from(schema.metadata.get(&col_idx.to_string()).unwrap());
     let dtype = schema.field(col_idx).data_type().clone();
     Ok(BinaryColumnReader {
-      col_path,
+      source: Self::open_source(&col_path, endian)?,
       dtype,
+      endian,
       did_read: false,
     })
   }
+
+  #[cfg(feature = "mmap")]
+  fn open_source(path: &Path, endian: Endianness) -> Result<BinarySource> {
+    if endian == Endianness::Little {
+      return Ok(BinarySource::Mapped {
+        buffer: mmap_buffer(path)?,
+        pos: 0,
+      });
+    }
+    Ok(BinarySource::File(File::open(path)?))
+  }
+
+  #[cfg(not(feature = "mmap"))]
+  fn open_source(path: &Path, _endian: Endianness) -> Result<BinarySource> {
+    Ok(BinarySource::File(File::open(path)?))
+  }
+}
+
+// The returned buffer borrows the mapped file, and it keeps the map alive for
+// as long as any array uses it.
+#[cfg(feature = "mmap")]
+fn mmap_buffer(path: &Path) -> Result<Buffer> {
+  let file = File::open(path)?;
+  // SAFETY: we assume the file isn't modified while we're reading it.
+  let mmap = Arc::new(unsafe { memmap2::Mmap::map(&file)? });
+  let ptr = std::ptr::NonNull::new(mmap.as_ptr() as *mut u8).unwrap();
+  let len = mmap.len();
+  // SAFETY: the pointer and length describe the map, which the buffer owns.
+  Ok(unsafe { Buffer::from_custom_allocation(ptr, len, mmap) })
 }
 
 impl BinaryColumnReader {
-  fn get_array(&self) -> Result<ArrayRef> {
-    let bytes = fs::read(&self.col_path)?;
-    let n_bytes = bytes.len();
-    let buffer = Buffer::from_vec(bytes);
+  // Returns the next batch of bytes, which is empty once the file is
+  // exhausted.
+  fn read_buffer(&mut self, width: usize) -> Result<Buffer> {
+    match &mut self.source {
+      BinarySource::File(file) => {
+        let mut bytes = Vec::new();
+        file
+          .take(BINARY_BATCH_BYTES as u64)
+          .read_to_end(&mut bytes)?;
+        if self.endian == Endianness::Big {
+          // arrow expects little-endian numbers
+          for num_bytes in bytes.chunks_exact_mut(width) {
+            num_bytes.reverse();
+          }
+        }
+        Ok(Buffer::from_vec(bytes))
+      }
+      #[cfg(feature = "mmap")]
+      BinarySource::Mapped { buffer, pos } => {
+        let batch_len = (buffer.len() - *pos).min(BINARY_BATCH_BYTES);
+        let batch = buffer.slice_with_length(*pos, batch_len);
+        *pos += batch_len;
+        Ok(batch)
+      }
+    }
+  }
+
+  fn get_array(&mut self) -> Result<ArrayRef> {
+    let width = self.dtype.primitive_width().unwrap();
+    let buffer = self.read_buffer(width)?;
+    let n_bytes = buffer.len();
 
     let array_data = ArrayData::builder(self.dtype.clone())
       .add_buffer(buffer)
-      .len(n_bytes / self.dtype.primitive_width().unwrap())
+      .len(n_bytes / width)
       .build()?;
     let array = arrow::array::make_array(array_data);
 
@@ -326,8 +502,14 @@ impl Iterator for BinaryColumnReader {
       return None;
     }
 
-    self.did_read = true;
-    Some(self.get_array())
+    let array_result = self.get_array();
+    // We always yield at least one array, even for an empty file, and stop
+    // after the first batch that isn't full.
+    self.did_read = match &array_result {
+      Ok(array) => array.len() * self.dtype.primitive_width().unwrap() < BINARY_BATCH_BYTES,
+      Err(_) => true,
+    };
+    Some(array_result)
   }
 }
 
@@ -355,13 +537,17 @@ impl Iterator for ParquetColumnReader {
     self.0.next().map(|batch_result| {
       let batch = batch_result?;
       // 0 because we told arrow to only read the exact column we want
-      Ok(batch.column(0).clone())
+      let array = batch.column(0);
+      match array.data_type() {
+        DataType::Dictionary(_, value_dtype) => Ok(compute::cast(array, value_dtype)?),
+        _ => Ok(array.clone()),
+      }
     })
   }
 }
 
 struct CsvColumnReader {
-  csv_reader: CsvReader<File>,
+  csv_reader: CsvReader<Box<dyn Read>>,
   col_idx: usize,
 }
 
@@ -370,7 +556,7 @@ impl CsvColumnReader {
     let csv_reader = csv::ReaderBuilder::new(SchemaRef::new(schema.clone()))
       .with_header(opt.csv_has_header)
       .with_delimiter(opt.csv_delimiter as u8)
-      .build(File::open(path)?)?;
+      .build(open_maybe_compressed(path)?)?;
 
     Ok(Self {
       csv_reader,
@@ -408,47 +594,40 @@ impl PcoColumnReader {
   }
 }
 
+// Arrow takes ownership of the decompressed Vec's allocation, so the numbers
+// are never copied.
+fn decompress_to_array<P: ArrowPrimitiveType>(src: &[u8]) -> Result<ArrayRef>
+where
+  P::Native: Number,
+{
+  let nums = simple_decompress::<P::Native>(src)?;
+  Ok(Arc::new(PrimitiveArray::<P>::new(
+    ScalarBuffer::from(nums),
+    None,
+  )))
+}
+
 impl PcoColumnReader {
   fn get_array(&self) -> Result<ArrayRef> {
     use NumberType::*;
 
     let compressed = fs::read(&self.col_path)?;
-    let array: ArrayRef = match self.dtype {
-      F16 => Arc::new(Float16Array::from(simple_decompress::<f16>(
-        &compressed,
-      )?)),
-      F32 => Arc::new(Float32Array::from(simple_decompress::<f32>(
-        &compressed,
-      )?)),
-      F64 => Arc::new(Float64Array::from(simple_decompress::<f64>(
-        &compressed,
-      )?)),
-      I16 => Arc::new(Int16Array::from(simple_decompress::<i16>(
-        &compressed,
-      )?)),
-      I32 => Arc::new(Int32Array::from(simple_decompress::<i32>(
-        &compressed,
-      )?)),
-      I64 => Arc::new(Int64Array::from(simple_decompress::<i64>(
-        &compressed,
-      )?)),
-      U16 => Arc::new(UInt16Array::from(simple_decompress::<u16>(
-        &compressed,
-      )?)),
-      U32 => Arc::new(UInt32Array::from(simple_decompress::<u32>(
-        &compressed,
-      )?)),
-      U64 => Arc::new(UInt64Array::from(simple_decompress::<u64>(
-        &compressed,
-      )?)),
-      other => {
-        return Err(anyhow!(
-          "number type {:?} not yet supported in pco_cli",
-          other
-        ))
-      }
-    };
-    Ok(array)
+    match self.dtype {
+      F16 => decompress_to_array::<Float16Type>(&compressed),
+      F32 => decompress_to_array::<Float32Type>(&compressed),
+      F64 => decompress_to_array::<Float64Type>(&compressed),
+      I16 => decompress_to_array::<Int16Type>(&compressed),
+      I32 => decompress_to_array::<Int32Type>(&compressed),
+      I64 => decompress_to_array::<Int64Type>(&compressed),
+      U8 => decompress_to_array::<UInt8Type>(&compressed),
+      U16 => decompress_to_array::<UInt16Type>(&compressed),
+      U32 => decompress_to_array::<UInt32Type>(&compressed),
+      U64 => decompress_to_array::<UInt64Type>(&compressed),
+      other => Err(anyhow!(
+        "number type {:?} not yet supported in pco_cli",
+        other
+      )),
+    }
   }
 }
 
@@ -0,0 +1,109 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+
+use pco::data_types::Number;
+use pco::match_latent_enum;
+use pco::metadata::{ChunkMeta, DynBins, LatentVarKey};
+use pco::standalone::{FileDecompressor, MaybeChunkDecompressor};
+use pco::FULL_BATCH_N;
+
+fn latent_var_name(key: LatentVarKey) -> &'static str {
+  match key {
+    LatentVarKey::Delta => "delta",
+    LatentVarKey::Primary => "primary",
+    LatentVarKey::Secondary => "secondary",
+  }
+}
+
+fn dump_chunk_meta(res: &mut String, prefix: &str, meta: &ChunkMeta) -> Result<()> {
+  writeln!(res, "{}.mode = {:?}", prefix, meta.mode)?;
+  writeln!(
+    res,
+    "{}.delta_encoding = {:?}",
+    prefix, meta.delta_encoding
+  )?;
+  for (key, latent_var_meta) in meta.per_latent_var.as_ref().enumerated() {
+    let prefix = format!("{}.{}", prefix, latent_var_name(key));
+    writeln!(
+      res,
+      "{}.ans_size_log = {}",
+      prefix, latent_var_meta.ans_size_log
+    )?;
+    match_latent_enum!(
+      &latent_var_meta.bins,
+      DynBins<L>(bins) => {
+        writeln!(res, "{}.n_bins = {}", prefix, bins.len())?;
+        for (bin_idx, bin) in bins.iter().enumerate() {
+          writeln!(
+            res,
+            "{}.bin[{}] = weight={} lower={} offset_bits={}",
+            prefix, bin_idx, bin.weight, bin.lower, bin.offset_bits,
+          )?;
+        }
+      }
+    );
+  }
+  Ok(())
+}
+
+// Produces one `key = value` line per field, in file order, so that dumps of
+// two files can be compared with `diff`.
+// Bin lowers are printed as raw latents rather than numbers, since that's
+// what the file stores.
+pub fn dump<T: Number>(src: &[u8], dtype_name: &str) -> Result<String> {
+  let mut res = String::new();
+  let total_size = src.len();
+  let magic = String::from_utf8_lossy(&src[..src.len().min(4)]).into_owned();
+  let (fd, mut src) = FileDecompressor::new(src)?;
+  writeln!(res, "header.magic = {}", magic)?;
+  writeln!(
+    res,
+    "header.format_version = {}",
+    fd.format_version()
+  )?;
+  writeln!(res, "header.n_hint = {}", fd.n_hint())?;
+  writeln!(
+    res,
+    "header.size = {}",
+    total_size - src.len()
+  )?;
+  writeln!(res, "data_type = {}", dtype_name)?;
+
+  let mut scratch = vec![T::default(); FULL_BATCH_N];
+  let mut chunk_idx = 0;
+  loop {
+    match fd.chunk_decompressor::<T, _>(src)? {
+      MaybeChunkDecompressor::Some(mut cd) => {
+        let prefix = format!("chunk[{}]", chunk_idx);
+        writeln!(res, "{}.n = {}", prefix, cd.n())?;
+        dump_chunk_meta(&mut res, &prefix, cd.meta())?;
+        while !cd.decompress(&mut scratch)?.finished {}
+        writeln!(
+          res,
+          "{}.size = {}",
+          prefix,
+          cd.n_bytes_consumed()
+        )?;
+        src = cd.into_src();
+        chunk_idx += 1;
+      }
+      MaybeChunkDecompressor::EndOfData(rest) => {
+        writeln!(
+          res,
+          "footer.size = {}",
+          src.len() - rest.len()
+        )?;
+        writeln!(
+          res,
+          "unknown_trailing_bytes = {}",
+          rest.len()
+        )?;
+        break;
+      }
+    }
+  }
+  writeln!(res, "n_chunks = {}", chunk_idx)?;
+
+  Ok(res)
+}
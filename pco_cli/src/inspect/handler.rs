@@ -8,8 +8,9 @@ use tabled::{Table, Tabled};
 
 use pco::data_types::{Latent, Number};
 use pco::match_latent_enum;
-use pco::metadata::{ChunkMeta, DynBins, DynLatent, LatentVarKey};
+use pco::metadata::{ChunkMeta, DeltaEncoding, DynBins, DynLatent, LatentVarKey, Mode};
 use pco::standalone::{FileDecompressor, MaybeChunkDecompressor};
+use pco::FULL_BATCH_N;
 
 use crate::core_handlers::CoreHandlerImpl;
 use crate::dtypes::PcoNumber;
@@ -53,6 +54,8 @@ pub struct ChunkSummary {
   n: usize,
   mode: String,
   delta_encoding: String,
+  // a guess from the metadata, since files don't record fallbacks
+  looks_like_fallback: bool,
   // using BTreeMaps to preserve ordering
   latent_vars: BTreeMap<String, LatentVarSummary>,
 }
@@ -65,6 +68,7 @@ pub struct Output {
   pub n: usize,
   pub n_chunks: usize,
   pub uncompressed_size: usize,
+  pub preview: Option<Vec<String>>,
   pub compressed: CompressionSummary,
   pub chunks: Vec<ChunkSummary>,
 }
@@ -130,8 +134,47 @@ fn build_latent_var_summaries<T: Number>(meta: &ChunkMeta) -> BTreeMap<String, L
   summaries
 }
 
+// The file doesn't record whether the compressor fell back to its trivial
+// baseline, but the baseline's metadata is distinctive: a single full-width
+// bin starting at 0 with no delta encoding.
+fn looks_like_fallback(meta: &ChunkMeta) -> bool {
+  if meta.mode != Mode::Classic || meta.delta_encoding != DeltaEncoding::None {
+    return false;
+  }
+
+  let primary = &meta.per_latent_var.primary;
+  primary.ans_size_log == 0
+    && match_latent_enum!(
+      &primary.bins,
+      DynBins<L>(bins) => {
+        bins.len() == 1 && bins[0].lower == L::ZERO && bins[0].offset_bits == L::BITS
+      }
+    )
+}
+
+// Decompresses only as much of the file as needed to get its first n
+// numbers, or all of them if the file is shorter.
+fn preview<T: Number>(src: &[u8], n: usize) -> Result<Vec<String>> {
+  let (fd, mut src) = FileDecompressor::new(src)?;
+  let mut nums = Vec::with_capacity(n);
+  while nums.len() < n {
+    let MaybeChunkDecompressor::Some(mut cd) = fd.chunk_decompressor::<T, _>(src)? else {
+      break;
+    };
+    // the decompressor only accepts partial destinations in whole batches
+    let n_remaining = n - nums.len();
+    let batch_n = (n_remaining.div_ceil(FULL_BATCH_N) * FULL_BATCH_N).min(cd.n());
+    let mut batch = vec![T::default(); batch_n];
+    let n_processed = cd.decompress(&mut batch)?.n_processed;
+    nums.extend_from_slice(&batch[..n_processed.min(n_remaining)]);
+    src = cd.into_src();
+  }
+  Ok(nums.iter().map(|x| x.to_string()).collect())
+}
+
 impl<T: PcoNumber> InspectHandler for CoreHandlerImpl<T> {
   fn inspect(&self, opt: &InspectOpt, src: &[u8]) -> Result<()> {
+    let preview = opt.preview.map(|n| preview::<T>(src, n)).transpose()?;
     let mut prev_src_len_val = src.len();
     let prev_src_len = &mut prev_src_len_val;
     let (fd, mut src) = FileDecompressor::new(src)?;
@@ -183,6 +226,7 @@ impl<T: PcoNumber> InspectHandler for CoreHandlerImpl<T> {
         n: chunk_ns[idx],
         mode: format!("{:?}", meta.mode),
         delta_encoding: format!("{:?}", meta.delta_encoding),
+        looks_like_fallback: looks_like_fallback(meta),
         latent_vars,
       });
     }
@@ -194,6 +238,7 @@ impl<T: PcoNumber> InspectHandler for CoreHandlerImpl<T> {
       n,
       n_chunks: metas.len(),
       uncompressed_size,
+      preview,
       compressed: CompressionSummary {
         ratio: uncompressed_size as f64 / compressed_size as f64,
         total_size: compressed_size,
@@ -4,17 +4,27 @@ use std::path::PathBuf;
 use anyhow::Result;
 use clap::Parser;
 
-use pco::standalone::{FileDecompressor, MaybeChunkDecompressor};
+use pco::data_types::NumberType;
+use pco::match_number_enum;
+use pco::standalone::{peek_dtype, FileDecompressor, MaybeChunkDecompressor};
 
 use crate::core_handlers;
 use crate::inspect::handler::{CompressionSummary, Output};
 use crate::utils;
 
+mod dump;
 pub mod handler;
 
 /// Print metadata about a standalone .pco file.
 #[derive(Clone, Debug, Parser)]
 pub struct InspectOpt {
+  /// If specified, also prints up to this many of the file's first numbers.
+  #[arg(long, visible_alias = "sample")]
+  pub preview: Option<usize>,
+  /// If specified, instead prints a deterministic, line-oriented dump of
+  /// every header, chunk, and bin field, suitable for `diff`ing two files.
+  #[arg(long, conflicts_with = "preview")]
+  pub dump: bool,
   pub path: PathBuf,
 }
 
@@ -35,6 +45,7 @@ fn trivial_inspect(opt: &InspectOpt, src: &[u8]) -> Result<()> {
     n: 0,
     n_chunks: 0,
     uncompressed_size: 0,
+    preview: opt.preview.map(|_| Vec::new()),
     compressed: CompressionSummary {
       ratio: 0.0,
       total_size: start_len - src.len(),
@@ -52,7 +63,21 @@ fn trivial_inspect(opt: &InspectOpt, src: &[u8]) -> Result<()> {
 
 pub fn inspect(opt: InspectOpt) -> Result<()> {
   let bytes = fs::read(&opt.path)?;
-  let Some(dtype) = utils::get_standalone_dtype(&bytes)? else {
+  let maybe_dtype = peek_dtype(&bytes)?;
+  if opt.dump {
+    let dump = match maybe_dtype {
+      Some(dtype) => match_number_enum!(
+        dtype,
+        NumberType<T> => { dump::dump::<T>(&bytes, &utils::dtype_name::<T>())? }
+      ),
+      // the number type doesn't matter when there are no chunks
+      None => dump::dump::<i32>(&bytes, "<none>")?,
+    };
+    print!("{}", dump);
+    return Ok(());
+  }
+
+  let Some(dtype) = maybe_dtype else {
     return trivial_inspect(&opt, &bytes);
   };
   let handler = core_handlers::from_dtype(dtype);
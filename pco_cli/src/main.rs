@@ -15,6 +15,7 @@ mod inspect;
 pub mod num_vec;
 mod opt;
 mod parse;
+mod repair;
 mod utils;
 
 fn main() -> Result<()> {
@@ -24,5 +25,6 @@ fn main() -> Result<()> {
     Opt::Compress(compress_opt) => compress::compress(compress_opt),
     Opt::Decompress(decompress_opt) => decompress::decompress(decompress_opt),
     Opt::Inspect(inspect_opt) => inspect::inspect(inspect_opt),
+    Opt::Repair(repair_opt) => repair::repair(repair_opt),
   }
 }
@@ -1,17 +1,33 @@
 use crate::dtypes::PcoNumber;
-use pco::data_types::{Number, NumberType};
+use pco::data_types::{Latent, Number, NumberType};
 use pco::{define_number_enum, match_number_enum};
 
+/// Asserts that the numbers are bitwise identical.
+///
+/// Unlike `==`, this requires NaNs to match exactly (including sign and
+/// payload) and distinguishes -0.0 from 0.0, so mismatches print the raw bit
+/// patterns alongside the values.
 fn check_equal<T: PcoNumber>(recovered: &[T], original: &[T]) {
-  assert_eq!(recovered.len(), original.len());
-  for (i, (x, y)) in recovered.iter().zip(original.iter()).enumerate() {
-    assert_eq!(
-      x.to_latent_ordered(),
-      y.to_latent_ordered(),
-      "{} != {} at {}",
+  assert_eq!(
+    recovered.len(),
+    original.len(),
+    "recovered {} numbers but expected {}",
+    recovered.len(),
+    original.len(),
+  );
+  let hex_width = 2 + T::L::BITS as usize / 4;
+  for (i, (&x, &y)) in recovered.iter().zip(original.iter()).enumerate() {
+    let x_bits = x.transmute_to_latent();
+    let y_bits = y.transmute_to_latent();
+    assert!(
+      x_bits == y_bits,
+      "{} != {} at {} (bits {:#0w$x} != {:#0w$x})",
       x,
       y,
-      i
+      i,
+      x_bits.to_u64(),
+      y_bits.to_u64(),
+      w = hex_width,
     );
   }
 }
@@ -4,9 +4,10 @@ use crate::bench::BenchOpt;
 use crate::compress::CompressOpt;
 use crate::decompress::DecompressOpt;
 use crate::inspect::InspectOpt;
+use crate::repair::RepairOpt;
 
 #[derive(Clone, Debug, Parser)]
-#[command(about = "compress, decompress, and inspect .pco files")]
+#[command(about = "compress, decompress, inspect, and repair .pco files")]
 pub struct OptWrapper {
   #[command(subcommand)]
   pub opt: Opt,
@@ -18,4 +19,5 @@ pub enum Opt {
   Compress(CompressOpt),
   Decompress(DecompressOpt),
   Inspect(InspectOpt),
+  Repair(RepairOpt),
 }
@@ -3,6 +3,8 @@ use arrow::datatypes::{DataType, TimeUnit};
 
 use pco::{DeltaSpec, ModeSpec};
 
+use crate::input::DtypeOverride;
+
 pub fn delta_spec(s: &str) -> anyhow::Result<DeltaSpec> {
   let spec = match s.to_lowercase().as_str() {
     "auto" => DeltaSpec::Auto,
@@ -33,6 +35,7 @@ pub fn mode_spec(s: &str) -> anyhow::Result<ModeSpec> {
       let value = parts.next().ok_or_else(err)?;
       match name {
         "floatmult" => ModeSpec::TryFloatMult(value.parse()?),
+        "floatmultlossy" => ModeSpec::TryFloatMultLossy(value.parse()?),
         "floatquant" => ModeSpec::TryFloatQuant(value.parse()?),
         "intmult" => ModeSpec::TryIntMult(value.parse()?),
         _ => return Err(err()),
@@ -42,6 +45,32 @@ pub fn mode_spec(s: &str) -> anyhow::Result<ModeSpec> {
   Ok(spec)
 }
 
+// Parses a number of bytes with an optional decimal or binary unit suffix,
+// e.g. 5000, 64MB, or 64MiB.
+pub fn byte_size(s: &str) -> anyhow::Result<u64> {
+  let lower = s.trim().to_lowercase();
+  let split_idx = lower
+    .find(|c: char| !c.is_ascii_digit())
+    .unwrap_or(lower.len());
+  let (digits, unit) = lower.split_at(split_idx);
+  let multiplier: u64 = match unit.trim() {
+    "" | "b" => 1,
+    "kb" => 1_000,
+    "mb" => 1_000_000,
+    "gb" => 1_000_000_000,
+    "kib" => 1 << 10,
+    "mib" => 1 << 20,
+    "gib" => 1 << 30,
+    _ => return Err(anyhow!("invalid byte size: {}", s)),
+  };
+  let count: u64 = digits
+    .parse()
+    .map_err(|_| anyhow!("invalid byte size: {}", s))?;
+  count
+    .checked_mul(multiplier)
+    .ok_or_else(|| anyhow!("byte size too large: {}", s))
+}
+
 pub fn arrow_dtype(s: &str) -> anyhow::Result<DataType> {
   let name_pairs = [
     ("f16", DataType::Float16),
@@ -50,6 +79,7 @@ pub fn arrow_dtype(s: &str) -> anyhow::Result<DataType> {
     ("i16", DataType::Int16),
     ("i32", DataType::Int32),
     ("i64", DataType::Int64),
+    ("u8", DataType::UInt8),
     ("u16", DataType::UInt16),
     ("u32", DataType::UInt32),
     ("u64", DataType::UInt64),
@@ -87,3 +117,22 @@ pub fn arrow_dtype(s: &str) -> anyhow::Result<DataType> {
       .collect::<Vec<_>>()
   ))
 }
+
+pub fn dtype_override(s: &str) -> anyhow::Result<DtypeOverride> {
+  if !s.contains(':') {
+    return Ok(DtypeOverride::Selected(arrow_dtype(s)?));
+  }
+
+  let mut named = Vec::new();
+  for name_and_dtype in s.split(',') {
+    // split on the last colon, since column names may contain colons
+    let (name, dtype) = name_and_dtype.rsplit_once(':').ok_or_else(|| {
+      anyhow!(
+        "invalid dtype override: {}. Expected a comma-separated list of name:dtype",
+        name_and_dtype
+      )
+    })?;
+    named.push((name.to_string(), arrow_dtype(dtype)?));
+  }
+  Ok(DtypeOverride::Named(named))
+}
@@ -0,0 +1,112 @@
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use clap::Parser;
+
+use pco::data_types::{Number, NumberType};
+use pco::errors::PcoResult;
+use pco::match_number_enum;
+use pco::standalone::{
+  FileCompressor, FileDecompressor, MaybeChunkDecompressor, NumberTypeOrTermination,
+};
+
+/// Salvage a standalone .pco file whose write was interrupted.
+///
+/// Reads the input chunk by chunk, stopping at the first chunk that is
+/// corrupt or incomplete, and writes the valid prefix plus a fresh footer.
+#[derive(Clone, Debug, Parser)]
+pub struct RepairOpt {
+  /// Overwrite the output path (if it exists) instead of failing.
+  #[arg(long)]
+  pub overwrite: bool,
+  /// Output .pco path to write the repaired file to.
+  #[arg(short, long)]
+  pub output: PathBuf,
+
+  pub path: PathBuf,
+}
+
+// Returns the number of numbers in the chunk and the rest of the source if
+// the next chunk decompresses successfully.
+fn validate_chunk<'a, T: Number>(
+  fd: &FileDecompressor,
+  src: &'a [u8],
+) -> PcoResult<Option<(usize, &'a [u8])>> {
+  match fd.chunk_decompressor::<T, _>(src)? {
+    MaybeChunkDecompressor::Some(mut cd) => {
+      let n = cd.n();
+      let mut nums = vec![T::default(); n];
+      cd.decompress(&mut nums)?;
+      Ok(Some((n, cd.into_src())))
+    }
+    MaybeChunkDecompressor::EndOfData(_) => Ok(None),
+  }
+}
+
+pub fn repair(opt: RepairOpt) -> Result<()> {
+  let bytes = fs::read(&opt.path)?;
+  let (fd, mut src) = FileDecompressor::new(bytes.as_slice()).map_err(|e| {
+    anyhow!(
+      "unable to salvage file with invalid header: {}",
+      e
+    )
+  })?;
+
+  let mut n_chunks = 0;
+  let mut n = 0;
+  let mut was_terminated = false;
+  loop {
+    let number_type = match fd.peek_number_type_or_termination(src) {
+      Ok(NumberTypeOrTermination::Known(number_type)) => number_type,
+      Ok(NumberTypeOrTermination::Termination) => {
+        was_terminated = true;
+        break;
+      }
+      Ok(NumberTypeOrTermination::Unknown(_)) | Err(_) => break,
+    };
+
+    let validated = match_number_enum!(
+      number_type,
+      NumberType<T> => { validate_chunk::<T>(&fd, src) }
+    );
+    match validated {
+      Ok(Some((chunk_n, rest))) => {
+        n_chunks += 1;
+        n += chunk_n;
+        src = rest;
+      }
+      Ok(None) | Err(_) => break,
+    }
+  }
+
+  let valid_len = bytes.len() - src.len();
+  let mut open_options = OpenOptions::new();
+  open_options.write(true);
+  if opt.overwrite {
+    open_options.create(true);
+    open_options.truncate(true);
+  } else {
+    open_options.create_new(true);
+  }
+  let mut file = open_options.open(&opt.output)?;
+  file.write_all(&bytes[..valid_len])?;
+  FileCompressor::default().write_footer(&mut file)?;
+
+  if was_terminated {
+    println!(
+      "file was already intact; kept all {} chunks with {} numbers",
+      n_chunks, n,
+    );
+  } else {
+    println!(
+      "kept {} chunks with {} numbers; dropped {} trailing bytes",
+      n_chunks,
+      n,
+      src.len(),
+    );
+  }
+  Ok(())
+}
@@ -4,21 +4,10 @@ use anyhow::{anyhow, Result};
 use arrow::array::{ArrayRef, AsArray};
 use arrow::datatypes::Schema;
 
-use pco::data_types::{Number, NumberType};
-use pco::standalone::FileDecompressor;
+use pco::data_types::Number;
 
 use crate::dtypes::ArrowNumber;
-
-pub fn get_standalone_dtype(initial_bytes: &[u8]) -> Result<Option<NumberType>> {
-  let (fd, src) = FileDecompressor::new(initial_bytes)?;
-
-  use pco::standalone::NumberTypeOrTermination::*;
-  match fd.peek_number_type_or_termination(src)? {
-    Termination => Ok(None),
-    Known(number_type) => Ok(Some(number_type)),
-    Unknown(byte) => Err(anyhow!("unknown number type byte: {}", byte)),
-  }
-}
+use crate::input::InputColumnOpt;
 
 pub fn find_col_idx(
   schema: &Schema,
@@ -51,6 +40,33 @@ pub fn find_col_idx(
   Ok(col_idx)
 }
 
+// Like find_col_idx, but also supports selecting multiple columns by regex.
+pub fn find_col_idxs(schema: &Schema, col_opt: &InputColumnOpt) -> Result<Vec<usize>> {
+  let Some(regex) = &col_opt.col_regex else {
+    return Ok(vec![find_col_idx(
+      schema,
+      col_opt.col_idx,
+      &col_opt.col_name,
+    )?]);
+  };
+
+  let col_idxs = schema
+    .fields()
+    .iter()
+    .enumerate()
+    .filter(|(_, field)| regex.is_match(field.name()))
+    .map(|(col_idx, _)| col_idx)
+    .collect::<Vec<_>>();
+  if col_idxs.is_empty() {
+    return Err(anyhow!(
+      "No columns matched {}. Existing columns: {:?}",
+      regex,
+      schema.fields.iter().map(|f| f.name()).collect::<Vec<_>>()
+    ));
+  }
+  Ok(col_idxs)
+}
+
 pub fn dtype_name<T: Number>() -> String {
   any::type_name::<T>().split(':').last().unwrap().to_string()
 }
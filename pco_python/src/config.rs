@@ -27,6 +27,15 @@ impl PyModeSpec {
     Self(ModeSpec::TryFloatMult(base))
   }
 
+  /// :returns: a ModeSpec that tries to use the FloatMultLossy mode with the
+  /// given base, if possible, discarding any precision finer than the base.
+  /// This is lossy: numbers are reconstructed as the nearest multiple of the
+  /// base. Requires format version 4.
+  #[staticmethod]
+  fn try_float_mult_lossy(base: f64) -> Self {
+    Self(ModeSpec::TryFloatMultLossy(base))
+  }
+
   /// :returns: a ModeSpec that tries to use the IntMult mode with the given
   /// base, if possible.
   #[staticmethod]
@@ -0,0 +1,169 @@
+use std::mem;
+
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyList, PyModule};
+use pyo3::{pyfunction, wrap_pyfunction, Bound, PyResult, Python};
+
+use pco::data_types::{Number, NumberType};
+use pco::errors::PcoResult;
+use pco::match_number_enum;
+use pco::standalone::{FileDecompressor, MaybeChunkDecompressor, NumberTypeOrTermination};
+
+use crate::utils::pco_err_to_py;
+
+struct ChunkSummary {
+  n: usize,
+  mode: String,
+  delta_encoding: String,
+  meta_size: usize,
+  page_size: usize,
+}
+
+// Walks through the chunks, returning a summary of each and the bytes after
+// the last chunk.
+fn summarize_chunks<'a, T: Number>(
+  fd: &FileDecompressor,
+  mut src: &'a [u8],
+) -> PcoResult<(Vec<ChunkSummary>, &'a [u8])> {
+  let mut summaries = Vec::new();
+  let mut void = Vec::new();
+  loop {
+    // we read each chunk twice: first to measure its metadata size, then to
+    // measure its page size
+    let src_len = src.len();
+    let cd = match fd.chunk_decompressor::<T, _>(src)? {
+      MaybeChunkDecompressor::Some(cd) => cd,
+      MaybeChunkDecompressor::EndOfData(rest) => return Ok((summaries, rest)),
+    };
+    let n = cd.n();
+    let mode = format!("{:?}", cd.meta().mode);
+    let delta_encoding = format!("{:?}", cd.meta().delta_encoding);
+    let meta_size = src_len - cd.into_src().len();
+
+    let MaybeChunkDecompressor::Some(mut cd) = fd.chunk_decompressor::<T, _>(src)? else {
+      unreachable!("chunk was already read once");
+    };
+    void.resize(n, T::default());
+    cd.decompress(&mut void)?;
+    src = cd.into_src();
+
+    summaries.push(ChunkSummary {
+      n,
+      mode,
+      delta_encoding,
+      meta_size,
+      page_size: src_len - src.len() - meta_size,
+    });
+  }
+}
+
+fn inspect_generic<'py, T: Number>(
+  py: Python<'py>,
+  fd: &FileDecompressor,
+  src: &[u8],
+  total_size: usize,
+  header_size: usize,
+  number_type: Option<NumberType>,
+) -> PyResult<Bound<'py, PyDict>> {
+  let (summaries, rest) = py
+    .allow_threads(|| summarize_chunks::<T>(fd, src))
+    .map_err(pco_err_to_py)?;
+  let n = summaries.iter().map(|summary| summary.n).sum::<usize>();
+  let meta_size = summaries
+    .iter()
+    .map(|summary| summary.meta_size)
+    .sum::<usize>();
+  let page_size = summaries
+    .iter()
+    .map(|summary| summary.page_size)
+    .sum::<usize>();
+  let footer_size = src.len() - rest.len() - meta_size - page_size;
+  let compressed_size = header_size + meta_size + page_size + footer_size;
+  let uncompressed_size = n * mem::size_of::<T>();
+
+  let chunks = PyList::empty_bound(py);
+  for (idx, summary) in summaries.iter().enumerate() {
+    let chunk = PyDict::new_bound(py);
+    chunk.set_item("idx", idx)?;
+    chunk.set_item("n", summary.n)?;
+    chunk.set_item("mode", &summary.mode)?;
+    chunk.set_item("delta_encoding", &summary.delta_encoding)?;
+    chunk.set_item("meta_size", summary.meta_size)?;
+    chunk.set_item("page_size", summary.page_size)?;
+    chunks.append(chunk)?;
+  }
+
+  let compressed = PyDict::new_bound(py);
+  compressed.set_item(
+    "ratio",
+    uncompressed_size as f64 / compressed_size as f64,
+  )?;
+  compressed.set_item("total_size", compressed_size)?;
+  compressed.set_item("header_size", header_size)?;
+  compressed.set_item("meta_size", meta_size)?;
+  compressed.set_item("page_size", page_size)?;
+  compressed.set_item("footer_size", footer_size)?;
+  compressed.set_item(
+    "unknown_trailing_bytes",
+    total_size - compressed_size,
+  )?;
+
+  let res = PyDict::new_bound(py);
+  res.set_item(
+    "data_type",
+    number_type.map(|number_type| format!("{:?}", number_type).to_lowercase()),
+  )?;
+  res.set_item("format_version", fd.format_version())?;
+  res.set_item("n", n)?;
+  res.set_item("n_chunks", summaries.len())?;
+  res.set_item("uncompressed_size", uncompressed_size)?;
+  res.set_item("compressed", compressed)?;
+  res.set_item("chunks", chunks)?;
+  Ok(res)
+}
+
+// The wrapper #[pyfunction] generates converts the returned PyErr into a
+// PyErr.
+#[allow(clippy::useless_conversion)]
+pub fn register(m: &Bound<PyModule>) -> PyResult<()> {
+  /// Describes a standalone pcodec file without decompressing it into an
+  /// array, like the CLI's inspect command.
+  ///
+  /// :param compressed: a bytes object a full standalone file of compressed data.
+  ///
+  /// :returns: a dict with the file's data type (None if the file has no
+  /// chunks), format version, count of numbers, uncompressed size, a
+  /// breakdown of compressed sizes, and a list of chunks, each with its
+  /// count of numbers, mode, delta encoding, metadata size, and page size.
+  ///
+  /// :raises: RuntimeError
+  #[pyfunction]
+  fn inspect<'py>(py: Python<'py>, compressed: &Bound<PyBytes>) -> PyResult<Bound<'py, PyDict>> {
+    use NumberTypeOrTermination::*;
+
+    let src = compressed.as_bytes();
+    let (fd, rest) = FileDecompressor::new(src).map_err(pco_err_to_py)?;
+    let header_size = src.len() - rest.len();
+    let maybe_number_type = fd
+      .peek_number_type_or_termination(rest)
+      .map_err(pco_err_to_py)?;
+    match maybe_number_type {
+      Known(number_type) => match_number_enum!(
+        number_type,
+        NumberType<T> => {
+          inspect_generic::<T>(py, &fd, rest, src.len(), header_size, Some(number_type))
+        }
+      ),
+      // any type will do, since there are no chunks to read
+      Termination => inspect_generic::<u32>(py, &fd, rest, src.len(), header_size, None),
+      Unknown(other) => Err(PyRuntimeError::new_err(format!(
+        "unrecognized dtype byte {:?}",
+        other,
+      ))),
+    }
+  }
+  m.add_function(wrap_pyfunction!(inspect, m)?)?;
+
+  Ok(())
+}
@@ -4,6 +4,7 @@ use pyo3::prelude::*;
 use pyo3::{py_run, Bound};
 
 mod config;
+mod inspect;
 mod progress;
 pub mod standalone;
 mod utils;
@@ -24,6 +25,8 @@ fn pcodec(m: &Bound<PyModule>) -> PyResult<()> {
     pco::DEFAULT_COMPRESSION_LEVEL,
   )?;
 
+  inspect::register(m)?;
+
   // =========== STANDALONE ===========
   let standalone_module = PyModule::new_bound(py, "pcodec.standalone")?;
   standalone::register(&standalone_module)?;
@@ -12,6 +12,7 @@ pub fn core_dtype_from_str(s: &str) -> PyResult<NumberType> {
     "I16" => Ok(NumberType::I16),
     "I32" => Ok(NumberType::I32),
     "I64" => Ok(NumberType::I64),
+    "U8" => Ok(NumberType::U8),
     "U16" => Ok(NumberType::U16),
     "U32" => Ok(NumberType::U32),
     "U64" => Ok(NumberType::U64),
@@ -23,7 +24,9 @@ pub fn core_dtype_from_str(s: &str) -> PyResult<NumberType> {
 }
 
 pub fn number_type_from_numpy(py: Python, dtype: &Bound<PyArrayDescr>) -> PyResult<NumberType> {
-  let res = if dtype.is_equiv_to(&numpy::dtype_bound::<u16>(py)) {
+  let res = if dtype.is_equiv_to(&numpy::dtype_bound::<u8>(py)) {
+    NumberType::U8
+  } else if dtype.is_equiv_to(&numpy::dtype_bound::<u16>(py)) {
     NumberType::U16
   } else if dtype.is_equiv_to(&numpy::dtype_bound::<u32>(py)) {
     NumberType::U32
@@ -94,7 +94,7 @@ impl PyCc {
   ///
   /// :raises: TypeError, RuntimeError
   fn write_chunk_meta<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
-    let mut res = Vec::new();
+    let mut res = Vec::with_capacity(self.0.chunk_meta_size_hint());
     self.0.write_chunk_meta(&mut res).map_err(pco_err_to_py)?;
     Ok(PyBytes::new_bound(py, &res))
   }
@@ -110,7 +110,13 @@ impl PyCc {
   ///
   /// :raises: TypeError, RuntimeError
   fn write_page<'py>(&self, py: Python<'py>, page_idx: usize) -> PyResult<Bound<'py, PyBytes>> {
-    let mut res = Vec::new();
+    // an invalid page idx gets reported by write_page
+    let capacity = if page_idx < self.0.n_per_page().len() {
+      self.0.page_size_hint(page_idx)
+    } else {
+      0
+    };
+    let mut res = Vec::with_capacity(capacity);
     py.allow_threads(|| self.0.write_page(page_idx, &mut res))
       .map_err(pco_err_to_py)?;
     Ok(PyBytes::new_bound(py, &res))
@@ -5,6 +5,7 @@ from pcodec import (
     DeltaSpec,
     ModeSpec,
     PagingSpec,
+    inspect,
     standalone,
 )
 
@@ -13,7 +14,7 @@ all_lengths = (
     0,
     900,
 )
-all_dtypes = ("f2", "f4", "f8", "i2", "i4", "i8", "u2", "u4", "u8")
+all_dtypes = ("f2", "f4", "f8", "i2", "i4", "i8", "u1", "u2", "u4", "u8")
 
 
 @pytest.mark.parametrize("length", all_lengths)
@@ -150,3 +151,43 @@ def test_compression_float_mode_spec_options(mode_spec):
 
     # check that the decompressed data is correct
     np.testing.assert_array_equal(data, out)
+
+
+def test_compression_lossy_float_mult():
+    data = np.arange(100) * 0.1 + 1e-9
+    compressed = standalone.simple_compress(
+        data,
+        ChunkConfig(mode_spec=ModeSpec.try_float_mult_lossy(0.1)),
+    )
+
+    out = standalone.simple_decompress(compressed)
+
+    # the sub-base precision is lost
+    np.testing.assert_array_equal(np.round(data * 10) * 0.1, out)
+
+
+def test_inspect():
+    data = np.arange(1000, dtype="i8") * 7
+    compressed = standalone.simple_compress(
+        data, ChunkConfig(paging_spec=PagingSpec.equal_pages_up_to(300))
+    )
+    info = inspect(compressed)
+    assert info["data_type"] == "i64"
+    assert info["n"] == 1000
+    assert info["n_chunks"] == 4
+    assert info["uncompressed_size"] == 8000
+    assert [chunk["n"] for chunk in info["chunks"]] == [250] * 4
+    assert all(isinstance(chunk["mode"], str) for chunk in info["chunks"])
+
+    sizes = info["compressed"]
+    assert sizes["total_size"] == len(compressed)
+    assert sizes["meta_size"] == sum(chunk["meta_size"] for chunk in info["chunks"])
+    assert sizes["page_size"] == sum(chunk["page_size"] for chunk in info["chunks"])
+    component_keys = ("header_size", "meta_size", "page_size", "footer_size")
+    assert sum(sizes[key] for key in component_keys) == len(compressed)
+
+    empty_data = np.array([], dtype="f4")
+    empty = inspect(standalone.simple_compress(empty_data, ChunkConfig()))
+    assert empty["data_type"] is None
+    assert empty["n"] == 0
+    assert empty["chunks"] == []
@@ -0,0 +1,23 @@
+use wasm_bindgen::prelude::*;
+
+use pco::errors::PcoError;
+use pco::standalone::{simple_decompress, simpler_compress};
+use pco::DEFAULT_COMPRESSION_LEVEL;
+
+fn pco_err_to_js(err: PcoError) -> JsError {
+  JsError::new(&err.to_string())
+}
+
+/// Compresses a `Float64Array` into the bytes of a standalone .pco file,
+/// returned as a `Uint8Array`.
+#[wasm_bindgen]
+pub fn compress(nums: &[f64]) -> Result<Vec<u8>, JsError> {
+  simpler_compress(nums, DEFAULT_COMPRESSION_LEVEL).map_err(pco_err_to_js)
+}
+
+/// Decompresses the bytes of a standalone .pco file of `f64`s into a
+/// `Float64Array`.
+#[wasm_bindgen]
+pub fn decompress(compressed: &[u8]) -> Result<Vec<f64>, JsError> {
+  simple_decompress(compressed).map_err(pco_err_to_js)
+}